@@ -0,0 +1,38 @@
+//! Hashes the crate's sources and locked dependencies into `SPEC_EXTRACT_SOURCE_HASH`,
+//! so built-in plugins report a new version (and miss the spec cache) whenever the
+//! code that produces their output changes, not only on releases.
+
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::Hasher;
+use std::path::{Path, PathBuf};
+
+const INPUTS: &[&str] = &["src", "queries", "Cargo.lock"];
+
+fn main() {
+    let mut files = Vec::new();
+    for input in INPUTS {
+        println!("cargo:rerun-if-changed={}", input);
+        collect(Path::new(input), &mut files);
+    }
+    files.sort();
+
+    let mut hasher = DefaultHasher::new();
+    for file in &files {
+        let content = fs::read(file).unwrap_or_default();
+        hasher.write(file.to_string_lossy().as_bytes());
+        hasher.write_u64(content.len() as u64);
+        hasher.write(&content);
+    }
+    println!("cargo:rustc-env=SPEC_EXTRACT_SOURCE_HASH={:016x}", hasher.finish());
+}
+
+fn collect(path: &Path, files: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        files.push(path.to_path_buf());
+    } else if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.flatten() {
+            collect(&entry.path(), files);
+        }
+    }
+}
@@ -0,0 +1,55 @@
+; Package and imports
+(package_clause (package_identifier) @package)
+(import_spec path: (_) @import)
+
+; Types
+((type_declaration
+  (type_spec
+    name: (type_identifier) @type.name
+    type: (struct_type
+      (field_declaration_list (field_declaration)* @type.field)))) @type
+ (#set! kind "struct"))
+
+((type_declaration
+  (type_spec
+    name: (type_identifier) @type.name
+    type: (interface_type (method_elem)* @type.method))) @type
+ (#set! kind "interface"))
+
+((type_declaration
+  (type_spec
+    name: (type_identifier) @type.name
+    type: [(type_identifier) (qualified_type) (pointer_type) (slice_type) (map_type)
+           (function_type) (generic_type)] @type.embed)) @type
+ (#set! kind "type_alias"))
+
+; Functions and methods, with their signature up to the body
+(function_declaration body: (block) @function.body) @function
+
+(method_declaration
+  receiver: (parameter_list (parameter_declaration type: (_) @method.receiver))
+  body: (block) @method.body) @method
+
+; Constants and package-level variables; `var ErrX = errors.New("...")` is an error
+(const_spec
+  name: (identifier) @constant.name
+  type: (_)? @constant.type
+  value: (expression_list)? @constant.value) @constant
+
+((source_file
+  (var_declaration
+    (var_spec
+      name: (identifier) @error.name
+      value: (expression_list
+        (call_expression
+          function: (selector_expression) @_constructor
+          arguments: (argument_list . (interpreted_string_literal) @error.message)))) @error))
+ (#match? @error.name "^Err")
+ (#any-of? @_constructor "errors.New" "fmt.Errorf"))
+
+((source_file
+  (var_declaration
+    (var_spec
+      name: (identifier) @variable.name
+      type: (_)? @variable.type) @variable))
+ (#not-match? @variable.name "^Err"))
@@ -0,0 +1,237 @@
+use anyhow::{Context, Result};
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use crate::plugins::PluginRegistry;
+use crate::spec::FileSpec;
+
+/// Where cached specs live, relative to the working directory.
+pub const DEFAULT_CACHE_DIR: &str = ".opensdd/cache/spec-extract";
+
+/// Hex SHA-256 of `parts`, each length-prefixed so their boundaries count.
+pub fn content_hash(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Persistent cache of extracted specs, so unchanged files are not parsed again.
+///
+/// Entries are keyed by the file's path and content together with its
+/// plugin's name and version and the extraction options (see
+/// `PluginRegistry::fingerprint`), so any of them changing misses the cache.
+/// Specs are cached as extracted, before the cross-file `link` pass. Entries
+/// are never updated in place; after a full run `prune` drops those the run
+/// did not use, and `clear` drops them all.
+pub struct SpecCache {
+    dir: PathBuf,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    /// Keys looked up or stored by this run
+    used: Mutex<HashSet<String>>,
+}
+
+impl SpecCache {
+    /// Use `dir` as the cache, creating it if needed.
+    pub fn open(dir: &Path) -> Result<Self> {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create cache directory: {}", dir.display()))?;
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+            used: Mutex::new(HashSet::new()),
+        })
+    }
+
+    /// Remove every cached spec in `dir`.
+    pub fn clear(dir: &Path) -> Result<()> {
+        if dir.exists() {
+            fs::remove_dir_all(dir)
+                .with_context(|| format!("Failed to clear cache: {}", dir.display()))?;
+        }
+        Ok(())
+    }
+
+    /// The spec of `source` at `path`, from the cache or else extracted and
+    /// stored. Files that cannot be extracted are not cached.
+    pub fn get_or_extract(
+        &self,
+        source: &str,
+        path: &Path,
+        registry: &PluginRegistry,
+    ) -> Result<Option<FileSpec>> {
+        let Some(fingerprint) = registry.fingerprint(path) else {
+            return Ok(None);
+        };
+        let key = content_hash(&[
+            path.to_string_lossy().as_bytes(),
+            fingerprint.as_bytes(),
+            source.as_bytes(),
+        ]);
+        self.used.lock().unwrap().insert(key.clone());
+
+        if let Some(spec) = self.get(&key) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Some(spec));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let spec = crate::output::extract_spec(source, path, registry)?;
+        if let Some(spec) = &spec {
+            // An entry that cannot be written is merely a miss next time
+            let _ = self.put(&key, spec);
+        }
+        Ok(spec)
+    }
+
+    /// Remove the entries this run has not used, returning how many there were.
+    ///
+    /// Only call this after extracting the whole tree: the specs of files a
+    /// partial run skipped would be dropped too.
+    pub fn prune(&self) -> Result<usize> {
+        let used = self.used.lock().unwrap();
+        let mut removed = 0;
+        for shard in fs::read_dir(&self.dir)
+            .with_context(|| format!("Failed to read cache: {}", self.dir.display()))?
+        {
+            let shard = shard?.path();
+            let Some(prefix) = shard.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Ok(entries) = fs::read_dir(&shard) else {
+                continue;
+            };
+            for entry in entries {
+                let path = entry?.path();
+                // Temporary files belong to runs still writing them
+                if path.extension().is_none_or(|e| e != "json") {
+                    continue;
+                }
+                let rest = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+                if !used.contains(&format!("{}{}", prefix, rest)) {
+                    fs::remove_file(&path).with_context(|| {
+                        format!("Failed to prune cache entry: {}", path.display())
+                    })?;
+                    removed += 1;
+                }
+            }
+            // Fails while the shard still holds entries
+            let _ = fs::remove_dir(&shard);
+        }
+        Ok(removed)
+    }
+
+    /// How many lookups were served from the cache, and how many were not.
+    pub fn stats(&self) -> (usize, usize) {
+        (self.hits.load(Ordering::Relaxed), self.misses.load(Ordering::Relaxed))
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(format!("{}.json", &key[2..]))
+    }
+
+    /// A damaged or unreadable entry is a miss.
+    fn get(&self, key: &str) -> Option<FileSpec> {
+        let content = fs::read(self.entry_path(key)).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    fn put(&self, key: &str, spec: &FileSpec) -> Result<()> {
+        let path = self.entry_path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
+        }
+        // Written aside and renamed, so a concurrent run never reads half an entry
+        let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+        fs::write(&temp, serde_json::to_vec(spec)?)
+            .with_context(|| format!("Failed to write cache entry: {}", temp.display()))?;
+        fs::rename(&temp, &path)
+            .with_context(|| format!("Failed to write cache entry: {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::BUILD_VERSION;
+    use crate::plugins::Visibility;
+
+    #[test]
+    fn test_cache_hits_and_invalidation() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SpecCache::open(dir.path()).unwrap();
+        let mut registry = PluginRegistry::new();
+        let path = Path::new("store.go");
+        let source = "package store\n\nfunc Get() {}\nfunc put() {}\n";
+
+        let spec = cache.get_or_extract(source, path, &registry).unwrap().unwrap();
+        let cached = cache.get_or_extract(source, path, &registry).unwrap().unwrap();
+        assert_eq!(serde_json::to_value(&spec).unwrap(), serde_json::to_value(&cached).unwrap());
+        assert_eq!(cache.stats(), (1, 1));
+        // Built-in plugins change version with the build, not only on releases
+        let fingerprint = registry.fingerprint(path).unwrap();
+        assert!(fingerprint.starts_with(&format!("go@{};", BUILD_VERSION)), "{}", fingerprint);
+
+        // New content, another path or other options all miss
+        let changed = "package store\n\nfunc Get() {}\n";
+        let spec = cache.get_or_extract(changed, path, &registry).unwrap().unwrap();
+        assert_eq!(spec.functions.len(), 1);
+        cache.get_or_extract(source, Path::new("kv.go"), &registry).unwrap();
+        registry.set_visibility(Visibility::Public);
+        let public = cache.get_or_extract(source, path, &registry).unwrap().unwrap();
+        assert_eq!(public.functions.len(), 1);
+        assert_eq!(cache.stats(), (1, 4));
+
+        SpecCache::clear(dir.path()).unwrap();
+        let cache = SpecCache::open(dir.path()).unwrap();
+        cache.get_or_extract(source, path, &registry).unwrap();
+        assert_eq!(cache.stats(), (0, 1));
+    }
+
+    #[test]
+    fn test_prune_drops_unused_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = PluginRegistry::new();
+        let cache = SpecCache::open(dir.path()).unwrap();
+        cache.get_or_extract("def old(): pass\n", Path::new("app.py"), &registry).unwrap();
+        cache.get_or_extract("def keep(): pass\n", Path::new("lib.py"), &registry).unwrap();
+
+        // The next run sees app.py changed and lib.py as it was
+        let cache = SpecCache::open(dir.path()).unwrap();
+        cache.get_or_extract("def new(): pass\n", Path::new("app.py"), &registry).unwrap();
+        cache.get_or_extract("def keep(): pass\n", Path::new("lib.py"), &registry).unwrap();
+        assert_eq!(cache.prune().unwrap(), 1);
+        assert_eq!(cache.prune().unwrap(), 0);
+
+        let cache = SpecCache::open(dir.path()).unwrap();
+        cache.get_or_extract("def new(): pass\n", Path::new("app.py"), &registry).unwrap();
+        cache.get_or_extract("def keep(): pass\n", Path::new("lib.py"), &registry).unwrap();
+        assert_eq!(cache.stats(), (2, 0));
+    }
+
+    #[test]
+    fn test_damaged_entry_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = SpecCache::open(dir.path()).unwrap();
+        let registry = PluginRegistry::new();
+        let source = "def load(): pass\n";
+        cache.get_or_extract(source, Path::new("app.py"), &registry).unwrap();
+
+        for shard in fs::read_dir(dir.path()).unwrap() {
+            for entry in fs::read_dir(shard.unwrap().path()).unwrap() {
+                fs::write(entry.unwrap().path(), "{\"file\": ").unwrap();
+            }
+        }
+        let spec = cache.get_or_extract(source, Path::new("app.py"), &registry).unwrap().unwrap();
+        assert_eq!(spec.functions[0].signature, "def load()");
+        assert_eq!(cache.stats(), (0, 2));
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::{bail, Context, Result};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// How a file differs from the ref.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileChange {
+    Added(PathBuf),
+    Modified(PathBuf),
+    Deleted(PathBuf),
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+/// Files changed in a working tree since a git ref, committed or not.
+/// Untracked files that are not ignored count as added. Paths are joined to
+/// the root, like those `SourceWalker` lists.
+#[derive(Debug, Clone)]
+pub struct GitChanges {
+    pub since: String,
+    pub changes: Vec<FileChange>,
+}
+
+impl GitChanges {
+    /// Ask git what changed under `root` since `since`.
+    pub fn since(root: &Path, since: &str) -> Result<Self> {
+        let commit = format!("{}^{{commit}}", since);
+        if git(root, &["rev-parse", "--verify", "--quiet", &commit]).is_err() {
+            bail!("Unknown git ref: {}", since);
+        }
+        let diff = git(root, &["diff", "--name-status", "-z", "-M", "--relative", since, "--"])?;
+        let untracked = git(root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+
+        let mut changes = parse_name_status(&diff)?;
+        changes.extend(untracked.split('\0').filter(|p| !p.is_empty()).map(|p| {
+            FileChange::Added(PathBuf::from(p))
+        }));
+        for change in &mut changes {
+            match change {
+                FileChange::Added(path)
+                | FileChange::Modified(path)
+                | FileChange::Deleted(path) => *path = root.join(&*path),
+                FileChange::Renamed { from, to } => {
+                    *from = root.join(&*from);
+                    *to = root.join(&*to);
+                }
+            }
+        }
+        Ok(Self { since: since.to_string(), changes })
+    }
+
+    /// Files whose current content is to be extracted.
+    pub fn present(&self) -> HashSet<&Path> {
+        self.changes
+            .iter()
+            .filter_map(|change| match change {
+                FileChange::Added(path) | FileChange::Modified(path) => Some(path.as_path()),
+                FileChange::Renamed { to, .. } => Some(to.as_path()),
+                FileChange::Deleted(_) => None,
+            })
+            .collect()
+    }
+}
+
+fn git(root: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("git {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim());
+    }
+    String::from_utf8(output.stdout).context("git printed a non UTF-8 path")
+}
+
+/// Parse `git diff --name-status -z`: a status, then one path, or two for
+/// renames and copies (`R087`, `C100`).
+fn parse_name_status(output: &str) -> Result<Vec<FileChange>> {
+    let mut fields = output.split('\0').filter(|f| !f.is_empty());
+    let mut changes = Vec::new();
+    while let Some(status) = fields.next() {
+        let mut path = || {
+            fields
+                .next()
+                .map(PathBuf::from)
+                .with_context(|| format!("Truncated git diff output after {}", status))
+        };
+        let change = match status.chars().next() {
+            Some('A') => FileChange::Added(path()?),
+            Some('M') | Some('T') => FileChange::Modified(path()?),
+            Some('D') => FileChange::Deleted(path()?),
+            Some('R') => {
+                let from = path()?;
+                FileChange::Renamed { from, to: path()? }
+            }
+            Some('C') => {
+                // The copy's source is unchanged
+                path()?;
+                FileChange::Added(path()?)
+            }
+            _ => bail!("Unexpected git diff status: {}", status),
+        };
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_parse_name_status() {
+        let output = "M\0api/users.go\0R087\0old.go\0new.go\0D\0gone.go\0C100\0a.go\0b.go\0";
+        assert_eq!(
+            parse_name_status(output).unwrap(),
+            vec![
+                FileChange::Modified(PathBuf::from("api/users.go")),
+                FileChange::Renamed { from: PathBuf::from("old.go"), to: PathBuf::from("new.go") },
+                FileChange::Deleted(PathBuf::from("gone.go")),
+                FileChange::Added(PathBuf::from("b.go")),
+            ]
+        );
+        assert!(parse_name_status("R100\0old.go\0").is_err());
+    }
+
+    #[test]
+    fn test_changes_since_ref() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        let run = |args: &[&str]| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(root)
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap()
+                .status;
+            assert!(status.success(), "git {:?}", args);
+        };
+        run(&["init", "-q"]);
+        fs::write(root.join("keep.go"), "package a\n").unwrap();
+        fs::write(root.join("edit.go"), "package a\n").unwrap();
+        fs::write(root.join("drop.go"), "package a\n").unwrap();
+        let moved = "package a\n\n// Move is a function with enough lines to be tracked.\n\
+                     func Move() {}\n";
+        fs::write(root.join("old.go"), moved).unwrap();
+        run(&["add", "."]);
+        run(&["commit", "-q", "-m", "base"]);
+
+        fs::write(root.join("edit.go"), "package a\n\nfunc Edit() {}\n").unwrap();
+        fs::remove_file(root.join("drop.go")).unwrap();
+        run(&["mv", "old.go", "new.go"]);
+        fs::write(root.join("fresh.go"), "package a\n").unwrap();
+
+        let changes = GitChanges::since(root, "HEAD").unwrap();
+        let mut present: Vec<&Path> = changes.present().into_iter().collect();
+        present.sort();
+        assert_eq!(present, vec![root.join("edit.go"), root.join("fresh.go"), root.join("new.go")]);
+        assert!(changes.changes.contains(&FileChange::Deleted(root.join("drop.go"))));
+        assert!(changes
+            .changes
+            .contains(&FileChange::Renamed { from: root.join("old.go"), to: root.join("new.go") }));
+
+        let err = GitChanges::since(root, "no-such-ref").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown git ref: no-such-ref");
+    }
+}
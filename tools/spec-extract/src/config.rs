@@ -0,0 +1,246 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::plugins::{PluginRegistry, Visibility};
+use crate::spec::OutputFormat;
+use crate::walk::{WalkOptions, DEFAULT_EXCLUDES};
+
+/// Where the project configuration lives, relative to the working directory.
+pub const DEFAULT_CONFIG_PATH: &str = ".opensdd/spec-extract.toml";
+
+/// The project spec, whose `spec-extract:` section may hold the configuration.
+pub const SPEC_PATH: &str = ".opensdd/spec.yaml";
+
+/// Section of `spec.yaml` holding the configuration.
+const SPEC_SECTION: &str = "spec-extract";
+
+/// Project configuration, e.g. `.opensdd/spec-extract.toml`:
+///
+/// ```toml
+/// roots = ["services/api", "libs"]
+/// languages = ["go", "typescript"]
+/// exclude = ["testdata", "*.pb.go"]
+/// default_excludes = ["node_modules"]
+/// skip_generated = true
+/// visibility = "public"
+/// format = "json"
+/// passes = ["link"]
+///
+/// [extensions]
+/// mjs = "typescript"
+/// ```
+///
+/// Command-line flags override the corresponding settings; `--include` and
+/// `--exclude` add to the configured globs.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Directories (or a single file) to extract, relative to the working directory
+    pub roots: Vec<PathBuf>,
+    /// Languages to extract; empty means all
+    pub languages: Vec<String>,
+    /// Globs a file must match to be extracted; empty means all
+    pub include: Vec<String>,
+    /// Globs of files and directories to leave out
+    pub exclude: Vec<String>,
+    /// Directories left out wherever they appear, ignore files or not; defaults to
+    /// `node_modules`, `vendor` and `__pycache__`, and `[]` keeps them all
+    pub default_excludes: Vec<String>,
+    /// Leave out files carrying a generated-code marker
+    pub skip_generated: bool,
+    /// Which declarations end up in the spec
+    pub visibility: Visibility,
+    /// Extension (without the dot) to the language whose plugin handles it
+    pub extensions: BTreeMap<String, String>,
+    /// Output format
+    pub format: Option<OutputFormat>,
+    /// Semantic passes to run
+    pub passes: Vec<Pass>,
+    /// File the configuration was read from, for error messages
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+}
+
+/// A semantic pass over extracted specs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Pass {
+    /// Per-file analysis by the file's plugin (`LanguagePlugin::analyze_semantics`)
+    Semantics,
+    /// Cross-file reconciliation once the tree is extracted (`LanguagePlugin::link`)
+    Link,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            roots: Vec::new(),
+            languages: Vec::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            default_excludes: DEFAULT_EXCLUDES.iter().map(|dir| dir.to_string()).collect(),
+            skip_generated: false,
+            visibility: Visibility::All,
+            extensions: BTreeMap::new(),
+            format: None,
+            passes: vec![Pass::Semantics, Pass::Link],
+            source: None,
+        }
+    }
+}
+
+impl Config {
+    /// Load the configuration at `path`: a TOML file, or the `spec-extract:`
+    /// section of a YAML spec.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config: {}", path.display()))?;
+        let is_yaml = matches!(path.extension().and_then(|e| e.to_str()), Some("yaml" | "yml"));
+        let config = if is_yaml {
+            match Self::from_spec(&content)
+                .with_context(|| format!("Invalid config: {}", path.display()))?
+            {
+                Some(config) => config,
+                None => bail!("{} has no `{}` section", path.display(), SPEC_SECTION),
+            }
+        } else {
+            toml::from_str(&content)
+                .with_context(|| format!("Invalid config: {}", path.display()))?
+        };
+        Ok(Self { source: Some(path.to_path_buf()), ..config })
+    }
+
+    /// Load `path`; otherwise `DEFAULT_CONFIG_PATH` or the `spec-extract:`
+    /// section of `SPEC_PATH`, whichever exists; otherwise the defaults.
+    pub fn discover(path: Option<&Path>) -> Result<Self> {
+        if let Some(path) = path {
+            return Self::load(path);
+        }
+        let default = Path::new(DEFAULT_CONFIG_PATH);
+        if default.is_file() {
+            return Self::load(default);
+        }
+        let spec = Path::new(SPEC_PATH);
+        if spec.is_file() {
+            let content = fs::read_to_string(spec)
+                .with_context(|| format!("Failed to read spec: {}", spec.display()))?;
+            let config = Self::from_spec(&content)
+                .with_context(|| format!("Invalid config: {}", spec.display()))?;
+            if let Some(config) = config {
+                return Ok(Self { source: Some(spec.to_path_buf()), ..config });
+            }
+        }
+        Ok(Self::default())
+    }
+
+    /// The `spec-extract:` section of a YAML spec, if it has one.
+    fn from_spec(content: &str) -> Result<Option<Self>> {
+        let spec: serde_yaml::Value = serde_yaml::from_str(content)?;
+        match spec.get(SPEC_SECTION) {
+            Some(section) => Ok(Some(serde_yaml::from_value(section.clone())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Check the settings that depend on the available plugins, then apply the
+    /// extension overrides, visibility policy and semantic pass to `registry`.
+    /// Every problem found is reported in one error.
+    pub fn apply(&self, registry: &mut PluginRegistry) -> Result<()> {
+        let mut problems = Vec::new();
+        let known = registry.languages();
+        for language in &self.languages {
+            if !known.contains(language) {
+                problems.push(format!(
+                    "unknown language `{}` in `languages` (expected one of: {})",
+                    language,
+                    known.join(", ")
+                ));
+            }
+        }
+        for (ext, language) in &self.extensions {
+            if let Err(e) = registry.map_extension(ext, language) {
+                problems.push(format!("`extensions.{}`: {}", ext, e));
+            }
+        }
+        for root in &self.roots {
+            if !root.exists() {
+                problems.push(format!("root `{}` does not exist", root.display()));
+            }
+        }
+        if !problems.is_empty() {
+            let source = self.source.as_ref().map(|p| format!(" {}", p.display()));
+            bail!("Invalid config{}: {}", source.unwrap_or_default(), problems.join("; "));
+        }
+
+        registry.set_visibility(self.visibility);
+        registry.set_semantics(self.passes.contains(&Pass::Semantics));
+        Ok(())
+    }
+
+    pub fn walk_options(&self) -> WalkOptions {
+        WalkOptions {
+            include: self.include.clone(),
+            exclude: self.exclude.clone(),
+            default_excludes: self.default_excludes.clone(),
+            skip_generated: self.skip_generated,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spec-extract.toml");
+        let content = "exclude = [\"testdata\"]\nskip_generated = true\nformat = \"json\"\n\
+                       passes = [\"link\"]\ndefault_excludes = [\"vendor\"]\n\n\
+                       [extensions]\nmjs = \"typescript\"\n";
+        fs::write(&path, content).unwrap();
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.exclude, vec!["testdata"]);
+        assert!(config.include.is_empty());
+        assert!(config.walk_options().skip_generated);
+        assert_eq!(config.walk_options().default_excludes, vec!["vendor"]);
+        assert_eq!(Config::default().default_excludes, DEFAULT_EXCLUDES);
+        assert_eq!(config.format, Some(OutputFormat::Json));
+        assert_eq!(config.passes, vec![Pass::Link]);
+
+        let mut registry = PluginRegistry::new();
+        config.apply(&mut registry).unwrap();
+        assert_eq!(registry.get_for_file(Path::new("index.mjs")).unwrap().name(), "typescript");
+
+        let spec = dir.path().join("spec.yaml");
+        fs::write(&spec, "name: shop\nspec-extract:\n  languages: [go]\n  visibility: public\n")
+            .unwrap();
+        let config = Config::load(&spec).unwrap();
+        assert_eq!(config.languages, vec!["go"]);
+        assert_eq!(config.visibility, Visibility::Public);
+        assert_eq!(config.passes, vec![Pass::Semantics, Pass::Link]);
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("spec-extract.toml");
+        fs::write(&path, "excludes = [\"testdata\"]\n").unwrap();
+        let err = Config::load(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("unknown field `excludes`"), "{:#}", err);
+
+        fs::write(&path, "visibility = \"private\"\n").unwrap();
+        let err = Config::load(&path).unwrap_err();
+        assert!(format!("{:#}", err).contains("unknown variant `private`"), "{:#}", err);
+
+        let mut registry = PluginRegistry::new();
+        fs::write(&path, "languages = [\"golang\"]\n[extensions]\nmjs = \"ecmascript\"\n").unwrap();
+        let err = Config::load(&path).unwrap().apply(&mut registry).unwrap_err().to_string();
+        assert!(err.starts_with("Invalid config "), "{}", err);
+        assert!(err.contains("unknown language `golang` in `languages`"), "{}", err);
+        assert!(err.ends_with("; `extensions.mjs`: Unknown language: ecmascript"), "{}", err);
+    }
+}
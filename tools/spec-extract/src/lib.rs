@@ -1,9 +1,19 @@
+pub mod cache;
+pub mod changes;
+pub mod config;
 pub mod output;
 pub mod parser;
 pub mod plugins;
 pub mod spec;
+pub mod walk;
+pub mod watch;
 
-pub use output::{extract_spec, generate_file_spec, IndexBuilder};
+pub use cache::SpecCache;
+pub use changes::{FileChange, GitChanges};
+pub use config::{Config, Pass};
+pub use output::{extract_spec, generate_file_spec, write_spec_file, IndexBuilder};
 pub use parser::{ExtractOptions, LanguagePlugin};
-pub use plugins::PluginRegistry;
-pub use spec::{write_extracted_spec, ExtractedSpec, FileSpec, OutputFormat};
+pub use plugins::{PluginRegistry, Visibility};
+pub use spec::{write_extracted_spec, ExtractedSpec, FileSpec, OutputFormat, RenameSpec};
+pub use walk::{SourceWalker, WalkOptions};
+pub use watch::{FileDelta, WatchSession};
@@ -1,13 +1,18 @@
 use anyhow::{Context, Result};
-use clap::Parser;
-use std::collections::HashSet;
+use clap::{Parser, Subcommand};
+use notify::event::ModifyKind;
+use notify::{EventKind, RecursiveMode, Watcher};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::mpsc;
+use std::time::Duration;
 
 use spec_extract::{
-    generate_file_spec, write_extracted_spec, ExtractedSpec, IndexBuilder, OutputFormat,
-    PluginRegistry,
+    write_extracted_spec, write_spec_file, Config, ExtractedSpec, FileChange, GitChanges,
+    IndexBuilder, OutputFormat, Pass, PluginRegistry, RenameSpec, SourceWalker, SpecCache,
+    Visibility, WatchSession,
 };
 
 #[derive(Parser)]
@@ -15,24 +20,26 @@ use spec_extract::{
 #[command(about = "Extract code specifications from multiple languages into YAML/JSON format")]
 #[command(version)]
 struct Cli {
-    /// Path to file or directory to extract specs from
-    #[arg(default_value = ".")]
-    path: PathBuf,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Path to file or directory to extract specs from (defaults to the configured roots, or .)
+    path: Option<PathBuf>,
 
     /// Output path (file path by default, directory when --multi-file is set)
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     output: Option<PathBuf>,
 
-    /// Output format (yaml or json)
-    #[arg(short, long, default_value = "yaml")]
-    format: String,
+    /// Output format (yaml or json, defaults to the configured format, or yaml)
+    #[arg(short, long, global = true)]
+    format: Option<String>,
 
-    /// Filter by language (comma-separated: go,rust,python,typescript)
-    #[arg(short, long)]
+    /// Filter by language (comma-separated: go,rust,python,typescript,javascript,java,csharp,kotlin,c,cpp,swift,ruby,php,elixir,scala,dart,proto,openapi,graphql,sql,vue,svelte,markdown,notebook)
+    #[arg(short, long, global = true)]
     lang: Option<String>,
 
     /// Extract behavior specifications from doc comments
-    #[arg(long)]
+    #[arg(long, global = true)]
     behavior: bool,
 
     /// Generate index.yaml file (only used with --multi-file)
@@ -43,54 +50,284 @@ struct Cli {
     #[arg(long)]
     multi_file: bool,
 
+    /// Directory of query and external plugin manifests (defaults to .opensdd/plugins when present)
+    #[arg(long, global = true)]
+    plugins: Option<PathBuf>,
+
+    /// Project configuration file (defaults to .opensdd/spec-extract.toml, or the spec-extract
+    /// section of .opensdd/spec.yaml, when present)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Only extract files matching this glob (repeatable, added to the config's)
+    #[arg(long, value_name = "GLOB", global = true)]
+    include: Vec<String>,
+
+    /// Skip files and directories matching this glob (repeatable, added to the config's)
+    #[arg(long, value_name = "GLOB", global = true)]
+    exclude: Vec<String>,
+
+    /// Directory left out wherever it appears, replacing the configured ones (repeatable;
+    /// defaults to node_modules, vendor and __pycache__)
+    #[arg(long, value_name = "DIR", global = true)]
+    default_exclude: Vec<String>,
+
+    /// Extract the default excluded directories too, unless ignore files leave them out
+    #[arg(long, global = true, conflicts_with = "default_exclude")]
+    no_default_excludes: bool,
+
+    /// Skip generated files (`Code generated ... DO NOT EDIT`, `@generated`)
+    #[arg(long, global = true, overrides_with = "no_skip_generated")]
+    skip_generated: bool,
+
+    /// Extract generated files even when the config skips them
+    #[arg(long, global = true, overrides_with = "skip_generated")]
+    no_skip_generated: bool,
+
+    /// Which declarations end up in the spec: all, or only the public API (defaults to the
+    /// configured visibility, or all)
+    #[arg(long, value_parser = ["all", "public"], global = true)]
+    visibility: Option<String>,
+
+    /// Only extract files changed since this git ref, committed or not; the output also lists
+    /// deleted and renamed files
+    #[arg(long, value_name = "REF", conflicts_with = "multi_file")]
+    since: Option<String>,
+
+    /// Extract every file again instead of reusing cached specs of unchanged files
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Remove all cached specs before extracting
+    #[arg(long, global = true)]
+    clear_cache: bool,
+
+    /// Number of files to extract in parallel (defaults to the number of CPUs)
+    #[arg(short, long, global = true)]
+    jobs: Option<usize>,
+
     /// Verbose output
-    #[arg(short, long)]
+    #[arg(short, long, global = true)]
     verbose: bool,
 }
 
+#[derive(Subcommand)]
+enum Command {
+    /// Keep the aggregated output up to date, re-extracting files as they change
+    Watch {
+        /// Directory to watch (defaults to the configured roots, or .)
+        path: Option<PathBuf>,
+
+        /// Print what changed in each file's spec: `~ path: +added -removed ~changed`
+        #[arg(long)]
+        delta: bool,
+
+        /// Milliseconds to wait for a burst of changes to settle before extracting
+        #[arg(long, value_name = "MS", default_value = "100")]
+        debounce: u64,
+    },
+}
+
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    let format = OutputFormat::from_str(&cli.format)
-        .unwrap_or_else(|| {
-            eprintln!("Warning: Unknown format '{}', defaulting to YAML", cli.format);
+    // Flags override the project configuration
+    let mut config = Config::discover(cli.config.as_deref())?;
+    if cli.skip_generated || cli.no_skip_generated {
+        config.skip_generated = cli.skip_generated;
+    }
+    match cli.visibility.as_deref() {
+        Some("public") => config.visibility = Visibility::Public,
+        Some(_) => config.visibility = Visibility::All,
+        None => {}
+    }
+
+    let format = match &cli.format {
+        Some(name) => OutputFormat::from_str(name).unwrap_or_else(|| {
+            eprintln!("Warning: Unknown format '{}', defaulting to YAML", name);
             OutputFormat::Yaml
-        });
+        }),
+        None => config.format.unwrap_or_default(),
+    };
+
+    let mut walk_options = config.walk_options();
+    walk_options.include.extend(cli.include.iter().cloned());
+    walk_options.exclude.extend(cli.exclude.iter().cloned());
+    if cli.no_default_excludes {
+        walk_options.default_excludes.clear();
+    } else if !cli.default_exclude.is_empty() {
+        walk_options.default_excludes = cli.default_exclude.clone();
+    }
 
-    let registry = PluginRegistry::new();
+    let mut registry = PluginRegistry::new();
+
+    // Plugin manifests add languages, or replace built-in ones, without a rebuild
+    let plugins_dir = cli
+        .plugins
+        .clone()
+        .or_else(|| Some(PathBuf::from(".opensdd/plugins")).filter(|dir| dir.is_dir()));
+    if let Some(dir) = plugins_dir {
+        let loaded = registry.load_plugins(&dir)?;
+        if cli.verbose && !loaded.is_empty() {
+            println!("Loaded plugins: {}", loaded.join(", "));
+        }
+    }
+    config.apply(&mut registry)?;
+    let link = config.passes.contains(&Pass::Link);
 
     // Parse language filter
-    let lang_filter: Option<HashSet<String>> = cli.lang.map(|l| {
-        l.split(',')
-            .map(|s| s.trim().to_lowercase())
-            .collect()
-    });
-
-    let path = &cli.path;
-
-    if path.is_file() {
-        // Single source file: output to single spec file
-        let output_file = cli.output.unwrap_or_else(|| {
-            PathBuf::from(format!(".opensdd/extracted.{}", format.extension()))
-        });
-        extract_single_file(path, &output_file, format, &registry, cli.verbose)?;
-    } else if path.is_dir() {
-        // Directory mode
-        if cli.multi_file {
+    let lang_filter: Option<HashSet<String>> = match cli.lang {
+        Some(l) => Some(l.split(',').map(|s| s.trim().to_lowercase()).collect()),
+        None if !config.languages.is_empty() => Some(config.languages.iter().cloned().collect()),
+        None => None,
+    };
+
+    let path = match &cli.command {
+        Some(Command::Watch { path, .. }) => path.clone(),
+        None => cli.path.clone(),
+    };
+    let roots = match path {
+        Some(path) => vec![path],
+        None if !config.roots.is_empty() => config.roots.clone(),
+        None => vec![PathBuf::from(".")],
+    };
+
+    if let [path] = roots.as_slice() {
+        if path.is_file() && cli.command.is_none() {
+            // Single source file: output to single spec file
+            let output_file = cli.output.unwrap_or_else(|| {
+                PathBuf::from(format!(".opensdd/extracted.{}", format.extension()))
+            });
+            extract_single_file(path, &output_file, format, &registry, cli.verbose)?;
+            return Ok(());
+        }
+    }
+
+    // Directory mode: .gitignore and .ignore files decide what is source,
+    // refined by the include/exclude globs
+    let mut walkers = Vec::new();
+    for path in &roots {
+        if !path.is_dir() {
+            anyhow::bail!("Path does not exist: {}", path.display());
+        }
+        walkers.push(SourceWalker::new(path, &walk_options)?);
+    }
+
+    if let Some(Command::Watch { .. }) = &cli.command {
+        if cli.multi_file || cli.since.is_some() {
+            anyhow::bail!(
+                "watch keeps a single spec up to date; --multi-file and --since do not apply"
+            );
+        }
+    }
+
+    let mut files = source_files(&walkers, &registry, &lang_filter);
+
+    // Scope to a diff: git lists what changed, the walkers still decide what is source
+    let mut scope = None;
+    if let Some(since) = &cli.since {
+        let mut diff = DiffScope { since: Some(since.clone()), ..Default::default() };
+        let mut present = HashSet::new();
+        for walker in &walkers {
+            let changes = GitChanges::since(walker.root(), since)?;
+            let is_source = |path: &Path| {
+                walker.is_included(path)
+                    && registry.language_for_file(path).is_some_and(|language| {
+                        lang_filter.as_ref().is_none_or(|filter| filter.contains(&language))
+                    })
+            };
+            for change in &changes.changes {
+                match change {
+                    FileChange::Deleted(path) if is_source(path) => {
+                        diff.deleted.push(path.to_string_lossy().to_string())
+                    }
+                    FileChange::Renamed { from, to } if is_source(from) || is_source(to) => {
+                        diff.renamed.push(RenameSpec {
+                            from: from.to_string_lossy().to_string(),
+                            to: to.to_string_lossy().to_string(),
+                        })
+                    }
+                    _ => {}
+                }
+            }
+            present.extend(changes.present().into_iter().map(Path::to_path_buf));
+        }
+        files.retain(|file| present.contains(&file.path));
+        scope = Some(diff);
+    }
+
+    // Specs of unchanged files are reused across runs
+    let cache_dir = Path::new(spec_extract::cache::DEFAULT_CACHE_DIR);
+    if cli.clear_cache {
+        SpecCache::clear(cache_dir)?;
+    }
+    let cache = if cli.no_cache { None } else { Some(SpecCache::open(cache_dir)?) };
+
+    // Each worker thread keeps its own parsers (see `TreeSitterParser`)
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(cli.jobs.unwrap_or(0))
+        .build()
+        .context("Failed to start worker threads")?;
+
+    pool.install(|| {
+        if let Some(Command::Watch { delta, debounce, .. }) = &cli.command {
+            let output_file = cli.output.clone().unwrap_or_else(|| {
+                PathBuf::from(format!(".opensdd/extracted.{}", format.extension()))
+            });
+            watch(
+                &walkers,
+                files,
+                &registry,
+                cache.as_ref(),
+                &lang_filter,
+                &output_file,
+                format,
+                link,
+                *delta,
+                Duration::from_millis(*debounce),
+                cli.verbose,
+            )
+        } else if cli.multi_file {
             // Multi-file output: -o is a directory (legacy mode)
             let output_dir = cli.output.unwrap_or_else(|| PathBuf::from(".opensdd/extracted"));
-            extract_directory_multi(path, &output_dir, format, &registry, lang_filter, cli.verbose, cli.index)?;
+            extract_directory_multi(
+                &walkers,
+                files,
+                &output_dir,
+                format,
+                &registry,
+                cache.as_ref(),
+                cli.verbose,
+                link,
+                cli.index,
+            )
         } else {
             // Single-file output (default): -o is a file path
             let output_file = cli.output.unwrap_or_else(|| {
                 PathBuf::from(format!(".opensdd/extracted.{}", format.extension()))
             });
-            extract_directory_single(path, &output_file, format, &registry, lang_filter, cli.verbose)?;
+            extract_directory_single(
+                &walkers,
+                files,
+                &output_file,
+                format,
+                &registry,
+                cache.as_ref(),
+                cli.verbose,
+                link,
+                scope,
+            )
+        }
+    })?;
+
+    if let Some(cache) = &cache {
+        // A `--since` run leaves out unchanged files, whose entries stay
+        let pruned = if cli.since.is_none() { cache.prune()? } else { 0 };
+        if cli.verbose {
+            let (hits, misses) = cache.stats();
+            println!("Cache: {} reused, {} extracted, {} pruned", hits, misses, pruned);
         }
-    } else {
-        anyhow::bail!("Path does not exist: {}", path.display());
     }
-
     Ok(())
 }
 
@@ -123,6 +360,7 @@ fn extract_single_file(
                 root: path.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or_default(),
                 extracted_at: Some(chrono::Utc::now().to_rfc3339()),
                 files: vec![spec],
+                ..Default::default()
             };
 
             write_extracted_spec(&extracted_spec, output_file, format)?;
@@ -141,14 +379,95 @@ fn extract_single_file(
     Ok(())
 }
 
-fn extract_directory_single(
+/// A source file to extract, with the directory its multi-file spec path is relative to.
+struct SourceFile {
+    path: PathBuf,
+    base: PathBuf,
+    language: String,
+}
+
+/// What a `--since` run reports besides the specs of the changed files.
+#[derive(Default)]
+struct DiffScope {
+    since: Option<String>,
+    deleted: Vec<String>,
+    renamed: Vec<RenameSpec>,
+}
+
+/// The files under every root that have a plugin and pass the language filter,
+/// in walk order.
+fn source_files(
+    walkers: &[SourceWalker],
+    registry: &PluginRegistry,
+    lang_filter: &Option<HashSet<String>>,
+) -> Vec<SourceFile> {
+    let mut files = Vec::new();
+    for walker in walkers {
+        // With several roots, each one's specs go under a directory named after it
+        let base = match walkers {
+            [_] => walker.root(),
+            _ => walker.root().parent().unwrap_or(Path::new("")),
+        };
+        for path in walker.files() {
+            if let Some(language) = source_language(&path, registry, lang_filter) {
+                files.push(SourceFile { path, base: base.to_path_buf(), language });
+            }
+        }
+    }
+    files
+}
+
+/// The language of `path` if a plugin handles it and `lang_filter` lets it through.
+fn source_language(
     path: &Path,
+    registry: &PluginRegistry,
+    lang_filter: &Option<HashSet<String>>,
+) -> Option<String> {
+    let language = registry.language_for_file(path)?;
+    match lang_filter {
+        Some(filter) if !filter.contains(&language) => None,
+        _ => Some(language),
+    }
+}
+
+/// Bring `files`, the source files of `walkers` in output order, in line with the
+/// `touched` paths without walking the trees again: files that are gone, also with
+/// their directory, or no longer pass the filters are dropped and touched files that
+/// now pass them are added.
+fn refresh_source_files(
+    files: &mut Vec<PathBuf>,
+    touched: &HashSet<PathBuf>,
+    walkers: &[SourceWalker],
+    registry: &PluginRegistry,
+    lang_filter: &Option<HashSet<String>>,
+) {
+    let walker_index = |path: &Path| walkers.iter().position(|w| path.starts_with(w.root()));
+    let is_source = |path: &Path| {
+        path.is_file()
+            && walker_index(path).is_some_and(|i| walkers[i].is_included(path))
+            && source_language(path, registry, lang_filter).is_some()
+    };
+
+    files.retain(|file| !touched.iter().any(|path| file.starts_with(path)) || is_source(file));
+    let known: HashSet<PathBuf> = files.iter().cloned().collect();
+    files.extend(touched.iter().filter(|path| !known.contains(*path) && is_source(path)).cloned());
+    files.sort_by_cached_key(|file| (walker_index(file), file.clone()));
+}
+
+#[allow(clippy::too_many_arguments)]
+fn extract_directory_single(
+    walkers: &[SourceWalker],
+    files: Vec<SourceFile>,
     output_file: &Path,
     format: OutputFormat,
     registry: &PluginRegistry,
-    lang_filter: Option<HashSet<String>>,
+    cache: Option<&SpecCache>,
     verbose: bool,
+    link: bool,
+    scope: Option<DiffScope>,
 ) -> Result<()> {
+    let path = output_root(walkers);
+
     // Create parent directory if needed
     if let Some(parent) = output_file.parent() {
         if !parent.as_os_str().is_empty() {
@@ -167,37 +486,24 @@ fn extract_directory_single(
     let mut skipped_count = 0;
     let mut all_specs: Vec<spec_extract::FileSpec> = Vec::new();
 
-    for entry in WalkDir::new(path)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let file_path = entry.path();
-
-        if !file_path.is_file() {
-            continue;
-        }
-
-        if should_skip_path(file_path) {
-            continue;
-        }
-
-        let plugin = match registry.get_for_file(file_path) {
-            Some(p) => p,
-            None => continue,
-        };
-
-        if let Some(ref filter) = lang_filter {
-            if !filter.contains(plugin.name()) {
-                continue;
+    // Files are extracted in parallel; results come back in walk order
+    let results: Vec<Result<Option<spec_extract::FileSpec>>> = files
+        .par_iter()
+        .map(|file| {
+            let source = fs::read_to_string(&file.path)
+                .with_context(|| format!("Failed to read source file: {}", file.path.display()))?;
+            match cache {
+                Some(cache) => cache.get_or_extract(&source, &file.path, registry),
+                None => spec_extract::extract_spec(&source, &file.path, registry),
             }
-        }
+        })
+        .collect();
 
-        let source = fs::read_to_string(file_path)?;
-        match spec_extract::extract_spec(&source, file_path, registry) {
+    for (file, result) in files.iter().zip(results) {
+        match result {
             Ok(Some(spec)) => {
                 if verbose {
-                    println!("Extracted: {}", file_path.display());
+                    println!("Extracted: {}", file.path.display());
                 }
                 all_specs.push(spec);
                 extracted_count += 1;
@@ -206,18 +512,28 @@ fn extract_directory_single(
                 skipped_count += 1;
             }
             Err(e) => {
-                eprintln!("Error extracting {}: {}", file_path.display(), e);
+                eprintln!("Error extracting {}: {}", file.path.display(), e);
                 skipped_count += 1;
             }
         }
     }
 
-    if extracted_count > 0 {
+    // Cross-file passes (e.g. merging .pyi stubs into their modules)
+    if link {
+        registry.link(&mut all_specs)?;
+    }
+
+    // A partial spec is written even when nothing changed, so it never goes stale
+    if extracted_count > 0 || scope.is_some() {
+        let scope = scope.unwrap_or_default();
         let extracted_spec = ExtractedSpec {
             project: project_name,
             root: path.to_string_lossy().to_string(),
             extracted_at: Some(chrono::Utc::now().to_rfc3339()),
             files: all_specs,
+            since: scope.since,
+            deleted: scope.deleted,
+            renamed: scope.renamed,
         };
 
         write_extracted_spec(&extracted_spec, output_file, format)?;
@@ -235,15 +551,19 @@ fn extract_directory_single(
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn extract_directory_multi(
-    path: &Path,
+    walkers: &[SourceWalker],
+    files: Vec<SourceFile>,
     output_dir: &Path,
     format: OutputFormat,
     registry: &PluginRegistry,
-    lang_filter: Option<HashSet<String>>,
+    cache: Option<&SpecCache>,
     verbose: bool,
+    link: bool,
     generate_index: bool,
 ) -> Result<()> {
+    let path = output_root(walkers);
     fs::create_dir_all(output_dir)
         .with_context(|| format!("Failed to create output directory: {}", output_dir.display()))?;
 
@@ -257,60 +577,72 @@ fn extract_directory_multi(
     let mut extracted_count = 0;
     let mut skipped_count = 0;
 
-    for entry in WalkDir::new(path)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        let file_path = entry.path();
-
-        if !file_path.is_file() {
-            continue;
-        }
-
-        if should_skip_path(file_path) {
-            continue;
-        }
-
-        let plugin = match registry.get_for_file(file_path) {
-            Some(p) => p,
-            None => continue,
-        };
+    // Files are extracted in parallel; results come back in walk order
+    let results: Vec<Result<Option<spec_extract::FileSpec>>> = files
+        .par_iter()
+        .map(|file| {
+            let source = fs::read_to_string(&file.path)
+                .with_context(|| format!("Failed to read source file: {}", file.path.display()))?;
+            match cache {
+                Some(cache) => cache.get_or_extract(&source, &file.path, registry),
+                None => spec_extract::extract_spec(&source, &file.path, registry),
+            }
+        })
+        .collect();
 
-        if let Some(ref filter) = lang_filter {
-            if !filter.contains(plugin.name()) {
-                continue;
+    let mut specs = Vec::new();
+    for (file, result) in files.iter().zip(results) {
+        match result {
+            Ok(Some(spec)) => specs.push(spec),
+            Ok(None) => {
+                skipped_count += 1;
+            }
+            Err(e) => {
+                eprintln!("Error extracting {}: {}", file.path.display(), e);
+                skipped_count += 1;
             }
         }
+    }
 
-        let relative = file_path.strip_prefix(path).unwrap_or(file_path);
-        let spec_output_dir = output_dir.join(relative.parent().unwrap_or(Path::new("")));
+    // Cross-file passes run before writing, so merged companion files (e.g.
+    // .pyi stubs) get no spec file of their own
+    if link {
+        registry.link(&mut specs)?;
+    }
 
-        match generate_file_spec(file_path, &spec_output_dir, format, registry) {
-            Ok(Some(output_path)) => {
+    // Specs name their file the way plugins record it
+    let by_path: HashMap<String, &SourceFile> =
+        files.iter().map(|file| (file.path.to_string_lossy().to_string(), file)).collect();
+    let specs: Vec<(&SourceFile, spec_extract::FileSpec)> = specs
+        .into_iter()
+        .filter_map(|spec| Some((*by_path.get(&spec.file)?, spec)))
+        .collect();
+
+    // Spec files are written in parallel too
+    let results: Vec<Result<PathBuf>> = specs
+        .par_iter()
+        .map(|(file, spec)| {
+            let relative = file.path.strip_prefix(&file.base).unwrap_or(&file.path);
+            let spec_output_dir = output_dir.join(relative.parent().unwrap_or(Path::new("")));
+            write_spec_file(spec, &file.path, &spec_output_dir, format)
+        })
+        .collect();
+
+    for ((file, spec), result) in specs.into_iter().zip(results) {
+        match result {
+            Ok(output_path) => {
                 if verbose {
-                    println!("Extracted: {} -> {}", file_path.display(), output_path.display());
+                    println!("Extracted: {} -> {}", file.path.display(), output_path.display());
                 }
 
                 if generate_index {
-                    let source = fs::read_to_string(file_path)?;
-                    if let Ok(Some(spec)) = spec_extract::extract_spec(&source, file_path, registry) {
-                        index_builder.add(
-                            output_path,
-                            file_path.to_path_buf(),
-                            plugin.name().to_string(),
-                            spec,
-                        );
-                    }
+                    index_builder.add(output_path, file.path.clone(), file.language.clone(), spec);
                 }
 
                 extracted_count += 1;
             }
-            Ok(None) => {
-                skipped_count += 1;
-            }
             Err(e) => {
-                eprintln!("Error extracting {}: {}", file_path.display(), e);
+                eprintln!("Error extracting {}: {}", file.path.display(), e);
                 skipped_count += 1;
             }
         }
@@ -335,43 +667,145 @@ fn extract_directory_multi(
     Ok(())
 }
 
-fn should_skip_path(path: &Path) -> bool {
-    let path_str = path.to_string_lossy();
+/// The root recorded in the output: the extraction root, or the working
+/// directory when extracting several.
+fn output_root(walkers: &[SourceWalker]) -> &Path {
+    match walkers {
+        [walker] => walker.root(),
+        _ => Path::new("."),
+    }
+}
+
+/// Keep `output_file` in step with the tree: extract `files` once, then
+/// re-extract the files touched as filesystem events come in, until killed.
+#[allow(clippy::too_many_arguments)]
+fn watch(
+    walkers: &[SourceWalker],
+    files: Vec<SourceFile>,
+    registry: &PluginRegistry,
+    cache: Option<&SpecCache>,
+    lang_filter: &Option<HashSet<String>>,
+    output_file: &Path,
+    format: OutputFormat,
+    link: bool,
+    delta: bool,
+    debounce: Duration,
+    verbose: bool,
+) -> Result<()> {
+    let (sender, events) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).context("Failed to start watching")?;
+    // Events carry absolute paths, specs the paths as walked
+    let mut roots = Vec::new();
+    for walker in walkers {
+        let absolute = walker
+            .root()
+            .canonicalize()
+            .with_context(|| format!("Failed to resolve: {}", walker.root().display()))?;
+        watcher
+            .watch(&absolute, RecursiveMode::Recursive)
+            .with_context(|| format!("Failed to watch: {}", walker.root().display()))?;
+        roots.push((absolute, walker.root()));
+    }
 
-    // Skip hidden files and directories
-    for component in path.components() {
-        if let std::path::Component::Normal(s) = component {
-            if let Some(s) = s.to_str() {
-                if s.starts_with('.') {
-                    return true;
+    let mut session = WatchSession::new(registry, cache);
+    let mut files: Vec<PathBuf> = files.into_iter().map(|file| file.path).collect();
+    session.update(files.clone(), &HashSet::new());
+    if let Some(cache) = cache {
+        cache.prune()?;
+    }
+    let count = write_watched(&session, walkers, output_file, format, link)?;
+    println!("Extracted {} files to {}", count, output_file.display());
+    println!("Watching for changes (Ctrl-C to stop)");
+
+    loop {
+        // A save comes as a burst of events; wait for it to settle
+        let mut touched = HashSet::new();
+        let mut rescan = false;
+        let mut next = events.recv().context("Stopped watching")?;
+        loop {
+            match next {
+                // Reading files, our own included, is not a change
+                Ok(event) if event.kind.is_access() => {}
+                Ok(event) => {
+                    // A new or renamed directory can bring any number of files
+                    let names = matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))
+                    );
+                    rescan |= names && event.paths.iter().any(|path| path.is_dir());
+                    for path in event.paths {
+                        for (absolute, root) in &roots {
+                            if let Ok(relative) = path.strip_prefix(absolute) {
+                                touched.insert(root.join(relative));
+                            }
+                        }
+                    }
                 }
+                Err(e) => eprintln!("Warning: {}", e),
+            }
+            match events.recv_timeout(debounce) {
+                Ok(event) => next = event,
+                Err(_) => break,
             }
         }
-    }
+        if touched.is_empty() {
+            continue;
+        }
 
-    // Skip common non-source directories
-    let skip_patterns = [
-        "node_modules",
-        "vendor",
-        "target",
-        "dist",
-        "build",
-        "__pycache__",
-        ".git",
-        ".svn",
-        ".hg",
-    ];
-
-    for pattern in skip_patterns {
-        if path_str.contains(&format!("/{}/", pattern))
-            || path_str.contains(&format!("\\{}\\", pattern))
-        {
-            return true;
+        // Only a new directory needs the walk; the touched files are checked one by one
+        if rescan {
+            files = source_files(walkers, registry, lang_filter)
+                .into_iter()
+                .map(|file| file.path)
+                .collect();
+        } else {
+            refresh_source_files(&mut files, &touched, walkers, registry, lang_filter);
+        }
+        let deltas = session.update(files.clone(), &touched);
+        if deltas.is_empty() {
+            continue;
+        }
+        write_watched(&session, walkers, output_file, format, link)?;
+
+        if delta {
+            for change in &deltas {
+                println!("{}", change);
+            }
+        } else {
+            if verbose {
+                for change in &deltas {
+                    println!("Extracted: {}", change.file);
+                }
+            }
+            println!("Updated {} files in {}", deltas.len(), output_file.display());
         }
     }
+}
 
-    // Skip test files (optional, could be configurable)
-    // For now, include test files
+/// Write the aggregated spec of a watch session, returning how many files it has.
+fn write_watched(
+    session: &WatchSession,
+    walkers: &[SourceWalker],
+    output_file: &Path,
+    format: OutputFormat,
+    link: bool,
+) -> Result<usize> {
+    let path = output_root(walkers);
+    let project_name = path
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("project")
+        .to_string();
 
-    false
+    let files = session.specs(link)?;
+    let count = files.len();
+    let extracted_spec = ExtractedSpec {
+        project: project_name,
+        root: path.to_string_lossy().to_string(),
+        extracted_at: Some(chrono::Utc::now().to_rfc3339()),
+        files,
+        ..Default::default()
+    };
+    write_extracted_spec(&extracted_spec, output_file, format)?;
+    Ok(count)
 }
@@ -2,38 +2,53 @@ use anyhow::{Context, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::cache::SpecCache;
 use crate::plugins::PluginRegistry;
 use crate::spec::{write_file_spec, FileSpec, OutputFormat};
 
 /// Generate a spec file for a single source file.
+///
+/// Returns the path of the spec file along with the spec written to it. With a
+/// `cache`, unchanged files are not extracted again.
 pub fn generate_file_spec(
     source_path: &Path,
     output_dir: &Path,
     format: OutputFormat,
     registry: &PluginRegistry,
-) -> Result<Option<PathBuf>> {
-    // Get the appropriate plugin
-    let plugin = match registry.get_for_file(source_path) {
-        Some(p) => p,
-        None => return Ok(None),
-    };
+    cache: Option<&SpecCache>,
+) -> Result<Option<(PathBuf, FileSpec)>> {
+    if registry.language_for_file(source_path).is_none() {
+        return Ok(None);
+    }
 
     // Read the source file
     let source = fs::read_to_string(source_path)
         .with_context(|| format!("Failed to read source file: {}", source_path.display()))?;
 
     // Extract the spec
-    let spec = plugin
-        .extract(&source, source_path)
-        .with_context(|| format!("Failed to extract spec from: {}", source_path.display()))?;
-
-    // Determine output path
-    let output_path = get_output_path(source_path, output_dir, format);
+    let spec = match cache {
+        Some(cache) => cache.get_or_extract(&source, source_path, registry)?,
+        None => extract_spec(&source, source_path, registry)?,
+    };
+    let spec = match spec {
+        Some(spec) => spec,
+        None => return Ok(None),
+    };
 
-    // Write the spec
-    write_file_spec(&spec, &output_path, format)?;
+    let output_path = write_spec_file(&spec, source_path, output_dir, format)?;
+    Ok(Some((output_path, spec)))
+}
 
-    Ok(Some(output_path))
+/// Write the spec of `source_path` into `output_dir`, returning the spec file's path.
+pub fn write_spec_file(
+    spec: &FileSpec,
+    source_path: &Path,
+    output_dir: &Path,
+    format: OutputFormat,
+) -> Result<PathBuf> {
+    let output_path = get_output_path(source_path, output_dir, format);
+    write_file_spec(spec, &output_path, format)?;
+    Ok(output_path)
 }
 
 /// Generate spec from source content without writing to file.
@@ -42,16 +57,9 @@ pub fn extract_spec(
     source_path: &Path,
     registry: &PluginRegistry,
 ) -> Result<Option<FileSpec>> {
-    let plugin = match registry.get_for_file(source_path) {
-        Some(p) => p,
-        None => return Ok(None),
-    };
-
-    let spec = plugin
+    registry
         .extract(source, source_path)
-        .with_context(|| format!("Failed to extract spec from: {}", source_path.display()))?;
-
-    Ok(Some(spec))
+        .with_context(|| format!("Failed to extract spec from: {}", source_path.display()))
 }
 
 /// Calculate the output path for a source file.
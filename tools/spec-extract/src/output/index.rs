@@ -1,5 +1,5 @@
 use anyhow::Result;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 use crate::spec::{write_index_spec, FileEntry, FileSpec, IndexSpec, IndexStats, OutputFormat};
@@ -35,7 +35,7 @@ impl IndexBuilder {
         let mut total_types = 0;
         let mut total_functions = 0;
         let mut total_methods = 0;
-        let mut by_language: HashMap<String, usize> = HashMap::new();
+        let mut by_language: BTreeMap<String, usize> = BTreeMap::new();
 
         let files: Vec<FileEntry> = self
             .entries
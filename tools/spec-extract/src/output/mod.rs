@@ -1,5 +1,5 @@
 pub mod file_spec;
 pub mod index;
 
-pub use file_spec::{extract_spec, generate_file_spec};
+pub use file_spec::{extract_spec, generate_file_spec, write_spec_file};
 pub use index::IndexBuilder;
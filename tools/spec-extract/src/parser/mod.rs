@@ -1,5 +1,5 @@
 pub mod traits;
 pub mod tree_sitter;
 
-pub use traits::{ExtractOptions, LanguagePlugin};
+pub use traits::{ExtractOptions, LanguagePlugin, BUILD_VERSION};
 pub use tree_sitter::{NodeHelper, TreeSitterParser};
@@ -3,6 +3,11 @@ use std::path::Path;
 
 use crate::spec::FileSpec;
 
+/// Version of this build: the crate version and a hash of the sources it was built
+/// from (see `build.rs`), so specs cached by another build are not reused.
+pub const BUILD_VERSION: &str =
+    concat!(env!("CARGO_PKG_VERSION"), "+", env!("SPEC_EXTRACT_SOURCE_HASH"));
+
 /// Trait that all language plugins must implement.
 ///
 /// Each plugin is responsible for extracting specifications from source files
@@ -24,6 +29,14 @@ pub trait LanguagePlugin: Send + Sync {
     /// A FileSpec containing all extracted specifications, or an error.
     fn extract(&self, source: &str, path: &Path) -> Result<FileSpec>;
 
+    /// Version of the specs this plugin produces. Cached specs are only reused
+    /// by the same version, so it must change whenever the output does.
+    ///
+    /// Default implementation returns [`BUILD_VERSION`].
+    fn version(&self) -> String {
+        BUILD_VERSION.to_string()
+    }
+
     /// Optional: Perform deeper semantic analysis on an already-extracted spec.
     ///
     /// This can be used for things like:
@@ -36,6 +49,17 @@ pub trait LanguagePlugin: Send + Sync {
         Ok(())
     }
 
+    /// Optional: Reconcile specs across files once a whole tree has been extracted.
+    ///
+    /// `specs` holds every FileSpec produced in the run, so plugins must only touch
+    /// the entries they handle (see `can_handle`). This can be used for things like
+    /// merging companion files (e.g. Python `.pyi` stubs into their `.py` module).
+    ///
+    /// Default implementation does nothing.
+    fn link(&self, _specs: &mut Vec<FileSpec>) -> Result<()> {
+        Ok(())
+    }
+
     /// Check if this plugin can handle the given file path.
     fn can_handle(&self, path: &Path) -> bool {
         path.extension()
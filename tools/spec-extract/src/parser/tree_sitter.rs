@@ -1,29 +1,64 @@
 use anyhow::{Context, Result};
+use std::cell::RefCell;
+use std::collections::HashMap;
 use tree_sitter::{Language, Node, Parser, Tree};
 
+thread_local! {
+    /// Idle parsers of this thread, by language. Setting up a parser is not free,
+    /// and extraction runs many files per worker thread.
+    static IDLE_PARSERS: RefCell<HashMap<Language, Vec<Parser>>> = RefCell::new(HashMap::new());
+}
+
 /// Wrapper around tree-sitter Parser with utility methods.
+///
+/// Parsers are reused: dropping one hands it back to its thread, whose next
+/// `new` for the same language picks it up again.
 pub struct TreeSitterParser {
-    parser: Parser,
+    parser: Option<Parser>,
+    language: Language,
 }
 
 impl TreeSitterParser {
     /// Create a new parser for the given language.
     pub fn new(language: Language) -> Result<Self> {
-        let mut parser = Parser::new();
-        parser
-            .set_language(&language)
-            .context("Failed to set parser language")?;
-        Ok(Self { parser })
+        let idle = IDLE_PARSERS.with(|idle| {
+            idle.borrow_mut().get_mut(&language).and_then(|parsers| parsers.pop())
+        });
+        let parser = match idle {
+            Some(parser) => parser,
+            None => {
+                let mut parser = Parser::new();
+                parser
+                    .set_language(&language)
+                    .context("Failed to set parser language")?;
+                parser
+            }
+        };
+        Ok(Self { parser: Some(parser), language })
     }
 
     /// Parse source code into a syntax tree.
     pub fn parse(&mut self, source: &str) -> Result<Tree> {
         self.parser
-            .parse(source, None)
+            .as_mut()
+            .and_then(|parser| parser.parse(source, None))
             .context("Failed to parse source code")
     }
 }
 
+impl Drop for TreeSitterParser {
+    fn drop(&mut self) {
+        if let Some(mut parser) = self.parser.take() {
+            parser.reset();
+            let language = self.language.clone();
+            // The thread may be shutting down, taking its parsers with it
+            let _ = IDLE_PARSERS.try_with(|idle| {
+                idle.borrow_mut().entry(language).or_default().push(parser);
+            });
+        }
+    }
+}
+
 /// Helper functions for working with tree-sitter nodes.
 pub struct NodeHelper;
 
@@ -101,20 +136,21 @@ impl NodeHelper {
             .map(|line| {
                 let trimmed = line.trim();
                 // Handle various comment styles
-                if trimmed.starts_with("///") {
-                    trimmed[3..].trim().to_string()
-                } else if trimmed.starts_with("//!") {
-                    trimmed[3..].trim().to_string()
-                } else if trimmed.starts_with("//") {
-                    trimmed[2..].trim().to_string()
-                } else if trimmed.starts_with("/*") {
-                    trimmed[2..].trim_start().trim_end_matches("*/").trim().to_string()
+                if let Some(rest) = trimmed
+                    .strip_prefix("///")
+                    .or_else(|| trimmed.strip_prefix("//!"))
+                    .or_else(|| trimmed.strip_prefix("//"))
+                {
+                    rest.trim().to_string()
+                } else if let Some(rest) = trimmed.strip_prefix("/*") {
+                    rest.trim_start_matches('*').trim_start().trim_end_matches("*/").trim().to_string()
                 } else if trimmed.starts_with("*/") {
                     String::new()
-                } else if trimmed.starts_with('*') {
-                    trimmed[1..].trim().to_string()
-                } else if trimmed.starts_with('#') {
-                    trimmed[1..].trim().to_string()
+                } else if let Some(rest) = trimmed
+                    .strip_prefix('*')
+                    .or_else(|| trimmed.strip_prefix('#'))
+                {
+                    rest.trim().to_string()
                 } else if trimmed.starts_with("\"\"\"") || trimmed.starts_with("'''") {
                     trimmed[3..].trim_end_matches("\"\"\"").trim_end_matches("'''").trim().to_string()
                 } else {
@@ -164,4 +200,27 @@ mod tests {
     fn test_clean_comment_python() {
         assert_eq!(NodeHelper::clean_comment("# Python comment"), "Python comment");
     }
+
+    #[test]
+    fn test_parsers_are_reused() {
+        let go: Language = tree_sitter_go::LANGUAGE.into();
+        let idle = |language: &Language| {
+            IDLE_PARSERS.with(|idle| idle.borrow().get(language).map_or(0, |p| p.len()))
+        };
+
+        let mut parser = TreeSitterParser::new(go.clone()).unwrap();
+        // A second parser for the same language while the first is in use
+        let mut nested = TreeSitterParser::new(go.clone()).unwrap();
+        let tree = parser.parse("package a\n").unwrap();
+        assert_eq!(tree.root_node().kind(), "source_file");
+        assert!(nested.parse("package b\n").is_ok());
+        drop(nested);
+        drop(parser);
+        assert_eq!(idle(&go), 2);
+
+        let mut parser = TreeSitterParser::new(go.clone()).unwrap();
+        assert_eq!(idle(&go), 1);
+        let tree = parser.parse("package c\n\nfunc F() {}\n").unwrap();
+        assert!(!tree.root_node().has_error());
+    }
 }
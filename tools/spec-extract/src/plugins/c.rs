@@ -0,0 +1,134 @@
+use anyhow::Result;
+use std::path::Path;
+
+use super::CppPlugin;
+use crate::parser::LanguagePlugin;
+use crate::spec::FileSpec;
+
+/// File extensions handled by the C plugin.
+pub(crate) const C_EXTENSIONS: &[&str] = &["c", "h"];
+
+/// Plugin for extracting specifications from C source files.
+///
+/// Extraction is shared with the C++ plugin, which also pairs header declarations
+/// with their definitions across both languages.
+pub struct CPlugin;
+
+impl CPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for CPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for CPlugin {
+    fn name(&self) -> &'static str {
+        "c"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        C_EXTENSIONS
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        // `.h` headers are often shared with C++, whose grammar also parses C declarations
+        let language = match path.extension().and_then(|e| e.to_str()) {
+            Some("h") => tree_sitter_cpp::LANGUAGE,
+            _ => tree_sitter_c::LANGUAGE,
+        };
+        CppPlugin::new().extract_with_language(source, path, language.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_header() {
+        let plugin = CPlugin::new();
+        let source = r#"
+#ifndef DB_H
+#define DB_H
+
+#include <stddef.h>
+
+#define DB_VERSION "1.2"
+#define MIN(a, b) ((a) < (b) ? (a) : (b))
+
+/** A database connection. */
+typedef struct db {
+    int fd;
+    char *name;
+} db_t;
+
+union value { int i; double d; };
+enum color { RED, GREEN = 2 };
+typedef int (*db_callback)(void *ctx, int code);
+
+extern int db_count;
+static const int DB_MAX = 8;
+
+/* Opens a database. */
+db_t *db_open(const char *path);
+
+#endif
+"#;
+        let spec = plugin.extract(source, Path::new("db.h")).unwrap();
+        assert_eq!(spec.package, "db");
+        assert_eq!(spec.imports, vec!["stddef.h"]);
+
+        let names: Vec<(&str, &str)> =
+            spec.types.iter().map(|t| (t.name.as_str(), t.kind.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("db_t struct", "struct"),
+                ("value union", "union"),
+                ("color enum", "enum"),
+                ("db_callback type", "type_alias"),
+            ]
+        );
+        assert_eq!(spec.types[0].doc.as_deref(), Some("A database connection."));
+        assert_eq!(spec.types[0].fields, vec!["int fd", "char *name"]);
+        assert_eq!(spec.types[2].variants, vec!["RED", "GREEN = 2"]);
+
+        let constants: Vec<(&str, Option<&str>)> =
+            spec.constants.iter().map(|c| (c.name.as_str(), c.value.as_deref())).collect();
+        assert_eq!(constants, vec![("DB_VERSION", Some("\"1.2\"")), ("DB_MAX", Some("8"))]);
+        assert_eq!(spec.variables[0].name, "db_count");
+        assert_eq!(spec.variables[0].type_name.as_deref(), Some("extern int"));
+
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(spec.functions[0].signature, "db_t *db_open(const char *path)");
+        assert_eq!(spec.functions[0].doc.as_deref(), Some("Opens a database."));
+        assert!(spec.functions[0].declaration);
+    }
+
+    #[test]
+    fn test_extract_source() {
+        let plugin = CPlugin::new();
+        let source = r#"
+#include "db.h"
+
+static int open_count = 0;
+
+/* Opens a database. */
+db_t *db_open(const char *path)
+{
+    open_count++;
+    return NULL;
+}
+"#;
+        let spec = plugin.extract(source, Path::new("db.c")).unwrap();
+        assert_eq!(spec.imports, vec!["db.h"]);
+        assert_eq!(spec.variables[0].name, "open_count");
+        assert_eq!(spec.functions[0].signature, "db_t *db_open(const char *path)");
+        assert!(!spec.functions[0].declaration);
+    }
+}
@@ -0,0 +1,822 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tree_sitter::{Language, Node};
+
+use super::c::C_EXTENSIONS;
+use super::lexer::squash;
+use super::tsconfig::normalize_path;
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
+use crate::spec::{ConstSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+
+pub(crate) const CPP_EXTENSIONS: &[&str] = &["cc", "cpp", "cxx", "hpp", "hh", "hxx"];
+
+/// Preprocessor conditionals whose contents belong to the enclosing scope.
+const CONDITIONAL_KINDS: &[&str] =
+    &["preproc_ifdef", "preproc_if", "preproc_else", "preproc_elif", "preproc_elifdef"];
+
+/// Specifiers that can introduce a named type.
+const TYPE_SPECIFIERS: &[&str] =
+    &["struct_specifier", "union_specifier", "enum_specifier", "class_specifier"];
+
+/// Plugin for extracting specifications from C++ source files.
+///
+/// Also does the work for C (see [`super::CPlugin`]): the two grammars share node kinds
+/// for everything C has.
+pub struct CppPlugin;
+
+impl CppPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Extract using the given grammar.
+    pub(crate) fn extract_with_language(
+        &self,
+        source: &str,
+        path: &Path,
+        language: Language,
+    ) -> Result<FileSpec> {
+        let mut parser = TreeSitterParser::new(language)?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: self.extract_package(root, source, path),
+            ..Default::default()
+        };
+
+        self.walk(root, source, &[], &mut spec);
+        drop_defined_declarations(&mut spec);
+        Ok(spec)
+    }
+
+    /// The outermost namespace (`a::b` for directly nested ones), or the file name.
+    fn extract_package(&self, root: Node, source: &str, path: &Path) -> String {
+        let mut segments = Vec::new();
+        let mut namespace = NodeHelper::find_all(root, "namespace_definition")
+            .into_iter()
+            .find(|ns| NodeHelper::field(*ns, "name").is_some());
+
+        while let Some(ns) = namespace {
+            segments.extend(self.namespace_name(ns, source));
+            // Continue only while the body is nothing but another namespace
+            namespace = NodeHelper::field(ns, "body").and_then(|body| {
+                let mut cursor = body.walk();
+                let members: Vec<Node> =
+                    body.named_children(&mut cursor).filter(|c| c.kind() != "comment").collect();
+                match members.as_slice() {
+                    [only] if only.kind() == "namespace_definition" => Some(*only),
+                    _ => None,
+                }
+            });
+        }
+
+        if segments.is_empty() {
+            path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string()
+        } else {
+            segments.join("::")
+        }
+    }
+
+    fn namespace_name(&self, node: Node, source: &str) -> Vec<String> {
+        NodeHelper::field_text(node, "name", source)
+            .map(|name| name.split("::").map(|s| s.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Name qualified by its namespaces, relative to the file's package.
+    fn qualify(&self, namespace: &[String], name: &str, package: &str) -> String {
+        if namespace.is_empty() {
+            return name.to_string();
+        }
+        let full = format!("{}::{}", namespace.join("::"), name);
+        full.strip_prefix(&format!("{}::", package)).map(str::to_string).unwrap_or(full)
+    }
+
+    fn walk(&self, node: Node, source: &str, namespace: &[String], spec: &mut FileSpec) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit(child, source, namespace, None, spec);
+        }
+    }
+
+    /// Handle one namespace-level declaration; `template` is an enclosing
+    /// `template <...>` header, which also carries the doc comment.
+    fn visit(
+        &self,
+        node: Node,
+        source: &str,
+        namespace: &[String],
+        template: Option<(&str, Option<String>)>,
+        spec: &mut FileSpec,
+    ) {
+        let (template, doc) = match template {
+            Some((params, doc)) => (Some(params), doc),
+            None => (None, NodeHelper::preceding_comment(node, source)),
+        };
+
+        match node.kind() {
+            "preproc_include" => {
+                if let Some(path) = NodeHelper::field_text(node, "path", source) {
+                    let path = path.trim_matches(|c| matches!(c, '<' | '>' | '"'));
+                    spec.imports.push(path.to_string());
+                }
+            }
+            "preproc_def" => {
+                // Valueless defines are include guards and feature flags
+                let name = NodeHelper::field_text(node, "name", source);
+                let value = NodeHelper::field_text(node, "value", source).map(str::trim);
+                if let (Some(name), Some(value)) = (name, value.filter(|v| !v.is_empty())) {
+                    spec.constants.push(ConstSpec {
+                        name: name.to_string(),
+                        value: Some(value.to_string()),
+                        doc,
+                        ..Default::default()
+                    });
+                }
+            }
+            kind if CONDITIONAL_KINDS.contains(&kind) => self.walk(node, source, namespace, spec),
+            "linkage_specification" => match NodeHelper::field(node, "body") {
+                Some(body) if body.kind() == "declaration_list" => {
+                    self.walk(body, source, namespace, spec)
+                }
+                Some(body) => self.visit(body, source, namespace, None, spec),
+                None => {}
+            },
+            "namespace_definition" => {
+                let mut inner = namespace.to_vec();
+                inner.extend(self.namespace_name(node, source));
+                if let Some(body) = NodeHelper::field(node, "body") {
+                    self.walk(body, source, &inner, spec);
+                }
+            }
+            "template_declaration" => {
+                let params = NodeHelper::field_text(node, "parameters", source).unwrap_or("<>");
+                let header = format!("template {}", params);
+                if let Some(inner) = self.template_body(node) {
+                    self.visit(inner, source, namespace, Some((&header, doc)), spec);
+                }
+            }
+            kind if TYPE_SPECIFIERS.contains(&kind) => {
+                self.extract_type(node, source, namespace, None, template, doc, spec);
+            }
+            "type_definition" => self.extract_typedef(node, source, namespace, doc, spec),
+            "alias_declaration" => {
+                let name = NodeHelper::field_text(node, "name", source).unwrap_or("");
+                spec.types.push(TypeSpec {
+                    name: format!("{} type", self.qualify(namespace, name, &spec.package)),
+                    doc,
+                    kind: "type_alias".to_string(),
+                    type_params: template.map(template_params).unwrap_or_default(),
+                    ..Default::default()
+                });
+            }
+            "declaration" => self.extract_declaration(node, source, namespace, template, doc, spec),
+            "function_definition" => {
+                let mut func = self.function_spec(node, source, template, doc);
+                // `void User::save() {}` defines a method out of line
+                match declarator_scope(node, source) {
+                    Some(scope) => {
+                        func.receiver = Some(self.qualify(namespace, &scope, &spec.package));
+                        spec.methods.push(func);
+                    }
+                    None => spec.functions.push(func),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The declaration a `template <...>` header applies to.
+    fn template_body<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let mut cursor = node.walk();
+        let body = node
+            .named_children(&mut cursor)
+            .find(|c| c.kind() != "template_parameter_list" && c.kind() != "comment");
+        body
+    }
+
+    /// A `struct`/`union`/`enum`/`class` with a body, and (for classes) its members.
+    #[allow(clippy::too_many_arguments)]
+    fn extract_type(
+        &self,
+        node: Node,
+        source: &str,
+        namespace: &[String],
+        typedef_name: Option<&str>,
+        template: Option<&str>,
+        doc: Option<String>,
+        spec: &mut FileSpec,
+    ) {
+        let body = match NodeHelper::field(node, "body") {
+            Some(body) => body,
+            None => return, // Forward declaration or elaborated type
+        };
+        let name = match typedef_name.or_else(|| NodeHelper::field_text(node, "name", source)) {
+            Some(name) => self.qualify(namespace, name, &spec.package),
+            None => return, // Anonymous; its members belong to the enclosing declaration
+        };
+
+        let mut kind = match node.kind() {
+            "struct_specifier" => "struct",
+            "union_specifier" => "union",
+            "enum_specifier" => "enum",
+            _ => "class",
+        };
+
+        let embeds = NodeHelper::child_by_kind(node, "base_class_clause")
+            .map(|bases| {
+                let mut cursor = bases.walk();
+                let embeds: Vec<String> = bases
+                    .named_children(&mut cursor)
+                    .filter(|b| b.kind() != "access_specifier")
+                    .map(|b| NodeHelper::text(b, source).to_string())
+                    .collect();
+                embeds
+            })
+            .unwrap_or_default();
+
+        let mut variants = Vec::new();
+        let mut fields = Vec::new();
+        if kind == "enum" {
+            for enumerator in NodeHelper::children_by_kind(body, "enumerator") {
+                variants.push(squash(NodeHelper::text(enumerator, source)));
+            }
+        } else {
+            let path: Vec<String> = namespace.iter().cloned().chain([leaf(&name)]).collect();
+            let members = self.extract_members(node, body, source, &name, &path, spec);
+            if members.is_abstract {
+                kind = "abstract_class";
+            }
+            fields = members.fields;
+        }
+
+        let suffix = if kind == "abstract_class" { "class" } else { kind };
+        spec.types.push(TypeSpec {
+            name: format!("{} {}", name, suffix),
+            doc,
+            kind: kind.to_string(),
+            fields,
+            embeds,
+            type_params: template.map(template_params).unwrap_or_default(),
+            variants,
+            ..Default::default()
+        });
+    }
+
+    /// Fields and methods of a struct, union or class body.
+    fn extract_members(
+        &self,
+        node: Node,
+        body: Node,
+        source: &str,
+        owner: &str,
+        path: &[String],
+        spec: &mut FileSpec,
+    ) -> Members {
+        let mut members = Members::default();
+        // Access labels only mean something in C++ classes, or structs that use them
+        let is_class = node.kind() == "class_specifier";
+        let show_access = is_class || NodeHelper::has_child_kind(body, "access_specifier");
+        let mut access = if is_class { "private" } else { "public" };
+        let prefix = |access: &str| {
+            if show_access {
+                format!("{} ", access)
+            } else {
+                String::new()
+            }
+        };
+
+        let mut cursor = body.walk();
+        for member in body.named_children(&mut cursor) {
+            let doc = NodeHelper::preceding_comment(member, source);
+            let (member, template, doc) = match member.kind() {
+                "template_declaration" => {
+                    let params =
+                        NodeHelper::field_text(member, "parameters", source).unwrap_or("<>");
+                    match self.template_body(member) {
+                        Some(inner) => (inner, Some(format!("template {}", params)), doc),
+                        None => continue,
+                    }
+                }
+                _ => (member, None, doc),
+            };
+
+            match member.kind() {
+                "access_specifier" => access = NodeHelper::text(member, source),
+                kind if TYPE_SPECIFIERS.contains(&kind) => {
+                    self.extract_type(member, source, path, None, template.as_deref(), doc, spec);
+                }
+                "function_definition" => {
+                    let mut func = self.function_spec(member, source, template.as_deref(), doc);
+                    func.signature = format!("{}{}", prefix(access), func.signature);
+                    func.receiver = Some(owner.to_string());
+                    spec.methods.push(func);
+                }
+                "field_declaration" | "declaration" => {
+                    let declarator = NodeHelper::field(member, "declarator");
+                    if declarator.and_then(function_declarator).is_some() {
+                        let pure =
+                            NodeHelper::field_text(member, "default_value", source) == Some("0");
+                        members.is_abstract |= pure;
+                        let mut func = self.function_spec(member, source, template.as_deref(), doc);
+                        func.signature = format!("{}{}", prefix(access), func.signature);
+                        func.receiver = Some(owner.to_string());
+                        func.declaration &= !pure;
+                        spec.methods.push(func);
+                        continue;
+                    }
+
+                    // Nested type declared together with a member (`enum Kind { A } kind;`)
+                    if let Some(ty) = NodeHelper::field(member, "type") {
+                        if TYPE_SPECIFIERS.contains(&ty.kind()) {
+                            self.extract_type(ty, source, path, None, None, doc, spec);
+                        }
+                    }
+
+                    let type_text = declaration_type(member, source);
+                    for declarator in declarators(member) {
+                        let text = NodeHelper::text(declarator, source);
+                        members.fields.push(format!("{}{} {}", prefix(access), type_text, text));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        members
+    }
+
+    /// `typedef struct {...} name;` names the struct; other typedefs are aliases.
+    fn extract_typedef(
+        &self,
+        node: Node,
+        source: &str,
+        namespace: &[String],
+        doc: Option<String>,
+        spec: &mut FileSpec,
+    ) {
+        let names: Vec<String> = declarators(node)
+            .into_iter()
+            .filter_map(|d| declarator_name(d, source))
+            .collect();
+
+        if let Some(ty) = NodeHelper::field(node, "type") {
+            if TYPE_SPECIFIERS.contains(&ty.kind()) && NodeHelper::field(ty, "body").is_some() {
+                let name = names.first().map(String::as_str);
+                self.extract_type(ty, source, namespace, name, None, doc, spec);
+                return;
+            }
+        }
+
+        for name in names {
+            spec.types.push(TypeSpec {
+                name: format!("{} type", self.qualify(namespace, &name, &spec.package)),
+                doc: doc.clone(),
+                kind: "type_alias".to_string(),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// A namespace-level declaration: prototypes, variables, constants, and types
+    /// declared along with them.
+    fn extract_declaration(
+        &self,
+        node: Node,
+        source: &str,
+        namespace: &[String],
+        template: Option<&str>,
+        doc: Option<String>,
+        spec: &mut FileSpec,
+    ) {
+        if let Some(ty) = NodeHelper::field(node, "type") {
+            if TYPE_SPECIFIERS.contains(&ty.kind()) {
+                self.extract_type(ty, source, namespace, None, template, doc.clone(), spec);
+            }
+        }
+
+        let declarator = NodeHelper::field(node, "declarator");
+        if declarator.and_then(function_declarator).is_some() {
+            spec.functions.push(self.function_spec(node, source, template, doc));
+            return;
+        }
+
+        let type_text = declaration_type(node, source);
+        let is_const =
+            self.modifiers(node, source).iter().any(|m| matches!(*m, "const" | "constexpr"));
+        for declarator in declarators(node) {
+            let name = match declarator_name(declarator, source) {
+                Some(name) => self.qualify(namespace, &name, &spec.package),
+                None => continue,
+            };
+            let value = NodeHelper::field_text(declarator, "value", source).map(squash);
+            if is_const && value.is_some() {
+                spec.constants.push(ConstSpec {
+                    name,
+                    type_name: Some(type_text.clone()),
+                    value,
+                    doc: doc.clone(),
+                    ..Default::default()
+                });
+            } else {
+                spec.variables.push(VarSpec {
+                    name,
+                    type_name: Some(type_text.clone()),
+                    doc: doc.clone(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    /// Storage classes and qualifiers written before the type.
+    fn modifiers<'a>(&self, node: Node<'a>, source: &'a str) -> Vec<&'a str> {
+        let mut cursor = node.walk();
+        let modifiers = node
+            .named_children(&mut cursor)
+            .filter(|c| matches!(c.kind(), "storage_class_specifier" | "type_qualifier"))
+            .map(|c| NodeHelper::text(c, source))
+            .collect();
+        modifiers
+    }
+
+    /// Signature of a function definition or declaration, without body or initializers.
+    fn function_spec(
+        &self,
+        node: Node,
+        source: &str,
+        template: Option<&str>,
+        doc: Option<String>,
+    ) -> FuncSpec {
+        let end = ["field_initializer_list", "compound_statement", "try_statement"]
+            .iter()
+            .filter_map(|kind| NodeHelper::child_by_kind(node, kind))
+            .map(|n| n.start_byte())
+            .min()
+            .unwrap_or(node.end_byte());
+        let text = source[node.start_byte()..end].trim_end().trim_end_matches(';');
+        let signature = match template {
+            Some(template) => format!("{} {}", template, squash(text)),
+            None => squash(text),
+        };
+
+        // `= delete` and `= default` need no separate definition
+        let has_body = node.kind() == "function_definition";
+        let initialized = NodeHelper::field(node, "declarator")
+            .is_some_and(|d| d.kind() == "init_declarator");
+
+        FuncSpec {
+            signature,
+            doc,
+            declaration: !has_body && !initialized,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Default)]
+struct Members {
+    fields: Vec<String>,
+    is_abstract: bool,
+}
+
+/// Last `::` segment of a qualified name.
+fn leaf(name: &str) -> String {
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
+
+fn template_params(template: &str) -> Vec<String> {
+    let params = template.trim_start_matches("template").trim();
+    let inner = params.strip_prefix('<').and_then(|p| p.strip_suffix('>')).unwrap_or(params);
+    super::lexer::split_top_level(inner).into_iter().map(|p| squash(&p)).collect()
+}
+
+/// The function declarator under pointer, reference and initializer declarators.
+fn function_declarator(node: Node) -> Option<Node> {
+    match node.kind() {
+        "function_declarator" => {
+            // `int (*callback)(int)` declares a function pointer, not a function
+            let inner = NodeHelper::field(node, "declarator")?;
+            (inner.kind() != "parenthesized_declarator").then_some(node)
+        }
+        "pointer_declarator" | "reference_declarator" | "init_declarator" => {
+            let inner = NodeHelper::field(node, "declarator").or_else(|| node.named_child(0))?;
+            function_declarator(inner)
+        }
+        _ => None,
+    }
+}
+
+/// `Owner` of an out-of-line `Owner::name` definition.
+fn declarator_scope(node: Node, source: &str) -> Option<String> {
+    let function = NodeHelper::field(node, "declarator").and_then(function_declarator)?;
+    let name = NodeHelper::field(function, "declarator")?;
+    if name.kind() != "qualified_identifier" {
+        return None;
+    }
+    let text = NodeHelper::text(name, source);
+    text.rsplit_once("::").map(|(scope, _)| scope.to_string())
+}
+
+/// The identifier a declarator introduces.
+fn declarator_name(node: Node, source: &str) -> Option<String> {
+    match node.kind() {
+        "identifier" | "field_identifier" | "type_identifier" | "qualified_identifier"
+        | "destructor_name" | "operator_name" => Some(NodeHelper::text(node, source).to_string()),
+        _ => {
+            let inner = NodeHelper::field(node, "declarator").or_else(|| node.named_child(0))?;
+            declarator_name(inner, source)
+        }
+    }
+}
+
+/// Every `declarator` field of a declaration (`int x, *y;` has two).
+fn declarators(node: Node) -> Vec<Node> {
+    let mut cursor = node.walk();
+    let declarators = node.children_by_field_name("declarator", &mut cursor).collect();
+    declarators
+}
+
+/// Declaration text before its first declarator (`static const char`).
+fn declaration_type(node: Node, source: &str) -> String {
+    let end = declarators(node).first().map(|d| d.start_byte()).unwrap_or(node.end_byte());
+    squash(source[node.start_byte()..end].trim())
+}
+
+/// Name a function is paired by: its last declarator segment and its receiver's.
+fn pairing_key(func: &FuncSpec) -> (String, String) {
+    // The name is the last word before the parameter list
+    let signature = func.signature.strip_prefix("template ").map_or(func.signature.as_str(), |s| {
+        let mut depth = 0;
+        for (i, c) in s.char_indices() {
+            match c {
+                '<' => depth += 1,
+                '>' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return &s[i + 1..];
+                    }
+                }
+                _ => {}
+            }
+        }
+        s
+    });
+    let head = signature.split('(').next().unwrap_or("");
+    let word = head.split_whitespace().last().unwrap_or("");
+    let word = word.trim_start_matches(['*', '&']);
+    let name = leaf(word);
+    let receiver = func.receiver.as_deref().map(leaf).unwrap_or_default();
+    (receiver, name)
+}
+
+/// Remove prototypes that are defined later in the same file.
+fn drop_defined_declarations(spec: &mut FileSpec) {
+    let defined: HashSet<(String, String)> = spec
+        .functions
+        .iter()
+        .chain(spec.methods.iter())
+        .filter(|f| !f.declaration)
+        .map(pairing_key)
+        .collect();
+    let keep = |f: &FuncSpec| !f.declaration || !defined.contains(&pairing_key(f));
+    spec.functions.retain(keep);
+    spec.methods.retain(keep);
+}
+
+fn is_c_family(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|ext| C_EXTENSIONS.contains(&ext) || CPP_EXTENSIONS.contains(&ext))
+}
+
+impl Default for CppPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for CppPlugin {
+    fn name(&self) -> &'static str {
+        "cpp"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        CPP_EXTENSIONS
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        self.extract_with_language(source, path, tree_sitter_cpp::LANGUAGE.into())
+    }
+
+    // Also covers C files, since headers are shared between the two
+    fn link(&self, specs: &mut Vec<FileSpec>) -> Result<()> {
+        let mut indices: Vec<usize> =
+            (0..specs.len()).filter(|&i| is_c_family(Path::new(&specs[i].file))).collect();
+        indices.sort_by(|&a, &b| specs[a].file.cmp(&specs[b].file));
+
+        // Resolve quoted includes next to the including file, then by path suffix
+        let files: HashMap<PathBuf, usize> =
+            indices.iter().map(|&i| (normalize_path(Path::new(&specs[i].file)), i)).collect();
+        for &idx in &indices {
+            let dir = Path::new(&specs[idx].file).parent().unwrap_or(Path::new("")).to_path_buf();
+            let resolved: BTreeMap<String, String> = specs[idx]
+                .imports
+                .iter()
+                .filter_map(|include| {
+                    let relative = files.get(&normalize_path(&dir.join(include))).copied();
+                    let target = relative.or_else(|| {
+                        let suffix = format!("/{}", include);
+                        let mut matches =
+                            indices.iter().filter(|&&i| specs[i].file.ends_with(&suffix));
+                        match (matches.next(), matches.next()) {
+                            (Some(&only), None) => Some(only),
+                            _ => None,
+                        }
+                    })?;
+                    Some((include.clone(), specs[target].file.clone()))
+                })
+                .collect();
+            specs[idx].resolved_imports = resolved;
+        }
+
+        // Pair declarations with the files defining them
+        let mut definitions: HashMap<(String, String), String> = HashMap::new();
+        let mut by_name: HashMap<String, String> = HashMap::new();
+        for &idx in &indices {
+            let spec = &specs[idx];
+            let functions = spec.functions.iter().chain(spec.methods.iter());
+            for func in functions.filter(|f| !f.declaration) {
+                let key = pairing_key(func);
+                by_name.entry(key.1.clone()).or_insert_with(|| spec.file.clone());
+                definitions.entry(key).or_insert_with(|| spec.file.clone());
+            }
+        }
+
+        for &idx in &indices {
+            let spec = &mut specs[idx];
+            for func in spec.functions.iter_mut().chain(spec.methods.iter_mut()) {
+                if !func.declaration {
+                    continue;
+                }
+                let (receiver, name) = pairing_key(func);
+                // A free function may be defined as `ns::name`, which reads like a method
+                func.defined_in = if receiver.is_empty() {
+                    by_name.get(&name).cloned()
+                } else {
+                    definitions.get(&(receiver, name)).cloned()
+                };
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_class() {
+        let plugin = CppPlugin::new();
+        let source = r#"
+#include <string>
+#include "db.h"
+
+namespace acme {
+namespace users {
+
+constexpr int MAX_USERS = 100;
+
+/// A stored user.
+template <typename T>
+class User : public Base, private Loggable {
+public:
+    explicit User(int id);
+    virtual ~User();
+    /** Saves the user. */
+    virtual void save() = 0;
+    int id() const { return id_; }
+    template <typename U> U as() const;
+    enum class Role { Admin = 1, Guest };
+private:
+    int id_;
+    std::string name_, email_;
+};
+
+using UserPtr = User<int>*;
+
+template <typename T> T clamp(T value, T lo, T hi);
+
+}  // namespace users
+}  // namespace acme
+"#;
+        let spec = plugin.extract(source, Path::new("user.hpp")).unwrap();
+        assert_eq!(spec.package, "acme::users");
+        assert_eq!(spec.imports, vec!["string", "db.h"]);
+        assert_eq!(spec.constants[0].name, "MAX_USERS");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("100"));
+
+        let names: Vec<(&str, &str)> =
+            spec.types.iter().map(|t| (t.name.as_str(), t.kind.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("User::Role enum", "enum"),
+                ("User class", "abstract_class"),
+                ("UserPtr type", "type_alias"),
+            ]
+        );
+        assert_eq!(spec.types[0].variants, vec!["Admin = 1", "Guest"]);
+
+        let user = &spec.types[1];
+        assert_eq!(user.doc.as_deref(), Some("A stored user."));
+        assert_eq!(user.type_params, vec!["typename T"]);
+        assert_eq!(user.embeds, vec!["Base", "Loggable"]);
+        assert_eq!(
+            user.fields,
+            vec!["private int id_", "private std::string name_", "private std::string email_"]
+        );
+
+        let methods: Vec<(&str, bool)> =
+            spec.methods.iter().map(|m| (m.signature.as_str(), m.declaration)).collect();
+        assert_eq!(
+            methods,
+            vec![
+                ("public explicit User(int id)", true),
+                ("public virtual ~User()", true),
+                ("public virtual void save() = 0", false),
+                ("public int id() const", false),
+                ("public template <typename U> U as() const", true),
+            ]
+        );
+        assert!(spec.methods.iter().all(|m| m.receiver.as_deref() == Some("User")));
+        assert_eq!(spec.methods[2].doc.as_deref(), Some("Saves the user."));
+
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(
+            spec.functions[0].signature,
+            "template <typename T> T clamp(T value, T lo, T hi)"
+        );
+    }
+
+    #[test]
+    fn test_pair_declarations_with_definitions() {
+        let header = r#"
+#ifndef USER_H
+#define USER_H
+class User {
+public:
+    void save();
+    void remove();
+};
+int user_count(void);
+int user_limit(void);
+#endif
+"#;
+        let source = r#"
+#include "user.h"
+
+static int helper(void);
+
+void User::save() { helper(); }
+
+int user_count(void) { return 0; }
+
+static int helper(void) { return 1; }
+"#;
+        let plugin = CppPlugin::new();
+        let mut specs = vec![
+            plugin.extract(source, Path::new("src/user.cpp")).unwrap(),
+            plugin.extract(header, Path::new("include/user.h")).unwrap(),
+        ];
+
+        // The forward declaration of `helper` is defined in the same file
+        assert_eq!(specs[0].functions.len(), 2);
+        assert_eq!(specs[0].methods[0].receiver.as_deref(), Some("User"));
+
+        plugin.link(&mut specs).unwrap();
+        assert_eq!(specs[0].resolved_imports["user.h"], "include/user.h");
+
+        let header = &specs[1];
+        let defined: Vec<(&str, Option<&str>)> = header
+            .methods
+            .iter()
+            .chain(header.functions.iter())
+            .map(|f| (f.signature.as_str(), f.defined_in.as_deref()))
+            .collect();
+        assert_eq!(
+            defined,
+            vec![
+                ("public void save()", Some("src/user.cpp")),
+                ("public void remove()", None),
+                ("int user_count(void)", Some("src/user.cpp")),
+                ("int user_limit(void)", None),
+            ]
+        );
+    }
+}
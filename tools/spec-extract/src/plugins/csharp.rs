@@ -0,0 +1,926 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::path::Path;
+use tree_sitter::Node;
+
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
+use crate::spec::{AttributeSpec, ConstSpec, FileSpec, FuncSpec, TypeSpec};
+
+/// Declarations that introduce a named type.
+const TYPE_DECLARATIONS: &[&str] = &[
+    "class_declaration",
+    "struct_declaration",
+    "interface_declaration",
+    "record_declaration",
+    "enum_declaration",
+    "delegate_declaration",
+];
+
+/// Nodes whose contents are implementation details.
+const BODY_KINDS: &[&str] = &["block", "arrow_expression_clause", "lambda_expression"];
+
+/// Modifiers that make up a declaration's accessibility, as in `protected internal`.
+const ACCESS_MODIFIERS: &[&str] = &["public", "protected", "internal", "private"];
+
+/// Awaitable return types that `async` signatures are normalized from.
+const TASK_TYPES: &[&str] = &["Task", "ValueTask"];
+
+/// Plugin for extracting specifications from C# source files.
+pub struct CSharpPlugin;
+
+impl CSharpPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The file-scoped namespace, or the first namespace block of the file.
+    fn extract_namespace(&self, root: Node, source: &str, path: &Path) -> String {
+        NodeHelper::child_by_kind(root, "file_scoped_namespace_declaration")
+            .or_else(|| NodeHelper::child_by_kind(root, "namespace_declaration"))
+            .and_then(|ns| NodeHelper::field_text(ns, "name", source))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| {
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown")
+                    .to_string()
+            })
+    }
+
+    fn extract_usings(&self, root: Node, source: &str) -> Vec<String> {
+        NodeHelper::find_all(root, "using_directive")
+            .into_iter()
+            .map(|using| {
+                let text = NodeHelper::text(using, source);
+                let text = text.trim_end_matches(';');
+                let text = text.strip_prefix("global ").unwrap_or(text);
+                text.trim_start_matches("using").split_whitespace().collect::<Vec<_>>().join(" ")
+            })
+            .collect()
+    }
+
+    fn extract_types(&self, root: Node, source: &str, package: &str) -> Vec<TypeSpec> {
+        let mut types = Vec::new();
+
+        for kind in TYPE_DECLARATIONS {
+            for node in NodeHelper::find_all(root, kind) {
+                if self.is_local(node) {
+                    continue;
+                }
+                if let Some(spec) = self.extract_type(node, source, package) {
+                    types.push((node.start_byte(), spec));
+                }
+            }
+        }
+
+        // Keep document order across declaration kinds
+        types.sort_by_key(|(start, _)| *start);
+        types.into_iter().map(|(_, spec)| spec).collect()
+    }
+
+    fn extract_type(&self, node: Node, source: &str, package: &str) -> Option<TypeSpec> {
+        let name = NodeHelper::field_text(node, "name", source)?;
+        let name = self.qualified_name(node, name, source, package);
+        let doc = self.doc_comment(node, source);
+
+        let modifiers = self.modifiers(node, source);
+        let kind = match node.kind() {
+            "class_declaration" if modifiers.contains(&"abstract") => "abstract_class",
+            "class_declaration" => "class",
+            "struct_declaration" => "struct",
+            "interface_declaration" => "interface",
+            "record_declaration" => "record",
+            "enum_declaration" => "enum",
+            _ => "delegate",
+        };
+
+        let type_params = NodeHelper::child_by_kind(node, "type_parameter_list")
+            .map(|params| {
+                NodeHelper::children_by_kind(params, "type_parameter")
+                    .into_iter()
+                    .map(|p| NodeHelper::text(p, source).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // C# lists the base class and interfaces together. By convention interface
+        // names are `I` + PascalCase, so everything else counts as a base type.
+        let mut embeds = Vec::new();
+        let mut interfaces = Vec::new();
+        if kind != "enum" {
+            if let Some(bases) = NodeHelper::child_by_kind(node, "base_list") {
+                let mut cursor = bases.walk();
+                for base in bases.named_children(&mut cursor) {
+                    let text = NodeHelper::text(base, source).to_string();
+                    if kind == "interface" || !is_interface_name(&text) {
+                        embeds.push(text);
+                    } else {
+                        interfaces.push(text);
+                    }
+                }
+            }
+        }
+
+        let mut attributes = self.attributes(node, source, "class", None);
+        let mut fields = Vec::new();
+        let mut methods = Vec::new();
+        let mut variants = Vec::new();
+
+        // Positional record parameters are its properties
+        if let Some(params) = NodeHelper::child_by_kind(node, "parameter_list") {
+            if kind == "record" {
+                fields.extend(self.parameter_list(params, source));
+            }
+        }
+        if kind == "delegate" {
+            methods.push(self.method_signature(node, source));
+        }
+
+        if let Some(body) = NodeHelper::field(node, "body") {
+            let mut cursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                match member.kind() {
+                    "field_declaration" if !self.modifiers(member, source).contains(&"const") => {
+                        for (name, field) in self.variable_members(member, source) {
+                            fields.push(field);
+                            attributes.extend(self.attributes(member, source, "field", Some(name)));
+                        }
+                    }
+                    "event_field_declaration" => {
+                        for (name, field) in self.variable_members(member, source) {
+                            fields.push(field);
+                            attributes.extend(self.attributes(member, source, "event", Some(name)));
+                        }
+                    }
+                    "property_declaration" | "event_declaration" | "indexer_declaration" => {
+                        // Indexers have no name
+                        let name = NodeHelper::field_text(member, "name", source).unwrap_or("this");
+                        fields.push(self.property_signature(member, source));
+                        let target = match member.kind() {
+                            "event_declaration" => "event",
+                            _ => "property",
+                        };
+                        let name = Some(name.to_string());
+                        attributes.extend(self.attributes(member, source, target, name));
+                    }
+                    "method_declaration" | "constructor_declaration" => {
+                        let method = NodeHelper::field_text(member, "name", source).unwrap_or("");
+                        let name = Some(method.to_string());
+                        attributes.extend(self.attributes(member, source, "method", name));
+                        if let Some(params) = NodeHelper::field(member, "parameters") {
+                            attributes.extend(self.parameter_attributes(params, method, source));
+                        }
+                        // Class methods become FileSpec methods; interfaces list signatures
+                        if kind == "interface" {
+                            methods.push(self.method_signature(member, source));
+                        }
+                    }
+                    "enum_member_declaration" => {
+                        variants.push(NodeHelper::text(member, source).to_string());
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let suffix = match kind {
+            "abstract_class" => "class",
+            other => other,
+        };
+
+        Some(TypeSpec {
+            name: format!("{} {}", name, suffix),
+            doc,
+            kind: kind.to_string(),
+            fields,
+            methods,
+            embeds,
+            implements: if interfaces.is_empty() { None } else { Some(interfaces.join(", ")) },
+            type_params,
+            variants,
+            attributes,
+            // Nested types are private by default, top-level ones internal
+            visibility: match self.enclosing_type(node) {
+                Some(_) => self.visibility(node, source, "private"),
+                None => self.visibility(node, source, "internal"),
+            },
+            ..Default::default()
+        })
+    }
+
+    fn extract_methods(&self, root: Node, source: &str, package: &str) -> Vec<FuncSpec> {
+        let mut methods = Vec::new();
+
+        for kind in ["method_declaration", "constructor_declaration"] {
+            for node in NodeHelper::find_all(root, kind) {
+                let owner = match self.enclosing_type(node) {
+                    Some(owner) => owner,
+                    None => continue,
+                };
+                // Interface members are listed on the type itself
+                if owner.kind() == "interface_declaration" {
+                    continue;
+                }
+
+                let receiver = NodeHelper::field_text(owner, "name", source)
+                    .map(|name| self.qualified_name(owner, name, source, package));
+
+                methods.push((
+                    node.start_byte(),
+                    FuncSpec {
+                        signature: self.method_signature(node, source),
+                        doc: self.doc_comment(node, source),
+                        receiver,
+                        visibility: self.visibility(node, source, "private"),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+
+        methods.sort_by_key(|(start, _)| *start);
+        methods.into_iter().map(|(_, spec)| spec).collect()
+    }
+
+    /// `[modifiers] ReturnType Name<T>(params)`, with awaitable returns normalized
+    /// so `Task<User> FindAsync()` and `async Task<User> FindAsync()` both read
+    /// `async User FindAsync()`, and `Task SaveAsync()` reads `async Task SaveAsync()`.
+    fn method_signature(&self, node: Node, source: &str) -> String {
+        let mut modifiers: Vec<&str> = self.modifiers(node, source);
+        let return_type = NodeHelper::field_text(node, "returns", source)
+            .or_else(|| NodeHelper::field_text(node, "type", source));
+
+        let return_type = match return_type.and_then(awaited_type) {
+            Some(awaited) => {
+                modifiers.retain(|m| *m != "async");
+                modifiers.push("async");
+                Some(awaited)
+            }
+            None => return_type.map(|t| t.to_string()),
+        };
+
+        let mut parts: Vec<String> = modifiers.iter().map(|m| m.to_string()).collect();
+        if node.kind() == "delegate_declaration" {
+            parts.push("delegate".to_string());
+        }
+        parts.extend(return_type);
+
+        let name = NodeHelper::field_text(node, "name", source).unwrap_or("");
+        let type_params = NodeHelper::child_by_kind(node, "type_parameter_list")
+            .map(|n| NodeHelper::text(n, source))
+            .unwrap_or("");
+        let params = NodeHelper::field(node, "parameters")
+            .map(|p| self.parameter_list(p, source).join(", "))
+            .unwrap_or_default();
+        parts.push(format!("{}{}({})", name, type_params, params));
+
+        parts.join(" ")
+    }
+
+    /// `[modifiers] Type Name { get; set; }` for properties, events and indexers.
+    fn property_signature(&self, node: Node, source: &str) -> String {
+        let mut parts: Vec<String> =
+            self.modifiers(node, source).iter().map(|m| m.to_string()).collect();
+        if node.kind() == "event_declaration" {
+            parts.push("event".to_string());
+        }
+        parts.extend(NodeHelper::field_text(node, "type", source).map(|t| t.to_string()));
+
+        match node.kind() {
+            "indexer_declaration" => {
+                let params = NodeHelper::field(node, "parameters")
+                    .map(|p| self.parameter_list(p, source).join(", "))
+                    .unwrap_or_default();
+                parts.push(format!("this[{}]", params));
+            }
+            _ => parts.extend(NodeHelper::field_text(node, "name", source).map(|n| n.to_string())),
+        }
+
+        let accessors: Vec<String> = match NodeHelper::field(node, "accessors") {
+            Some(list) => NodeHelper::children_by_kind(list, "accessor_declaration")
+                .into_iter()
+                .map(|accessor| {
+                    // Drop accessor bodies: `private set { ... }` -> `private set`
+                    let end = NodeHelper::field(accessor, "body")
+                        .map(|b| b.start_byte())
+                        .unwrap_or(accessor.end_byte());
+                    let mut cursor = accessor.walk();
+                    let start = accessor
+                        .children(&mut cursor)
+                        .find(|c| c.kind() != "attribute_list")
+                        .map(|c| c.start_byte())
+                        .unwrap_or(accessor.start_byte());
+                    source[start..end].trim().trim_end_matches(';').trim().to_string()
+                })
+                .collect(),
+            // Expression-bodied members are read-only
+            None if node.kind() != "event_declaration" => vec!["get".to_string()],
+            None => Vec::new(),
+        };
+
+        if !accessors.is_empty() && node.kind() != "event_declaration" {
+            parts.push(format!("{{ {}; }}", accessors.join("; ")));
+        }
+        parts.join(" ")
+    }
+
+    /// `(name, "[modifiers] Type name")` for each variable of a field or event field.
+    fn variable_members(&self, node: Node, source: &str) -> Vec<(String, String)> {
+        let mut prefix: Vec<&str> = self.modifiers(node, source);
+        if node.kind() == "event_field_declaration" {
+            prefix.push("event");
+        }
+
+        let declaration = match NodeHelper::child_by_kind(node, "variable_declaration") {
+            Some(d) => d,
+            None => return Vec::new(),
+        };
+        let type_name = NodeHelper::field_text(declaration, "type", source).unwrap_or("");
+
+        NodeHelper::children_by_kind(declaration, "variable_declarator")
+            .into_iter()
+            .filter_map(|declarator| NodeHelper::field_text(declarator, "name", source))
+            .map(|name| {
+                let mut parts = prefix.clone();
+                parts.push(type_name);
+                parts.push(name);
+                (name.to_string(), parts.join(" "))
+            })
+            .collect()
+    }
+
+    /// Parameters as written, minus attributes.
+    fn parameter_list(&self, params: Node, source: &str) -> Vec<String> {
+        let mut cursor = params.walk();
+        params
+            .named_children(&mut cursor)
+            .filter(|p| p.kind() == "parameter")
+            .map(|param| {
+                let mut inner = param.walk();
+                let start = param
+                    .children(&mut inner)
+                    .find(|c| c.kind() != "attribute_list")
+                    .map(|c| c.start_byte())
+                    .unwrap_or(param.start_byte());
+                source[start..param.end_byte()].to_string()
+            })
+            .collect()
+    }
+
+    fn parameter_attributes(&self, params: Node, method: &str, source: &str) -> Vec<AttributeSpec> {
+        let mut attributes = Vec::new();
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            let name = NodeHelper::field_text(param, "name", source).unwrap_or("");
+            let member = Some(format!("{}.{}", method, name));
+            attributes.extend(self.attributes(param, source, "parameter", member));
+        }
+        attributes
+    }
+
+    /// `[Name(args)]` attribute lists on a declaration. Target-specific lists
+    /// (`[return: ...]`) are skipped.
+    fn attributes(
+        &self,
+        node: Node,
+        source: &str,
+        target: &str,
+        member: Option<String>,
+    ) -> Vec<AttributeSpec> {
+        let mut attributes = Vec::new();
+
+        for list in NodeHelper::children_by_kind(node, "attribute_list") {
+            if NodeHelper::has_child_kind(list, "attribute_target_specifier") {
+                continue;
+            }
+            for attribute in NodeHelper::children_by_kind(list, "attribute") {
+                let name = NodeHelper::field_text(attribute, "name", source).unwrap_or("");
+                let args = NodeHelper::child_by_kind(attribute, "attribute_argument_list")
+                    .map(|list| {
+                        NodeHelper::children_by_kind(list, "attribute_argument")
+                            .into_iter()
+                            .map(|arg| {
+                                let text = NodeHelper::text(arg, source);
+                                let value = arg.named_child(0).map(|v| v.kind());
+                                if value == Some("string_literal") {
+                                    text.trim_matches('"').to_string()
+                                } else {
+                                    text.to_string()
+                                }
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                attributes.push(AttributeSpec {
+                    name: name.to_string(),
+                    args,
+                    target: target.to_string(),
+                    member: member.clone(),
+                });
+            }
+        }
+
+        attributes
+    }
+
+    fn modifiers<'a>(&self, node: Node<'a>, source: &'a str) -> Vec<&'a str> {
+        NodeHelper::children_by_kind(node, "modifier")
+            .into_iter()
+            .map(|m| NodeHelper::text(m, source))
+            .collect()
+    }
+
+    /// The access modifiers of `node`, as in `protected internal`, or `default` without any.
+    fn visibility(&self, node: Node, source: &str, default: &str) -> Option<String> {
+        let access: Vec<&str> = self
+            .modifiers(node, source)
+            .into_iter()
+            .filter(|m| ACCESS_MODIFIERS.contains(m))
+            .collect();
+        if access.is_empty() {
+            Some(default.to_string())
+        } else {
+            Some(access.join(" "))
+        }
+    }
+
+    fn extract_constants(&self, root: Node, source: &str, package: &str) -> Vec<ConstSpec> {
+        let mut constants = Vec::new();
+
+        for node in NodeHelper::find_all(root, "field_declaration") {
+            if !self.modifiers(node, source).contains(&"const") {
+                continue;
+            }
+            let owner = match self.enclosing_type(node) {
+                Some(owner) => owner,
+                None => continue,
+            };
+            let owner_name = NodeHelper::field_text(owner, "name", source)
+                .map(|name| self.qualified_name(owner, name, source, package))
+                .unwrap_or_default();
+            let member_access = match owner.kind() {
+                "interface_declaration" => "public",
+                _ => "private",
+            };
+
+            let declaration = match NodeHelper::child_by_kind(node, "variable_declaration") {
+                Some(d) => d,
+                None => continue,
+            };
+            let type_name =
+                NodeHelper::field_text(declaration, "type", source).map(|t| t.to_string());
+            let doc = self.doc_comment(node, source);
+
+            for declarator in NodeHelper::children_by_kind(declaration, "variable_declarator") {
+                let name = match NodeHelper::field_text(declarator, "name", source) {
+                    Some(name) => name,
+                    None => continue,
+                };
+                // The initializer is the declarator's last named child
+                let value = declarator
+                    .named_child(declarator.named_child_count().saturating_sub(1))
+                    .filter(|v| Some(*v) != NodeHelper::field(declarator, "name"))
+                    .map(|v| NodeHelper::text(v, source).to_string());
+
+                constants.push(ConstSpec {
+                    name: format!("{}.{}", owner_name, name),
+                    type_name: type_name.clone(),
+                    value,
+                    doc: doc.clone(),
+                    export: None,
+                    visibility: self.visibility(node, source, member_access),
+                });
+            }
+        }
+
+        constants
+    }
+
+    /// XML doc comments (`/// <summary>...`) as plain text.
+    fn doc_comment(&self, node: Node, source: &str) -> Option<String> {
+        let doc = NodeHelper::preceding_comment(node, source)?;
+        let text = strip_xml_doc(&doc);
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+
+    /// Prefix a type name with its enclosing types, and with its namespace when
+    /// that differs from the file's namespace.
+    fn qualified_name(&self, node: Node, name: &str, source: &str, package: &str) -> String {
+        let mut parts = vec![name.to_string()];
+        let mut namespaces = Vec::new();
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if TYPE_DECLARATIONS.contains(&parent.kind()) {
+                parts.extend(NodeHelper::field_text(parent, "name", source).map(|n| n.to_string()));
+            } else if parent.kind() == "namespace_declaration" {
+                namespaces.extend(NodeHelper::field_text(parent, "name", source));
+            }
+            current = parent.parent();
+        }
+
+        // Namespaces nested in a file-scoped namespace continue it
+        let file_scoped =
+            NodeHelper::child_by_kind(node_root(node), "file_scoped_namespace_declaration");
+        if let Some(root_ns) = file_scoped {
+            namespaces.extend(NodeHelper::field_text(root_ns, "name", source));
+        }
+        namespaces.reverse();
+        let namespace = namespaces.join(".");
+        let relative = namespace
+            .strip_prefix(package)
+            .map(|rest| rest.trim_start_matches('.'))
+            .unwrap_or(&namespace);
+        if !relative.is_empty() {
+            parts.push(relative.to_string());
+        }
+
+        parts.reverse();
+        parts.join(".")
+    }
+
+    fn enclosing_type<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if BODY_KINDS.contains(&parent.kind()) {
+                return None;
+            }
+            if TYPE_DECLARATIONS.contains(&parent.kind()) {
+                return Some(parent);
+            }
+            current = parent.parent();
+        }
+        None
+    }
+
+    /// Types declared inside method bodies are not part of the API.
+    fn is_local(&self, node: Node) -> bool {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if BODY_KINDS.contains(&parent.kind()) {
+                return true;
+            }
+            current = parent.parent();
+        }
+        false
+    }
+}
+
+fn node_root(node: Node) -> Node {
+    let mut current = node;
+    while let Some(parent) = current.parent() {
+        current = parent;
+    }
+    current
+}
+
+/// Whether a base type follows the `IName` interface naming convention.
+fn is_interface_name(name: &str) -> bool {
+    let name = name.rsplit('.').next().unwrap_or(name);
+    let mut chars = name.chars();
+    chars.next() == Some('I') && chars.next().is_some_and(|c| c.is_ascii_uppercase())
+}
+
+/// `T` for `Task<T>`/`ValueTask<T>`. A bare `Task`/`ValueTask` stays as it is so the
+/// signature is not mistaken for an `async void` one.
+fn awaited_type(return_type: &str) -> Option<String> {
+    let return_type = return_type.trim();
+    let unqualified = return_type.strip_prefix("System.Threading.Tasks.").unwrap_or(return_type);
+    for task in TASK_TYPES {
+        if unqualified == *task {
+            return Some(task.to_string());
+        }
+        if let Some(inner) = unqualified.strip_prefix(task).and_then(|r| r.strip_prefix('<')) {
+            return inner.strip_suffix('>').map(|t| t.trim().to_string());
+        }
+    }
+    None
+}
+
+/// Turn XML doc markup into text: `<summary>` content is kept, `<param name="x">`
+/// and `<returns>` become `@param x` / `@returns` lines, and `<see cref="X"/>` becomes `X`.
+fn strip_xml_doc(doc: &str) -> String {
+    let mut out = String::new();
+    let mut rest = doc;
+
+    while let Some(open) = rest.find('<') {
+        out.push_str(&rest[..open]);
+        let close = match rest[open..].find('>') {
+            Some(close) => open + close,
+            None => {
+                rest = &rest[open..];
+                break;
+            }
+        };
+        let tag = &rest[open + 1..close];
+        let name = tag.split_whitespace().next().unwrap_or("").trim_end_matches('/');
+        let attr = tag
+            .split_once('"')
+            .and_then(|(_, value)| value.split_once('"'))
+            .map(|(value, _)| value.rsplit(':').next().unwrap_or(value));
+
+        match (name, attr) {
+            ("param", Some(param)) => out.push_str(&format!("\n@param {} ", param)),
+            ("returns", _) => out.push_str("\n@returns "),
+            ("exception", Some(exception)) => out.push_str(&format!("\n@throws {} ", exception)),
+            ("see" | "seealso" | "paramref" | "typeparamref", Some(reference)) => {
+                out.push_str(reference)
+            }
+            _ => {}
+        }
+        rest = &rest[close + 1..];
+    }
+    out.push_str(rest);
+
+    out.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Merge `partial` declarations of the same type spread over several files into
+/// the first file that declares it.
+fn merge_partials(specs: &mut [FileSpec], indices: &[usize]) {
+    let mut seen: BTreeMap<(String, String), (usize, usize)> = BTreeMap::new();
+    let mut merged: Vec<(usize, usize, TypeSpec)> = Vec::new();
+
+    for &idx in indices {
+        for (ty_idx, ty) in specs[idx].types.iter().enumerate() {
+            let key = (specs[idx].package.clone(), ty.name.clone());
+            match seen.get(&key) {
+                Some(&(first, _)) if first == idx => {}
+                Some(&(first, first_ty)) => merged.push((first, first_ty, ty.clone())),
+                None => {
+                    seen.insert(key, (idx, ty_idx));
+                }
+            }
+        }
+    }
+
+    for (file, ty_idx, part) in &merged {
+        let base = &mut specs[*file].types[*ty_idx];
+        if base.doc.is_none() {
+            base.doc = part.doc.clone();
+        }
+        for (list, extra) in [
+            (&mut base.fields, &part.fields),
+            (&mut base.methods, &part.methods),
+            (&mut base.embeds, &part.embeds),
+            (&mut base.type_params, &part.type_params),
+        ] {
+            for item in extra {
+                if !list.contains(item) {
+                    list.push(item.clone());
+                }
+            }
+        }
+        for attribute in &part.attributes {
+            if !base.attributes.contains(attribute) {
+                base.attributes.push(attribute.clone());
+            }
+        }
+        base.implements = match (base.implements.take(), &part.implements) {
+            (Some(a), Some(b)) if a != *b => Some(format!("{}, {}", a, b)),
+            (a, b) => a.or(b.clone()),
+        };
+    }
+
+    // Drop the parts that were folded into their first declaration
+    for &idx in indices {
+        let package = specs[idx].package.clone();
+        let mut ty_idx = 0;
+        specs[idx].types.retain(|ty| {
+            let (first, first_ty) = seen[&(package.clone(), ty.name.clone())];
+            let keep = first == idx && first_ty == ty_idx;
+            ty_idx += 1;
+            keep
+        });
+    }
+}
+
+impl Default for CSharpPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for CSharpPlugin {
+    fn name(&self) -> &'static str {
+        "csharp"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["cs"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let language = tree_sitter_c_sharp::LANGUAGE;
+        let mut parser = TreeSitterParser::new(language.into())?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+
+        let package = self.extract_namespace(root, source, path);
+
+        Ok(FileSpec {
+            file: path.to_string_lossy().to_string(),
+            imports: self.extract_usings(root, source),
+            types: self.extract_types(root, source, &package),
+            methods: self.extract_methods(root, source, &package),
+            constants: self.extract_constants(root, source, &package),
+            package,
+            ..Default::default()
+        })
+    }
+
+    fn link(&self, specs: &mut Vec<FileSpec>) -> Result<()> {
+        let mut indices: Vec<usize> = (0..specs.len())
+            .filter(|&i| self.can_handle(Path::new(&specs[i].file)))
+            .collect();
+        // Deterministic "first file" regardless of walk order
+        indices.sort_by(|&a, &b| specs[a].file.cmp(&specs[b].file));
+
+        merge_partials(specs, &indices);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_class() {
+        let plugin = CSharpPlugin::new();
+        let source = r#"
+using System;
+using static System.Math;
+
+namespace Acme.Users;
+
+/// <summary>Serves users.</summary>
+[ApiController, Route("api/users")]
+public class UserService : ServiceBase, IUserService
+{
+    public const int Max = 10;
+    private readonly IRepo _repo;
+    public string Name { get; private set; }
+    public event EventHandler<UserEventArgs> Changed;
+
+    /// <summary>Finds a user.</summary>
+    /// <param name="id">The user id.</param>
+    [HttpGet("{id}")]
+    public async Task<User> FindAsync([FromRoute] int id, CancellationToken ct = default)
+    {
+        return await _repo.Find(id, ct);
+    }
+
+    public async Task SaveAsync() {}
+}
+"#;
+        let spec = plugin.extract(source, Path::new("UserService.cs")).unwrap();
+        assert_eq!(spec.package, "Acme.Users");
+        assert_eq!(spec.imports, vec!["System", "static System.Math"]);
+
+        let class = &spec.types[0];
+        assert_eq!(class.name, "UserService class");
+        assert_eq!(class.doc.as_deref(), Some("Serves users."));
+        assert_eq!(class.embeds, vec!["ServiceBase"]);
+        assert_eq!(class.implements.as_deref(), Some("IUserService"));
+        assert_eq!(
+            class.fields,
+            vec![
+                "private readonly IRepo _repo",
+                "public string Name { get; private set; }",
+                "public event EventHandler<UserEventArgs> Changed",
+            ]
+        );
+
+        let attrs: Vec<(&str, &str, Option<&str>)> = class
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.target.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                ("ApiController", "class", None),
+                ("Route", "class", None),
+                ("HttpGet", "method", Some("FindAsync")),
+                ("FromRoute", "parameter", Some("FindAsync.id")),
+            ]
+        );
+        assert_eq!(class.attributes[1].args, vec!["api/users"]);
+
+        let signatures: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "public async User FindAsync(int id, CancellationToken ct = default)",
+                "public async Task SaveAsync()",
+            ]
+        );
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("UserService"));
+        assert_eq!(spec.methods[0].doc.as_deref(), Some("Finds a user.\n@param id The user id."));
+
+        assert_eq!(spec.constants[0].name, "UserService.Max");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("10"));
+        assert_eq!(spec.constants[0].visibility.as_deref(), Some("public"));
+        assert_eq!(class.visibility.as_deref(), Some("public"));
+        assert_eq!(spec.methods[1].visibility.as_deref(), Some("public"));
+    }
+
+    #[test]
+    fn test_extract_other_types() {
+        let plugin = CSharpPlugin::new();
+        let source = r#"
+namespace Acme.Shapes
+{
+    public interface IShape : IComparable<IShape>
+    {
+        Task<double> AreaAsync();
+        string Name { get; }
+    }
+
+    public record Point(int X, int Y) : IShape;
+    public struct Vec { public double X; }
+    public enum Color : byte { Red = 1, Green }
+
+    namespace Internal
+    {
+        class Cache { class Entry {} }
+    }
+}
+"#;
+        let spec = plugin.extract(source, Path::new("Shapes.cs")).unwrap();
+        assert_eq!(spec.package, "Acme.Shapes");
+
+        let names: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "IShape interface",
+                "Point record",
+                "Vec struct",
+                "Color enum",
+                "Internal.Cache class",
+                "Internal.Cache.Entry class",
+            ]
+        );
+        assert_eq!(spec.types[0].embeds, vec!["IComparable<IShape>"]);
+        assert_eq!(spec.types[0].methods, vec!["async double AreaAsync()"]);
+        assert_eq!(spec.types[0].fields, vec!["string Name { get; }"]);
+        assert_eq!(spec.types[1].fields, vec!["int X", "int Y"]);
+        assert_eq!(spec.types[1].implements.as_deref(), Some("IShape"));
+        assert_eq!(spec.types[3].variants, vec!["Red = 1", "Green"]);
+
+        // Top-level types default to internal, nested types to private
+        let visibilities: Vec<Option<&str>> =
+            spec.types.iter().map(|t| t.visibility.as_deref()).collect();
+        assert_eq!(
+            visibilities,
+            vec![
+                Some("public"),
+                Some("public"),
+                Some("public"),
+                Some("public"),
+                Some("internal"),
+                Some("private"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_link_merges_partial_classes() {
+        let plugin = CSharpPlugin::new();
+        let files = [
+            (
+                "src/User.cs",
+                "namespace App;\n/// <summary>A user.</summary>\n\
+                 public partial class User { public string Name { get; set; } }",
+            ),
+            (
+                "src/User.Validation.cs",
+                "namespace App;\n\
+                 public partial class User : IValidatable { public bool Validate() => true; }",
+            ),
+        ];
+        let mut specs: Vec<FileSpec> = files
+            .iter()
+            .map(|(path, source)| plugin.extract(source, Path::new(path)).unwrap())
+            .collect();
+        plugin.link(&mut specs).unwrap();
+
+        // "src/User.Validation.cs" sorts before "src/User.cs", so it keeps the type
+        assert!(specs[0].types.is_empty());
+        let merged = &specs[1].types[0];
+        assert_eq!(merged.doc.as_deref(), Some("A user."));
+        assert_eq!(merged.fields, vec!["public string Name { get; set; }"]);
+        assert_eq!(merged.implements.as_deref(), Some("IValidatable"));
+        assert_eq!(specs[1].methods[0].receiver.as_deref(), Some("User"));
+    }
+}
@@ -0,0 +1,857 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::lexer::{
+    is_modifier, parse_header, qualify, split_top_level, squash, strip_default, unquote, Header,
+    LexOptions, Param, Scope, TokenCursor, TokenKind,
+};
+use super::sealed::link_sealed_variants;
+use crate::parser::LanguagePlugin;
+use crate::spec::{ConstSpec, ExportSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+
+/// Keywords that may precede a declaration.
+const MODIFIERS: &[&str] = &[
+    "abstract", "base", "interface", "final", "sealed", "mixin", "static", "const", "late",
+    "external", "covariant", "factory", "var",
+];
+
+/// Plugin for extracting specifications from Dart source files.
+///
+/// Like the Kotlin plugin this walks declarations over the [`super::lexer`] token
+/// stream. Classes annotated with `@freezed` are described by their factory
+/// constructors: the unnamed one declares the fields and named ones the union variants.
+pub struct DartPlugin;
+
+impl DartPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn lex_options() -> LexOptions {
+        LexOptions {
+            doc_prefixes: &["///", "/**"],
+            single_quote_strings: true,
+            operators: &["...?", "?..", "...", "??=", "==", "!=", "<=", "=>", "??", "?.", ".."],
+            ..LexOptions::default()
+        }
+    }
+
+    /// Parse declarations until the closing brace of `scope` (or the end of input).
+    fn parse_members(&self, cursor: &mut TokenCursor, scope: Option<&Scope>, spec: &mut FileSpec) {
+        while !cursor.is_eof() {
+            match cursor.peek_text() {
+                "}" if scope.is_some() => return,
+                "(" | "[" | "{" => {
+                    cursor.skip_balanced();
+                    continue;
+                }
+                ";" => {
+                    cursor.next();
+                    continue;
+                }
+                _ => {}
+            }
+
+            let header = self.parse_header(cursor);
+            match cursor.peek_text() {
+                "library" if scope.is_none() => {
+                    cursor.next();
+                    spec.package = cursor.dotted_name().unwrap_or_default();
+                    cursor.eat(";");
+                }
+                "import" | "export" | "part" if scope.is_none() => {
+                    self.parse_directive(cursor, spec);
+                }
+                "class" | "mixin" | "enum" | "extension" if scope.is_none() => {
+                    self.parse_class(cursor, header, spec);
+                }
+                "typedef" if scope.is_none() => self.parse_typedef(cursor, header, spec),
+                _ => {
+                    let start = cursor.position();
+                    self.parse_member(cursor, header, scope, spec);
+                    if cursor.position() == start {
+                        // Statements and anything else we do not model
+                        cursor.next();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parse annotations and modifiers, keeping the doc comment in front of them.
+    fn parse_header(&self, cursor: &mut TokenCursor) -> Header {
+        parse_header(cursor, |cursor, offset| self.is_modifier(cursor, offset))
+    }
+
+    /// Whether the token at `offset` is used as a modifier rather than a name.
+    fn is_modifier(&self, cursor: &TokenCursor, offset: usize) -> bool {
+        let text = match cursor.peek_at(offset) {
+            Some(token) if MODIFIERS.contains(&token.text) => token.text,
+            _ => return false,
+        };
+        let next = cursor.peek_at(offset + 1);
+        match text {
+            // `mixin M on Base` declares a mixin, `mixin class M` is a modifier
+            "mixin" | "interface" => next.is_some_and(|t| t.text == "class"),
+            "base" => next.is_some_and(|t| matches!(t.text, "class" | "mixin")),
+            _ => is_modifier(cursor, offset, MODIFIERS),
+        }
+    }
+
+    /// `import 'package:http/http.dart' as http;`, `export 'src/user.dart' show User;`
+    fn parse_directive(&self, cursor: &mut TokenCursor, spec: &mut FileSpec) {
+        let keyword = cursor.next().map(|t| t.text).unwrap_or("");
+        if cursor.eat("of") {
+            // `part of 'library.dart';`
+            self.skip_statement(cursor);
+            return;
+        }
+        let uri = cursor.next().map(|t| unquote(t.text)).unwrap_or_default();
+        let start = cursor.position();
+        while !cursor.is_eof() && !cursor.at(";") {
+            cursor.next();
+        }
+        let rest = squash(cursor.text(start, cursor.position()));
+        cursor.eat(";");
+
+        match keyword {
+            "import" if rest.is_empty() => spec.imports.push(uri),
+            "import" => spec.imports.push(format!("{} {}", uri, rest)),
+            "export" => {
+                let shown = rest
+                    .split_once("show ")
+                    .map(|(_, names)| names.split(" hide ").next().unwrap_or(names));
+                let names: Vec<String> = match shown {
+                    Some(names) => split_top_level(names),
+                    None => vec!["*".to_string()],
+                };
+                spec.exports.extend(names.into_iter().map(|name| ExportSpec {
+                    name,
+                    local: None,
+                    from: Some(uri.clone()),
+                }));
+            }
+            _ => {}
+        }
+    }
+
+    /// Skip up to and including the `;` ending a statement, or up to an unbalanced `}`.
+    fn skip_statement(&self, cursor: &mut TokenCursor) {
+        while let Some(token) = cursor.peek() {
+            match token.text {
+                ";" => {
+                    cursor.next();
+                    return;
+                }
+                "}" | ")" | "]" => return,
+                "(" | "[" | "{" => {
+                    cursor.skip_balanced();
+                }
+                _ => {
+                    cursor.next();
+                }
+            }
+        }
+    }
+
+    /// Read a type up to the next delimiter or clause keyword.
+    fn parse_type(&self, cursor: &mut TokenCursor) -> String {
+        let start = cursor.position();
+        while let Some(token) = cursor.peek() {
+            let stop = matches!(
+                token.text,
+                "{" | "}" | ";" | "," | "=" | "with" | "implements" | "on" | "extends"
+            );
+            if stop {
+                break;
+            }
+            if matches!(token.text, "<" | "(") {
+                cursor.skip_balanced();
+            } else {
+                cursor.next();
+            }
+        }
+        squash(cursor.text(start, cursor.position()))
+    }
+
+    /// A comma-separated list of types (`with A, B`).
+    fn parse_type_list(&self, cursor: &mut TokenCursor) -> Vec<String> {
+        let mut types = vec![self.parse_type(cursor)];
+        while cursor.eat(",") {
+            types.push(self.parse_type(cursor));
+        }
+        types.retain(|t| !t.is_empty());
+        types
+    }
+
+    /// `<T extends Comparable<T>, R>` as a list of parameters.
+    fn type_params(&self, cursor: &mut TokenCursor) -> Vec<String> {
+        if !cursor.at("<") {
+            return Vec::new();
+        }
+        let text = cursor.skip_balanced();
+        split_top_level(&text[1..text.len().saturating_sub(1)])
+            .iter()
+            .map(|p| squash(p))
+            .collect()
+    }
+
+    /// Parse a parenthesized parameter list, returning it as written and its parameters.
+    ///
+    /// Named (`{...}`) and optional positional (`[...]`) groups keep their brackets in
+    /// the text but are flattened into the parameters.
+    fn parse_params(&self, cursor: &mut TokenCursor) -> (String, Vec<Param>) {
+        let text = cursor.skip_balanced();
+        let inner = &text[1..text.len().saturating_sub(1)];
+        let options = Self::lex_options();
+
+        let mut params = Vec::new();
+        let mut parts = Vec::new();
+        for part in split_top_level(inner) {
+            let (open, close, body) = match part.chars().next() {
+                Some('{') => ("{", "}", &part[1..part.len().saturating_sub(1)]),
+                Some('[') => ("[", "]", &part[1..part.len().saturating_sub(1)]),
+                _ => ("", "", part.as_str()),
+            };
+            let mut texts = Vec::new();
+            for param in split_top_level(body) {
+                let mut tokens = TokenCursor::new(&param, &options);
+                let header = self.parse_header(&mut tokens);
+                let start = tokens.peek().map(|t| t.start).unwrap_or(param.len());
+                let text = squash(&param[start..]);
+                let declared = strip_default(&text, true);
+                let name = declared
+                    .rsplit(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                    .next()
+                    .unwrap_or("")
+                    .to_string();
+                params.push(Param {
+                    annotations: header.attributes("parameter", None),
+                    name,
+                    property: false,
+                    text: text.clone(),
+                });
+                texts.push(text);
+            }
+            parts.push(format!("{}{}{}", open, texts.join(", "), close));
+        }
+
+        (format!("({})", parts.join(", ")), params)
+    }
+
+    fn parse_class(&self, cursor: &mut TokenCursor, header: Header, spec: &mut FileSpec) {
+        let keyword = cursor.next().map(|t| t.text).unwrap_or("");
+        let extension_type = keyword == "extension" && cursor.eat("type");
+
+        let (kind, suffix) = match keyword {
+            "mixin" => ("mixin", "mixin"),
+            "enum" => ("enum", "enum"),
+            "extension" if extension_type => ("extension_type", "type"),
+            "extension" => ("extension", "extension"),
+            _ if header.annotated("freezed") => ("data_class", "class"),
+            _ if header.has("sealed") => ("sealed_class", "class"),
+            _ if header.has("interface") => ("interface", "interface"),
+            _ if header.has("abstract") => ("abstract_class", "class"),
+            _ => ("class", "class"),
+        };
+
+        // Extensions may be unnamed (`extension on String`)
+        let mut name = match cursor.peek() {
+            Some(t) if t.kind == TokenKind::Ident && t.text != "on" => {
+                cursor.ident().unwrap_or("").to_string()
+            }
+            _ => String::new(),
+        };
+        let type_params = self.type_params(cursor);
+
+        // The representation of an extension type is its only field
+        let mut fields = Vec::new();
+        if extension_type && cursor.at("(") {
+            let (_, params) = self.parse_params(cursor);
+            fields.extend(params.into_iter().map(|p| p.text));
+        }
+
+        let mut embeds = Vec::new();
+        let mut interfaces = Vec::new();
+        let mut extended = String::new();
+        loop {
+            match cursor.peek_text() {
+                "extends" => {
+                    cursor.next();
+                    embeds.push(self.parse_type(cursor));
+                }
+                "with" => {
+                    cursor.next();
+                    // Mixins such as freezed's `_$User` come from generated code
+                    let mixins = self.parse_type_list(cursor);
+                    embeds.extend(mixins.into_iter().filter(|m| !m.starts_with("_$")));
+                }
+                "implements" => {
+                    cursor.next();
+                    interfaces.extend(self.parse_type_list(cursor));
+                }
+                "on" if kind == "extension" => {
+                    cursor.next();
+                    extended = self.parse_type(cursor);
+                }
+                "on" => {
+                    cursor.next();
+                    embeds.extend(self.parse_type_list(cursor));
+                }
+                _ => break,
+            }
+        }
+
+        let receiver = if kind == "extension" {
+            extended.split('<').next().unwrap_or("").trim().to_string()
+        } else {
+            name.clone()
+        };
+        if name.is_empty() {
+            name = receiver.clone();
+        }
+        if !extended.is_empty() {
+            embeds.push(extended);
+        }
+
+        let index = spec.types.len();
+        spec.types.push(TypeSpec {
+            name: format!("{} {}", name, suffix),
+            attributes: header.attributes("class", None),
+            doc: header.doc,
+            kind: kind.to_string(),
+            fields,
+            embeds,
+            implements: Some(interfaces.join(", ")).filter(|s| !s.is_empty()),
+            type_params,
+            ..Default::default()
+        });
+
+        if cursor.eat("{") {
+            if kind == "enum" {
+                let variants = self.parse_enum_values(cursor);
+                spec.types[index].variants = variants;
+            }
+            let scope = Scope { index, name, kind, owner: receiver };
+            self.parse_members(cursor, Some(&scope), spec);
+            cursor.eat("}");
+        }
+    }
+
+    /// Enum values up to the `;` that separates them from members.
+    fn parse_enum_values(&self, cursor: &mut TokenCursor) -> Vec<String> {
+        let mut variants = Vec::new();
+        while !cursor.at("}") && !cursor.eat(";") {
+            self.parse_header(cursor);
+            let name = match cursor.ident() {
+                Some(name) => name,
+                None => break,
+            };
+            // `const Planet.giant(...)` style values name a constructor
+            let mut args = String::new();
+            if cursor.at("<") {
+                cursor.skip_balanced();
+            }
+            if cursor.at(".") {
+                cursor.next();
+                cursor.ident();
+            }
+            if cursor.at("(") {
+                args = squash(cursor.skip_balanced());
+            }
+            variants.push(format!("{}{}", name, args));
+            if !cursor.eat(",") {
+                cursor.eat(";");
+                break;
+            }
+        }
+        variants
+    }
+
+    /// `typedef Json = Map<String, dynamic>;` and `typedef void Callback(int x);`
+    fn parse_typedef(&self, cursor: &mut TokenCursor, header: Header, spec: &mut FileSpec) {
+        cursor.next();
+        let start = cursor.position();
+        let mut type_params = Vec::new();
+        while let Some(token) = cursor.peek() {
+            match token.text {
+                "=" | "(" | ";" => break,
+                "<" => type_params = self.type_params(cursor),
+                _ => {
+                    cursor.next();
+                }
+            }
+        }
+        let head = squash(cursor.text(start, cursor.position()));
+        let name = head
+            .split('<')
+            .next()
+            .and_then(|h| h.split_whitespace().last())
+            .unwrap_or("")
+            .to_string();
+        self.skip_statement(cursor);
+
+        spec.types.push(TypeSpec {
+            name: format!("{} type", name),
+            doc: header.doc,
+            kind: "type_alias".to_string(),
+            type_params,
+            ..Default::default()
+        });
+    }
+
+    /// Parse a field, variable, method, constructor, getter or setter.
+    fn parse_member(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        // The head ends at `(` for functions and at `=`, `;` or `,` for variables
+        let start = cursor.position();
+        let mut getter = false;
+        while let Some(token) = cursor.peek() {
+            match token.text {
+                "<" => {
+                    cursor.skip_balanced();
+                }
+                // `void Function(int) callback` is a function type, not a parameter list
+                "(" if cursor.position() > start
+                    && cursor.text(cursor.position() - 1, cursor.position()) == "Function" =>
+                {
+                    cursor.skip_balanced();
+                }
+                "(" | "=" | ";" | "," | "{" | "}" | "=>" => break,
+                "get" if cursor.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) => {
+                    cursor.next();
+                    cursor.next();
+                    getter = true;
+                    break;
+                }
+                "operator" => {
+                    cursor.next();
+                    while !cursor.is_eof() && !cursor.at("(") {
+                        cursor.next();
+                    }
+                    break;
+                }
+                _ => {
+                    cursor.next();
+                }
+            }
+        }
+        let head = squash(cursor.text(start, cursor.position()));
+        if head.is_empty() {
+            return;
+        }
+
+        if getter || cursor.at("(") {
+            self.parse_function(cursor, header, head, scope, spec);
+        } else {
+            self.parse_variables(cursor, header, head, scope, spec);
+        }
+    }
+
+    fn parse_function(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        head: String,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        let name = match head.find("operator") {
+            Some(i) => head[i..].to_string(),
+            None => head.rsplit(' ').next().unwrap_or(&head).to_string(),
+        };
+        let (params_text, params) = if cursor.at("(") {
+            self.parse_params(cursor)
+        } else {
+            (String::new(), Vec::new())
+        };
+
+        // Body, initializer list or redirection (`= _User;`)
+        while cursor.eat("async") || cursor.eat("sync") || cursor.eat("*") {}
+        let mut redirects = false;
+        if cursor.eat(":") {
+            while !cursor.is_eof() && !matches!(cursor.peek_text(), "{" | ";" | "=>" | "}") {
+                if matches!(cursor.peek_text(), "(" | "[") {
+                    cursor.skip_balanced();
+                } else {
+                    cursor.next();
+                }
+            }
+        }
+        if cursor.at("{") {
+            cursor.skip_balanced();
+        } else {
+            redirects = cursor.at("=");
+            self.skip_statement(cursor);
+        }
+
+        let signature = format!("{}{}{}", header.prefix(), head, params_text);
+        let scope = match scope {
+            Some(scope) => scope,
+            None => {
+                spec.functions.push(FuncSpec { signature, doc: header.doc, ..Default::default() });
+                return;
+            }
+        };
+
+        let owner = &mut spec.types[scope.index];
+        owner.attributes.extend(header.attributes("method", Some(name.clone())));
+        for param in &params {
+            let member = format!("{}.{}", name, param.name);
+            owner.attributes.extend(param.annotations.iter().cloned().map(|mut attr| {
+                attr.member = Some(member.clone());
+                attr
+            }));
+        }
+
+        // freezed: the unnamed factory lists the fields, named ones the variants
+        if scope.kind == "data_class" && header.has("factory") && redirects {
+            match name.strip_prefix(&format!("{}.", scope.name)) {
+                Some(variant) => owner.variants.push(format!("{}{}", variant, params_text)),
+                None => owner.fields.extend(params.iter().map(|p| field_of(&p.text))),
+            }
+            return;
+        }
+
+        if scope.kind == "interface" {
+            owner.methods.push(signature);
+        } else {
+            spec.methods.push(FuncSpec {
+                signature,
+                doc: header.doc,
+                receiver: Some(scope.owner.clone()),
+                ..Default::default()
+            });
+        }
+    }
+
+    /// `final String name;`, `static const max = 10;`, `int a = 1, b = 2;`
+    fn parse_variables(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        head: String,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        let (type_name, first) = match head.rsplit_once(' ') {
+            Some((ty, name)) => (Some(ty.to_string()), name.to_string()),
+            None => (None, head.clone()),
+        };
+
+        let mut name = first;
+        loop {
+            let value = if cursor.eat("=") {
+                let start = cursor.position();
+                while !cursor.is_eof() && !matches!(cursor.peek_text(), ";" | "," | "}") {
+                    if matches!(cursor.peek_text(), "(" | "[" | "{" | "<") {
+                        cursor.skip_balanced();
+                    } else {
+                        cursor.next();
+                    }
+                }
+                Some(squash(cursor.text(start, cursor.position())))
+            } else {
+                None
+            };
+
+            let constant = header.has("const") && (scope.is_none() || header.has("static"));
+            match scope {
+                _ if constant => spec.constants.push(ConstSpec {
+                    name: qualify(scope, &name),
+                    type_name: type_name.clone(),
+                    value,
+                    doc: header.doc.clone(),
+                    ..Default::default()
+                }),
+                Some(scope) => {
+                    let field = match &type_name {
+                        Some(ty) => format!("{}{} {}", header.prefix(), ty, name),
+                        None => format!("{}{}", header.prefix(), name),
+                    };
+                    let owner = &mut spec.types[scope.index];
+                    owner.fields.push(field);
+                    owner.attributes.extend(header.attributes("field", Some(name.clone())));
+                }
+                None => spec.variables.push(VarSpec {
+                    name: name.clone(),
+                    type_name: type_name.clone(),
+                    doc: header.doc.clone(),
+                    ..Default::default()
+                }),
+            }
+
+            if !cursor.eat(",") {
+                cursor.eat(";");
+                return;
+            }
+            match cursor.ident() {
+                Some(next) => name = next.to_string(),
+                None => return,
+            }
+        }
+    }
+}
+
+/// A freezed factory parameter as a field (`required String name` -> `String name`).
+fn field_of(param: &str) -> String {
+    let param = param.strip_prefix("required ").unwrap_or(param);
+    strip_default(param, true)
+}
+
+/// The package of a file under a pub package's `lib/` directory.
+fn pub_package(path: &Path) -> Option<String> {
+    let components: Vec<&str> = path.iter().filter_map(|c| c.to_str()).collect();
+    let lib = components.iter().rposition(|c| *c == "lib")?;
+    lib.checked_sub(1).map(|i| components[i].to_string())
+}
+
+impl Default for DartPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for DartPlugin {
+    fn name(&self) -> &'static str {
+        "dart"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["dart"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let mut cursor = TokenCursor::new(source, &Self::lex_options());
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        self.parse_members(&mut cursor, None, &mut spec);
+        // Dart has no primary constructors, so variants are plain subclass names
+        link_sealed_variants(&mut spec.types, &HashMap::new());
+
+        if spec.package.is_empty() {
+            spec.package = pub_package(path).unwrap_or_else(|| {
+                path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string()
+            });
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_classes_and_members() {
+        let plugin = DartPlugin::new();
+        let source = r#"
+import 'package:http/http.dart' as http show get;
+import '../db.dart';
+
+export 'src/repo.dart' show UserRepo;
+part 'user_service.g.dart';
+
+const defaultTimeout = Duration(seconds: 30);
+int counter = 0;
+
+/// Serves users.
+class UserService extends BaseService with Logging implements Disposable {
+  static const int maxUsers = 100;
+  final UserRepository _repo;
+
+  UserService(this._repo) : super('users');
+
+  /// Finds a user.
+  @override
+  Future<User?> find(String id, {bool fresh = false}) async {
+    return _repo.find(id);
+  }
+
+  String get name => 'users';
+  bool operator ==(Object other) => identical(this, other);
+}
+
+abstract interface class UserRepository {
+  Future<User?> find(String id);
+}
+
+extension StringX on String {
+  bool isBlank() => trim().isEmpty;
+}
+
+void main(List<String> args) {
+  print('hi');
+}
+"#;
+        let path = Path::new("users/lib/src/user_service.dart");
+        let spec = plugin.extract(source, path).unwrap();
+        assert_eq!(spec.package, "users");
+        assert_eq!(spec.imports, vec!["package:http/http.dart as http show get", "../db.dart"]);
+        assert_eq!(spec.exports[0].name, "UserRepo");
+        assert_eq!(spec.exports[0].from.as_deref(), Some("src/repo.dart"));
+
+        assert_eq!(spec.constants[0].name, "defaultTimeout");
+        assert_eq!(spec.constants[1].name, "UserService.maxUsers");
+        assert_eq!(spec.constants[1].value.as_deref(), Some("100"));
+        assert_eq!(spec.variables[0].name, "counter");
+        assert_eq!(spec.variables[0].type_name.as_deref(), Some("int"));
+
+        let class = &spec.types[0];
+        assert_eq!(class.name, "UserService class");
+        assert_eq!(class.doc.as_deref(), Some("Serves users."));
+        assert_eq!(class.embeds, vec!["BaseService", "Logging"]);
+        assert_eq!(class.implements.as_deref(), Some("Disposable"));
+        assert_eq!(class.fields, vec!["final UserRepository _repo"]);
+        assert_eq!(class.attributes[0].member.as_deref(), Some("find"));
+
+        let repo = &spec.types[1];
+        assert_eq!(repo.name, "UserRepository interface");
+        assert_eq!(repo.methods, vec!["Future<User?> find(String id)"]);
+        assert_eq!(spec.types[2].name, "StringX extension");
+
+        let methods: Vec<(&str, Option<&str>)> = spec
+            .methods
+            .iter()
+            .map(|m| (m.signature.as_str(), m.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                ("UserService(this._repo)", Some("UserService")),
+                ("Future<User?> find(String id, {bool fresh = false})", Some("UserService")),
+                ("String get name", Some("UserService")),
+                ("bool operator ==(Object other)", Some("UserService")),
+                ("bool isBlank()", Some("String")),
+            ]
+        );
+        assert_eq!(spec.methods[1].doc.as_deref(), Some("Finds a user."));
+        assert_eq!(spec.functions[0].signature, "void main(List<String> args)");
+    }
+
+    #[test]
+    fn test_extract_templates_annotations_and_generics() {
+        let plugin = DartPlugin::new();
+        let source = r#"
+const greeting = 'Hello, ${user?.name ?? 'guest'} {not a block}';
+const json = """{ "tags": [${tags.map((t) => '"$t"').join(',')}] }""";
+
+@JsonSerializable(
+  fieldRename: FieldRename.snake,
+  converters: [DateConverter()],
+)
+class Report {
+  final Map<String, List<Set<int>>> buckets;
+
+  Report(this.buckets);
+
+  @Deprecated(
+    'use group',
+  )
+  Future<Map<String, List<Row>>> index(List<MapEntry<String, Map<int, List<Row>>>> rows) async {
+    return {};
+  }
+
+  String label() => '${buckets.length}: ${buckets.keys.map((k) => '{$k}').join(', ')}';
+}
+"#;
+        let spec = plugin.extract(source, Path::new("lib/report.dart")).unwrap();
+        let constants: Vec<(&str, Option<&str>)> =
+            spec.constants.iter().map(|c| (c.name.as_str(), c.value.as_deref())).collect();
+        assert_eq!(
+            constants,
+            vec![
+                ("greeting", Some("'Hello, ${user?.name ?? 'guest'} {not a block}'")),
+                ("json", Some(r#""""{ "tags": [${tags.map((t) => '"$t"').join(',')}] }""""#)),
+            ]
+        );
+
+        let report = &spec.types[0];
+        assert_eq!(report.fields, vec!["final Map<String, List<Set<int>>> buckets"]);
+        let attrs: Vec<(&str, Option<&str>)> = report
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(attrs, vec![("JsonSerializable", None), ("Deprecated", Some("index"))]);
+        assert_eq!(
+            report.attributes[0].args,
+            vec!["fieldRename: FieldRename.snake", "converters: [DateConverter()]"]
+        );
+
+        let methods: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(
+            methods,
+            vec![
+                "Report(this.buckets)",
+                "Future<Map<String, List<Row>>> index(\
+                 List<MapEntry<String, Map<int, List<Row>>>> rows)",
+                "String label()",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_freezed_and_enums() {
+        let plugin = DartPlugin::new();
+        let source = r#"
+library acme.users;
+
+@freezed
+class User with _$User {
+  const factory User({
+    required String id,
+    @Default('anon') String name,
+  }) = _User;
+
+  factory User.fromJson(Map<String, dynamic> json) => _$UserFromJson(json);
+}
+
+@freezed
+sealed class Result with _$Result {
+  const factory Result.success(String data) = Success;
+  const factory Result.failure(Object error) = Failure;
+}
+
+mixin Logging on Object {}
+
+enum Planet implements Comparable<Planet> {
+  mercury(mass: 3.3),
+  venus(mass: 4.8);
+
+  const Planet({required this.mass});
+  final double mass;
+}
+
+typedef Json = Map<String, dynamic>;
+"#;
+        let spec = plugin.extract(source, Path::new("user.dart")).unwrap();
+        assert_eq!(spec.package, "acme.users");
+
+        let user = &spec.types[0];
+        assert_eq!(user.kind, "data_class");
+        assert!(user.embeds.is_empty());
+        assert_eq!(user.fields, vec!["String id", "String name"]);
+        assert_eq!(user.attributes[1].name, "Default");
+        assert_eq!(user.attributes[1].member.as_deref(), Some("User.name"));
+        assert_eq!(spec.methods[0].signature, "factory User.fromJson(Map<String, dynamic> json)");
+
+        assert_eq!(spec.types[1].variants, vec!["success(String data)", "failure(Object error)"]);
+        assert_eq!(spec.types[2].name, "Logging mixin");
+        assert_eq!(spec.types[2].embeds, vec!["Object"]);
+
+        let planet = &spec.types[3];
+        assert_eq!(planet.variants, vec!["mercury(mass: 3.3)", "venus(mass: 4.8)"]);
+        assert_eq!(planet.fields, vec!["final double mass"]);
+        assert_eq!(spec.types[4].name, "Json type");
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use super::lexer::split_top_level;
+
+/// Type information read from YARD (Ruby) or PHPDoc comments.
+///
+/// Works on comments already cleaned by `NodeHelper::clean_comment`, where each
+/// tag starts its own line.
+#[derive(Debug, Default)]
+pub struct DocTags {
+    params: HashMap<String, String>,
+    returns: Option<String>,
+    var: Option<String>,
+    raises: Vec<(String, Option<String>)>,
+}
+
+impl DocTags {
+    /// Parse YARD tags: `@param name [Type]`, `@return [Type]` and `@raise [Error]`.
+    ///
+    /// Type lists such as `[User, nil]` become unions (`User | nil`).
+    pub fn yard(doc: &str) -> Self {
+        let mut tags = Self::default();
+
+        for line in doc.lines() {
+            let line = line.trim();
+            if let Some(rest) = tag(line, &["@param"]) {
+                // Both `@param name [Type]` and `@param [Type] name` are valid
+                let parsed = match bracketed_type(rest) {
+                    Some((ty, rest)) => Some((ty, first_word(rest))),
+                    None => {
+                        let name = first_word(rest);
+                        bracketed_type(rest[name.len()..].trim_start()).map(|(ty, _)| (ty, name))
+                    }
+                };
+                if let Some((ty, name)) = parsed.filter(|(_, name)| !name.is_empty()) {
+                    tags.params.insert(name.to_string(), yard_union(&ty));
+                }
+            } else if let Some(rest) = tag(line, &["@return"]) {
+                tags.returns = bracketed_type(rest).map(|(ty, _)| yard_union(&ty));
+            } else if let Some(rest) = tag(line, &["@raise"]) {
+                if let Some((ty, rest)) = bracketed_type(rest) {
+                    for error in split_top_level(&ty) {
+                        tags.raises.push((error, description(rest)));
+                    }
+                }
+            }
+        }
+
+        // Attributes document their type with `@return`
+        tags.var = tags.returns.clone();
+        tags
+    }
+
+    /// Parse PHPDoc tags: `@param Type $name`, `@return Type`, `@var Type` and `@throws Type`.
+    ///
+    /// Static-analysis variants (`@phpstan-param`, `@psalm-return`) usually follow the
+    /// plain tag with a more precise type, so the last tag for a name wins.
+    pub fn phpdoc(doc: &str) -> Self {
+        let mut tags = Self::default();
+
+        for line in doc.lines() {
+            let line = line.trim();
+            if let Some(rest) = tag(line, &["@param", "@phpstan-param", "@psalm-param"]) {
+                let (ty, rest) = php_type(rest);
+                let name = first_word(rest).trim_start_matches("...").trim_start_matches('&');
+                if let (Some(ty), Some(name)) = (ty, name.strip_prefix('$')) {
+                    tags.params.insert(name.to_string(), ty);
+                }
+            } else if let Some(rest) = tag(line, &["@return", "@phpstan-return", "@psalm-return"]) {
+                tags.returns = php_type(rest).0;
+            } else if let Some(rest) = tag(line, &["@var"]) {
+                tags.var = php_type(rest).0;
+            } else if let Some(rest) = tag(line, &["@throws"]) {
+                if let (Some(ty), rest) = php_type(rest) {
+                    for error in ty.split('|') {
+                        tags.raises.push((error.trim().to_string(), description(rest)));
+                    }
+                }
+            }
+        }
+
+        tags
+    }
+
+    /// Documented type of a parameter.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+
+    /// Documented return type.
+    pub fn returns(&self) -> Option<&str> {
+        self.returns.as_deref()
+    }
+
+    /// Documented type of a property or attribute.
+    pub fn var(&self) -> Option<&str> {
+        self.var.as_deref()
+    }
+
+    /// Documented exceptions, with the description given for each.
+    pub fn raises(&self) -> &[(String, Option<String>)] {
+        &self.raises
+    }
+}
+
+/// Strip one of the given tags from the start of a line.
+fn tag<'a>(line: &'a str, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| {
+        line.strip_prefix(name)
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+            .map(|rest| rest.trim_start())
+    })
+}
+
+/// Split a leading `[Type, Other]` (which may contain nested brackets) from the line.
+fn bracketed_type(text: &str) -> Option<(String, &str)> {
+    if !text.starts_with('[') {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((text[1..i].trim().to_string(), text[i + 1..].trim_start()));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a leading PHP type, which may hold spaces inside `<...>`, `{...}` or `(...)`.
+fn php_type(text: &str) -> (Option<String>, &str) {
+    if text.starts_with('$') || text.is_empty() {
+        return (None, text);
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '<' | '{' | '(' => depth += 1,
+            '>' | '}' | ')' => depth -= 1,
+            c if c.is_whitespace() && depth == 0 => {
+                return (Some(text[..i].to_string()), text[i..].trim_start());
+            }
+            _ => {}
+        }
+    }
+    (Some(text.to_string()), "")
+}
+
+fn yard_union(types: &str) -> String {
+    split_top_level(types).join(" | ")
+}
+
+fn first_word(text: &str) -> &str {
+    text.split_whitespace().next().unwrap_or("")
+}
+
+fn description(text: &str) -> Option<String> {
+    let text = text.trim().trim_start_matches('-').trim();
+    if text.is_empty() {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yard_tags() {
+        let doc = "Finds a user.\n@param id [String] the id\n@param [Hash] opts\n\
+                   @return [User, nil] the user\n@raise [NotFoundError] when missing";
+        let tags = DocTags::yard(doc);
+        assert_eq!(tags.param("id"), Some("String"));
+        assert_eq!(tags.param("opts"), Some("Hash"));
+        assert_eq!(tags.returns(), Some("User | nil"));
+        assert_eq!(
+            tags.raises(),
+            &[("NotFoundError".to_string(), Some("when missing".to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_phpdoc_tags() {
+        let doc = "@param array<int, User> $users the users\n@param string ...$names\n\
+                   @return ?User\n@var int\n@throws NotFoundException|IOException";
+        let tags = DocTags::phpdoc(doc);
+        assert_eq!(tags.param("users"), Some("array<int, User>"));
+        assert_eq!(tags.param("names"), Some("string"));
+        assert_eq!(tags.returns(), Some("?User"));
+        assert_eq!(tags.var(), Some("int"));
+        let errors: Vec<&str> = tags.raises().iter().map(|(e, _)| e.as_str()).collect();
+        assert_eq!(errors, vec!["NotFoundException", "IOException"]);
+    }
+}
@@ -0,0 +1,685 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tree_sitter::Node;
+
+use super::lexer::{squash, unquote};
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
+use crate::spec::{ConstSpec, ErrorSpec, FileSpec, FuncSpec, TypeSpec};
+
+/// Macros that define a function.
+const DEFINITIONS: &[&str] = &[
+    "def", "defp", "defmacro", "defmacrop", "defguard", "defguardp", "defdelegate",
+];
+
+/// Macros that define a module.
+const MODULES: &[&str] = &["defmodule", "defprotocol", "defimpl"];
+
+/// Module attributes with a meaning to the compiler or tooling, rather than constants.
+const RESERVED_ATTRIBUTES: &[&str] = &[
+    "moduledoc", "doc", "typedoc", "spec", "type", "typep", "opaque", "callback",
+    "macrocallback", "optional_callbacks", "behaviour", "impl", "derive", "enforce_keys",
+    "deprecated", "since", "dialyzer", "compile", "before_compile", "after_compile",
+    "after_verify", "on_definition", "on_load", "vsn", "external_resource", "file",
+];
+
+/// Plugin for extracting specifications from Elixir source files.
+///
+/// Everything in Elixir is a macro call, so declarations are recognized by the name
+/// of the call (`defmodule`, `def`, `defstruct`) or of the module attribute (`@spec`).
+pub struct ElixirPlugin;
+
+/// `@spec` of a function: argument types (`id :: String.t()` or `String.t()`) and return type.
+struct Spec {
+    args: Vec<String>,
+    returns: String,
+}
+
+impl ElixirPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The namespace of the first module (`MyApp.Accounts` for `MyApp.Accounts.User`),
+    /// or the file name.
+    fn extract_package(&self, root: Node, source: &str, path: &Path) -> String {
+        let mut cursor = root.walk();
+        let first = root
+            .named_children(&mut cursor)
+            .find(|c| call_name(*c, source) == Some("defmodule"))
+            .and_then(|module| first_argument(module))
+            .map(|name| NodeHelper::text(name, source));
+
+        match first.and_then(|name| name.rsplit_once('.')) {
+            Some((namespace, _)) => namespace.to_string(),
+            None => path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string(),
+        }
+    }
+
+    /// Walk a `defmodule`, `defprotocol` or `defimpl`; `parent` is the enclosing module.
+    fn extract_module(&self, call: Node, source: &str, parent: Option<&str>, spec: &mut FileSpec) {
+        let keyword = call_name(call, source).unwrap_or("");
+        let name = match first_argument(call) {
+            Some(name) => NodeHelper::text(name, source),
+            None => return,
+        };
+
+        // `defimpl Proto, for: Type` defines the module `Proto.Type`
+        let target = keyword_argument(call, "for", source).or(parent);
+        let full = match (keyword, parent) {
+            ("defimpl", _) => format!("{}.{}", name, target.unwrap_or("")),
+            (_, Some(parent)) => format!("{}.{}", parent, name),
+            _ => name.to_string(),
+        };
+        let qualified = full
+            .strip_prefix(&format!("{}.", spec.package))
+            .unwrap_or(&full)
+            .to_string();
+
+        let index = spec.types.len();
+        spec.types.push(TypeSpec {
+            kind: match keyword {
+                "defprotocol" => "protocol",
+                "defimpl" => "impl",
+                _ => "module",
+            }
+            .to_string(),
+            implements: (keyword == "defimpl").then(|| name.to_string()),
+            ..Default::default()
+        });
+
+        let body: Vec<Node> = NodeHelper::child_by_kind(call, "do_block")
+            .map(|block| block.named_children(&mut block.walk()).collect())
+            .unwrap_or_default();
+
+        let specs = self.specs(&body, source);
+        let struct_types = self.struct_types(&body, source);
+        let mut behaviours = Vec::new();
+        let mut defined = HashSet::new();
+        let mut pending_doc: Option<Option<String>> = None;
+
+        for node in &body {
+            if let Some((attribute, value)) = module_attribute(*node, source) {
+                match attribute {
+                    "moduledoc" => spec.types[index].doc = value.and_then(|v| doc_text(v, source)),
+                    "doc" | "typedoc" => {
+                        pending_doc = Some(value.and_then(|v| doc_text(v, source)));
+                    }
+                    "behaviour" => behaviours.extend(value.map(|v| NodeHelper::text(v, source))),
+                    "callback" | "macrocallback" => {
+                        if let Some(value) = value {
+                            let text = squash(NodeHelper::text(value, source));
+                            spec.types[index].methods.push(format!("{} {}", attribute, text));
+                            if spec.types[index].kind == "module" {
+                                spec.types[index].kind = "behaviour".to_string();
+                            }
+                        }
+                    }
+                    "type" | "typep" | "opaque" => {
+                        let doc = pending_doc.take().flatten();
+                        if let Some(alias) = value.and_then(|v| self.type_alias(v, source)) {
+                            // The struct's own type is folded into its fields
+                            if alias.0 == "t" && !struct_types.is_empty() {
+                                continue;
+                            }
+                            spec.types.push(TypeSpec {
+                                name: format!("{}.{} type", qualified, alias.0),
+                                doc,
+                                kind: "type_alias".to_string(),
+                                type_params: alias.1,
+                                ..Default::default()
+                            });
+                        }
+                    }
+                    name if RESERVED_ATTRIBUTES.contains(&name) => {}
+                    name => {
+                        if let Some(value) = value {
+                            spec.constants.push(ConstSpec {
+                                name: format!("{}.@{}", qualified, name),
+                                type_name: None,
+                                value: Some(NodeHelper::text(value, source).to_string()),
+                                doc: pending_doc.take().flatten(),
+                                export: None,
+                                visibility: None,
+                            });
+                        }
+                    }
+                }
+                continue;
+            }
+
+            match call_name(*node, source) {
+                Some(keyword) if MODULES.contains(&keyword) => {
+                    self.extract_module(*node, source, Some(&full), spec)
+                }
+                Some(keyword) if DEFINITIONS.contains(&keyword) => {
+                    let doc = pending_doc.take().flatten();
+                    let (name, params) = match self.head(*node, source) {
+                        Some(head) => head,
+                        None => continue,
+                    };
+                    // Later clauses of the same function add no API
+                    if !defined.insert((name.to_string(), params.len())) {
+                        continue;
+                    }
+
+                    let spec_key = (name.to_string(), params.len());
+                    let signature =
+                        self.signature(keyword, name, &params, specs.get(&spec_key), source);
+                    if spec.types[index].kind == "protocol" {
+                        spec.types[index].methods.push(signature);
+                    } else {
+                        spec.methods.push(FuncSpec {
+                            signature,
+                            doc,
+                            receiver: Some(qualified.clone()),
+                            ..Default::default()
+                        });
+                    }
+                }
+                Some("defstruct") | Some("defexception") => {
+                    let exception = call_name(*node, source) == Some("defexception");
+                    let fields = self.struct_fields(*node, source);
+                    spec.types[index].fields = fields
+                        .iter()
+                        .map(|field| match struct_types.get(field) {
+                            Some(ty) => format!("{} :: {}", field, ty),
+                            None => field.clone(),
+                        })
+                        .collect();
+                    spec.types[index].kind =
+                        if exception { "exception" } else { "struct" }.to_string();
+                    if exception {
+                        spec.errors.push(ErrorSpec {
+                            name: qualified.clone(),
+                            message: keyword_argument(*node, "message", source)
+                                .map(unquote),
+                            doc: None,
+                        });
+                    }
+                }
+                Some("use") => {
+                    let used = first_argument(*node).map(|n| NodeHelper::text(n, source));
+                    spec.types[index].embeds.extend(used.map(str::to_string));
+                }
+                Some("alias" | "import" | "require") => {
+                    spec.imports.extend(self.import_names(*node, source))
+                }
+                _ => {}
+            }
+        }
+
+        let module = &mut spec.types[index];
+        module.name = format!("{} {}", qualified, module.kind);
+        if !behaviours.is_empty() {
+            let mut implements: Vec<&str> = module.implements.iter().map(|s| s.as_str()).collect();
+            implements.extend(behaviours);
+            module.implements = Some(implements.join(", "));
+        }
+        if module.kind == "exception" {
+            let doc = module.doc.clone();
+            if let Some(error) = spec.errors.iter_mut().find(|e| e.name == qualified) {
+                error.doc = doc;
+            }
+        }
+    }
+
+    /// Function name and parameter nodes of a definition head, without its guard.
+    fn head<'a>(&self, call: Node<'a>, source: &'a str) -> Option<(&'a str, Vec<Node<'a>>)> {
+        let mut head = first_argument(call)?;
+        if head.kind() == "binary_operator" && operator(head, source) == Some("when") {
+            head = NodeHelper::field(head, "left")?;
+        }
+        match head.kind() {
+            "identifier" => Some((NodeHelper::text(head, source), Vec::new())),
+            "call" => {
+                let name = NodeHelper::field_text(head, "target", source)?;
+                let params = NodeHelper::child_by_kind(head, "arguments")
+                    .map(|args| {
+                        let mut cursor = args.walk();
+                        let params: Vec<Node> = args
+                            .named_children(&mut cursor)
+                            .filter(|a| a.kind() != "comment")
+                            .collect();
+                        params
+                    })
+                    .unwrap_or_default();
+                Some((name, params))
+            }
+            _ => None,
+        }
+    }
+
+    /// `def name(params)`, or `def name(param :: type) :: return` when a `@spec` exists.
+    fn signature(
+        &self,
+        keyword: &str,
+        name: &str,
+        params: &[Node],
+        spec: Option<&Spec>,
+        source: &str,
+    ) -> String {
+        let texts: Vec<String> = params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| {
+                let text = squash(NodeHelper::text(*param, source));
+                let spec = match spec {
+                    Some(spec) => spec,
+                    None => return text,
+                };
+                let arg = &spec.args[i];
+                // Name the parameter after the head, or the spec when the head is a pattern
+                let (spec_name, spec_type) = match arg.split_once(" :: ") {
+                    Some((name, ty)) => (Some(name), ty),
+                    None => (None, arg.as_str()),
+                };
+                let binding = match param.kind() {
+                    "identifier" => Some(NodeHelper::text(*param, source)),
+                    "binary_operator" if operator(*param, source) == Some("\\\\") => {
+                        NodeHelper::field(*param, "left")
+                            .filter(|l| l.kind() == "identifier")
+                            .map(|l| NodeHelper::text(l, source))
+                    }
+                    _ => None,
+                };
+                match binding.filter(|b| !b.starts_with('_')).or(spec_name) {
+                    Some(binding) => format!("{} :: {}", binding, spec_type),
+                    None => spec_type.to_string(),
+                }
+            })
+            .collect();
+
+        let params = if params.is_empty() && spec.is_none() {
+            String::new()
+        } else {
+            format!("({})", texts.join(", "))
+        };
+        match spec {
+            Some(spec) => format!("{} {}{} :: {}", keyword, name, params, spec.returns),
+            None => format!("{} {}{}", keyword, name, params),
+        }
+    }
+
+    /// `@spec`s of a module body by function name and arity.
+    fn specs(&self, body: &[Node], source: &str) -> HashMap<(String, usize), Spec> {
+        let mut specs = HashMap::new();
+        for node in body {
+            let value = match module_attribute(*node, source) {
+                Some(("spec", Some(value))) => value,
+                _ => continue,
+            };
+            let mut spec = value;
+            if operator(spec, source) == Some("when") {
+                spec = match NodeHelper::field(spec, "left") {
+                    Some(left) => left,
+                    None => continue,
+                };
+            }
+            if operator(spec, source) != Some("::") {
+                continue;
+            }
+            let left = NodeHelper::field(spec, "left");
+            let (head, returns) = match (left, NodeHelper::field(spec, "right")) {
+                (Some(head), Some(returns)) => (head, returns),
+                _ => continue,
+            };
+            let name = match head.kind() {
+                "call" => NodeHelper::field_text(head, "target", source),
+                "identifier" => Some(NodeHelper::text(head, source)),
+                _ => None,
+            };
+            let args: Vec<String> = NodeHelper::child_by_kind(head, "arguments")
+                .map(|args| {
+                    let mut cursor = args.walk();
+                    let list: Vec<String> = args
+                        .named_children(&mut cursor)
+                        .map(|a| squash(NodeHelper::text(a, source)))
+                        .collect();
+                    list
+                })
+                .unwrap_or_default();
+            if let Some(name) = name {
+                let returns = squash(NodeHelper::text(returns, source));
+                specs.entry((name.to_string(), args.len())).or_insert(Spec { args, returns });
+            }
+        }
+        specs
+    }
+
+    /// Field types from `@type t :: %__MODULE__{field: type}`.
+    fn struct_types(&self, body: &[Node], source: &str) -> HashMap<String, String> {
+        let mut types = HashMap::new();
+        for node in body {
+            let value = match module_attribute(*node, source) {
+                Some(("type" | "opaque", Some(value))) => value,
+                _ => continue,
+            };
+            let is_t = NodeHelper::field(value, "left")
+                .is_some_and(|left| NodeHelper::text(left, source) == "t");
+            let map = NodeHelper::field(value, "right").filter(|r| r.kind() == "map");
+            if let (true, Some(map)) = (is_t, map) {
+                for pair in NodeHelper::find_all(map, "pair") {
+                    // Only the struct's own keys, not those of nested maps
+                    if pair.parent().and_then(|p| p.parent()).map(|p| p.kind())
+                        != Some("map_content")
+                    {
+                        continue;
+                    }
+                    let key = NodeHelper::field_text(pair, "key", source).unwrap_or("");
+                    let value = NodeHelper::field_text(pair, "value", source).unwrap_or("");
+                    types.insert(key.trim().trim_end_matches(':').to_string(), squash(value));
+                }
+            }
+        }
+        types
+    }
+
+    /// Name and parameters of `@type name(a, b) :: ...`.
+    fn type_alias(&self, value: Node, source: &str) -> Option<(String, Vec<String>)> {
+        let head = if operator(value, source) == Some("::") {
+            NodeHelper::field(value, "left")?
+        } else {
+            value
+        };
+        match head.kind() {
+            "identifier" => Some((NodeHelper::text(head, source).to_string(), Vec::new())),
+            "call" => {
+                let name = NodeHelper::field_text(head, "target", source)?.to_string();
+                let params = NodeHelper::child_by_kind(head, "arguments")
+                    .map(|args| {
+                        let mut cursor = args.walk();
+                        let list: Vec<String> = args
+                            .named_children(&mut cursor)
+                            .map(|a| NodeHelper::text(a, source).to_string())
+                            .collect();
+                        list
+                    })
+                    .unwrap_or_default();
+                Some((name, params))
+            }
+            _ => None,
+        }
+    }
+
+    /// Field names of `defstruct [:a, b: 1]` or `defstruct a: nil`.
+    fn struct_fields(&self, call: Node, source: &str) -> Vec<String> {
+        let args = match NodeHelper::child_by_kind(call, "arguments") {
+            Some(args) => args,
+            None => return Vec::new(),
+        };
+        let list = NodeHelper::child_by_kind(args, "list").unwrap_or(args);
+
+        let mut fields = Vec::new();
+        let mut cursor = list.walk();
+        for item in list.named_children(&mut cursor) {
+            match item.kind() {
+                "atom" => {
+                    fields.push(NodeHelper::text(item, source).trim_start_matches(':').into());
+                }
+                "keywords" => {
+                    for pair in NodeHelper::children_by_kind(item, "pair") {
+                        let key = NodeHelper::field_text(pair, "key", source).unwrap_or("");
+                        fields.push(key.trim().trim_end_matches(':').to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        fields
+    }
+
+    /// `alias A.B`, `alias A.{B, C}` and `import A, only: [...]` as module names.
+    fn import_names(&self, call: Node, source: &str) -> Vec<String> {
+        let target = match first_argument(call) {
+            Some(target) => target,
+            None => return Vec::new(),
+        };
+        // `alias MyApp.{Repo, User}`
+        if target.kind() == "dot" {
+            if let (Some(base), Some(group)) =
+                (NodeHelper::field(target, "left"), NodeHelper::field(target, "right"))
+            {
+                let base = NodeHelper::text(base, source);
+                let mut cursor = group.walk();
+                let names: Vec<String> = group
+                    .named_children(&mut cursor)
+                    .map(|n| format!("{}.{}", base, NodeHelper::text(n, source)))
+                    .collect();
+                return names;
+            }
+        }
+
+        let name = NodeHelper::text(target, source);
+        match keyword_argument(call, "as", source) {
+            Some(alias) => vec![format!("{} as {}", name, alias)],
+            None => vec![name.to_string()],
+        }
+    }
+}
+
+/// Name of the function a call invokes (`defmodule`, `def`).
+fn call_name<'a>(node: Node<'a>, source: &'a str) -> Option<&'a str> {
+    if node.kind() != "call" {
+        return None;
+    }
+    NodeHelper::field(node, "target")
+        .filter(|t| t.kind() == "identifier")
+        .map(|t| NodeHelper::text(t, source))
+}
+
+/// First positional argument of a call.
+fn first_argument(call: Node) -> Option<Node> {
+    let args = NodeHelper::child_by_kind(call, "arguments")?;
+    let mut cursor = args.walk();
+    let first = args.named_children(&mut cursor).find(|a| a.kind() != "keywords");
+    first
+}
+
+/// Value of a keyword argument (`for: User`), as written.
+fn keyword_argument<'a>(call: Node<'a>, key: &str, source: &'a str) -> Option<&'a str> {
+    let args = NodeHelper::child_by_kind(call, "arguments")?;
+    let keywords = NodeHelper::child_by_kind(args, "keywords")?;
+    NodeHelper::children_by_kind(keywords, "pair").into_iter().find_map(|pair| {
+        let name = NodeHelper::field_text(pair, "key", source)?;
+        if name.trim().trim_end_matches(':') == key {
+            NodeHelper::field_text(pair, "value", source)
+        } else {
+            None
+        }
+    })
+}
+
+/// `@name value` as the attribute name and its value.
+fn module_attribute<'a>(node: Node<'a>, source: &'a str) -> Option<(&'a str, Option<Node<'a>>)> {
+    if node.kind() != "unary_operator" || operator(node, source) != Some("@") {
+        return None;
+    }
+    let operand = NodeHelper::field(node, "operand")?;
+    match operand.kind() {
+        "call" => {
+            let name = call_name(operand, source)?;
+            Some((name, first_argument(operand)))
+        }
+        "identifier" => Some((NodeHelper::text(operand, source), None)),
+        _ => None,
+    }
+}
+
+fn operator<'a>(node: Node<'a>, source: &'a str) -> Option<&'a str> {
+    NodeHelper::field_text(node, "operator", source)
+}
+
+/// Text of a `@doc`/`@moduledoc` string (or sigil), dedented; `false` yields none.
+fn doc_text(value: Node, source: &str) -> Option<String> {
+    let content = NodeHelper::child_by_kind(value, "quoted_content")?;
+    let lines: Vec<&str> = NodeHelper::text(content, source).lines().map(str::trim).collect();
+    let start = lines.iter().position(|l| !l.is_empty())?;
+    let end = lines.iter().rposition(|l| !l.is_empty())?;
+    Some(lines[start..=end].join("\n"))
+}
+
+impl Default for ElixirPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for ElixirPlugin {
+    fn name(&self) -> &'static str {
+        "elixir"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["ex", "exs"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let language = tree_sitter_elixir::LANGUAGE;
+        let mut parser = TreeSitterParser::new(language.into())?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: self.extract_package(root, source, path),
+            ..Default::default()
+        };
+
+        let mut cursor = root.walk();
+        for node in root.named_children(&mut cursor) {
+            match call_name(node, source) {
+                Some(keyword) if MODULES.contains(&keyword) => {
+                    self.extract_module(node, source, None, &mut spec)
+                }
+                Some("alias" | "import" | "require") => {
+                    spec.imports.extend(self.import_names(node, source))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_struct_module() {
+        let plugin = ElixirPlugin::new();
+        let source = r#"
+defmodule MyApp.Accounts.User do
+  @moduledoc """
+  A user account.
+  """
+  use Ecto.Schema
+  alias MyApp.{Repo, Mailer}
+  import Ecto.Query, only: [from: 2]
+  @behaviour MyApp.Store
+
+  @timeout 5_000
+
+  @type t :: %__MODULE__{name: String.t(), age: non_neg_integer()}
+  @typedoc "A user id."
+  @type id :: String.t()
+
+  defstruct [:name, age: 0]
+
+  @doc "Finds a user."
+  @spec find(id(), keyword()) :: {:ok, t()} | {:error, :not_found}
+  def find(id, opts \\ []) when is_binary(id) do
+    {:ok, %__MODULE__{}}
+  end
+
+  def find(_, _), do: {:error, :not_found}
+
+  defp helper(x), do: x
+
+  defmodule Query do
+    def all, do: []
+  end
+end
+"#;
+        let spec = plugin.extract(source, Path::new("user.ex")).unwrap();
+        assert_eq!(spec.package, "MyApp.Accounts");
+        assert_eq!(spec.imports, vec!["MyApp.Repo", "MyApp.Mailer", "Ecto.Query"]);
+
+        let user = &spec.types[0];
+        assert_eq!(user.name, "User struct");
+        assert_eq!(user.doc.as_deref(), Some("A user account."));
+        assert_eq!(user.fields, vec!["name :: String.t()", "age :: non_neg_integer()"]);
+        assert_eq!(user.embeds, vec!["Ecto.Schema"]);
+        assert_eq!(user.implements.as_deref(), Some("MyApp.Store"));
+
+        assert_eq!(spec.types[1].name, "User.id type");
+        assert_eq!(spec.types[1].doc.as_deref(), Some("A user id."));
+        assert_eq!(spec.types[2].name, "User.Query module");
+
+        let methods: Vec<(&str, Option<&str>)> = spec
+            .methods
+            .iter()
+            .map(|m| (m.signature.as_str(), m.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                (
+                    "def find(id :: id(), opts :: keyword()) :: {:ok, t()} | {:error, :not_found}",
+                    Some("User")
+                ),
+                ("defp helper(x)", Some("User")),
+                ("def all", Some("User.Query")),
+            ]
+        );
+        assert_eq!(spec.methods[0].doc.as_deref(), Some("Finds a user."));
+
+        assert_eq!(spec.constants[0].name, "User.@timeout");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("5_000"));
+    }
+
+    #[test]
+    fn test_extract_behaviour_protocol_and_exception() {
+        let plugin = ElixirPlugin::new();
+        let source = r#"
+defmodule Store do
+  @callback fetch(id :: String.t()) :: {:ok, term()} | :error
+end
+
+defprotocol Size do
+  @spec size(t) :: non_neg_integer()
+  def size(data)
+end
+
+defimpl Size, for: Map do
+  def size(map), do: map_size(map)
+end
+
+defmodule NotFoundError do
+  @moduledoc "Raised when a record is missing."
+  defexception message: "not found"
+end
+"#;
+        let spec = plugin.extract(source, Path::new("store.ex")).unwrap();
+        assert_eq!(spec.package, "store");
+
+        let kinds: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(
+            kinds,
+            vec!["Store behaviour", "Size protocol", "Size.Map impl", "NotFoundError exception"]
+        );
+        assert_eq!(
+            spec.types[0].methods,
+            vec!["callback fetch(id :: String.t()) :: {:ok, term()} | :error"]
+        );
+        assert_eq!(spec.types[1].methods, vec!["def size(data :: t) :: non_neg_integer()"]);
+        assert_eq!(spec.types[2].implements.as_deref(), Some("Size"));
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("Size.Map"));
+
+        assert_eq!(spec.errors[0].name, "NotFoundError");
+        assert_eq!(spec.errors[0].message.as_deref(), Some("not found"));
+        assert_eq!(spec.errors[0].doc.as_deref(), Some("Raised when a record is missing."));
+    }
+}
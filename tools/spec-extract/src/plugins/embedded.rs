@@ -0,0 +1,347 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+use super::PluginRegistry;
+use crate::spec::FileSpec;
+
+/// Code embedded in a host document (a component, Markdown page or notebook).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    /// Extension of the embedded language ("ts", "py"), which picks its plugin
+    pub language: String,
+    /// The region's code, padded with blank lines and spaces so line and column
+    /// numbers are those of the host document
+    pub source: String,
+    /// Where the region sits in the host document, for error messages
+    pub location: String,
+    /// Whether a failing region fails the host document; Markdown snippets are
+    /// illustrative and may well not parse
+    pub required: bool,
+}
+
+/// Kinds of host documents, as returned by `host_kind`.
+pub const HOST_KINDS: &[&str] = &["markdown", "notebook", "svelte", "vue"];
+
+/// The kind of host document at `path`, if it is one.
+pub fn host_kind(path: &Path) -> Option<&'static str> {
+    match path.extension().and_then(|e| e.to_str())? {
+        "vue" => Some("vue"),
+        "svelte" => Some("svelte"),
+        "md" | "markdown" => Some("markdown"),
+        "ipynb" => Some("notebook"),
+        _ => None,
+    }
+}
+
+/// Split a host document into its embedded regions.
+pub fn split(path: &Path, source: &str) -> Result<Vec<Region>> {
+    match host_kind(path) {
+        Some("vue") | Some("svelte") => Ok(script_blocks(source)),
+        Some("markdown") => Ok(code_fences(source)),
+        Some("notebook") => notebook_cells(source),
+        _ => Ok(Vec::new()),
+    }
+}
+
+/// Extract every region with the plugin for its language and merge the
+/// results into one spec for the host document. Returns `None` when no region
+/// has a plugin.
+pub fn extract(registry: &PluginRegistry, source: &str, path: &Path) -> Result<Option<FileSpec>> {
+    let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+    let mut merged: Option<FileSpec> = None;
+    for region in split(path, source)? {
+        // `App.vue.ts`, so plugins that look at the extension see the region's
+        let mut virtual_path = path.as_os_str().to_owned();
+        virtual_path.push(format!(".{}", region.language));
+        let virtual_path = PathBuf::from(virtual_path);
+        let Some(plugin) = registry.get_for_file(&virtual_path) else {
+            continue;
+        };
+
+        let spec = match registry.extract_with(plugin.as_ref(), &region.source, &virtual_path) {
+            Ok(spec) => spec,
+            Err(_) if !region.required => continue,
+            Err(e) => {
+                return Err(e).with_context(|| {
+                    format!("Failed to extract {} at {}", region.language, region.location)
+                })
+            }
+        };
+        let virtual_stem = virtual_path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+        let package =
+            if spec.package == virtual_stem { stem.clone() } else { spec.package.clone() };
+
+        match merged.as_mut() {
+            None => {
+                merged = Some(FileSpec {
+                    file: path.to_string_lossy().to_string(),
+                    package,
+                    ..spec
+                });
+            }
+            Some(host) => merge(host, spec),
+        }
+    }
+    Ok(merged)
+}
+
+fn merge(host: &mut FileSpec, spec: FileSpec) {
+    for import in spec.imports {
+        if !host.imports.contains(&import) {
+            host.imports.push(import);
+        }
+    }
+    host.resolved_imports.extend(spec.resolved_imports);
+    host.exports.extend(spec.exports);
+    host.types.extend(spec.types);
+    host.functions.extend(spec.functions);
+    host.methods.extend(spec.methods);
+    host.constants.extend(spec.constants);
+    host.variables.extend(spec.variables);
+    host.errors.extend(spec.errors);
+}
+
+/// Blank out `source[..start]`, keeping its line breaks.
+fn pad(source: &str, start: usize) -> String {
+    let before = &source[..start];
+    let lines = before.matches('\n').count();
+    let column = before.rsplit('\n').next().unwrap_or_default().chars().count();
+    format!("{}{}", "\n".repeat(lines), " ".repeat(column))
+}
+
+fn line_of(source: &str, offset: usize) -> usize {
+    source[..offset].matches('\n').count() + 1
+}
+
+/// `<script>` blocks of Vue and Svelte components, by their `lang` attribute.
+fn script_blocks(source: &str) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut offset = 0;
+    while let Some(found) = source[offset..].find("<script") {
+        let tag_start = offset + found;
+        let Some(tag_len) = source[tag_start..].find('>') else {
+            break;
+        };
+        let tag = &source[tag_start..tag_start + tag_len];
+        let start = tag_start + tag_len + 1;
+        let Some(len) = source[start..].find("</script>") else {
+            break;
+        };
+        offset = start + len + "</script>".len();
+        // `<scripts>` or some other element that merely starts the same way
+        if tag.len() > 7 && !tag[7..].starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        let language = attribute(tag, "lang").unwrap_or("js");
+        let language = match language {
+            "typescript" => "ts",
+            "javascript" => "js",
+            other => other,
+        };
+        regions.push(Region {
+            language: language.to_string(),
+            source: format!("{}{}", pad(source, start), &source[start..start + len]),
+            location: format!("line {}", line_of(source, start)),
+            required: true,
+        });
+    }
+    regions
+}
+
+/// Value of `name="value"` (or single-quoted) in an opening tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(found) = rest.find(name) {
+        let after = rest[found + name.len()..].trim_start();
+        let preceded = rest[..found].ends_with(|c: char| c.is_whitespace());
+        rest = &rest[found + name.len()..];
+        let Some(value) = after.strip_prefix('=').map(str::trim_start).filter(|_| preceded) else {
+            continue;
+        };
+        let quote = value.chars().next()?;
+        if quote == '"' || quote == '\'' {
+            return value[1..].split(quote).next();
+        }
+        return value.split_whitespace().next();
+    }
+    None
+}
+
+/// Fenced code blocks of a Markdown document, by their info string.
+fn code_fences(source: &str) -> Vec<Region> {
+    let mut regions = Vec::new();
+    let mut open: Option<(String, String, usize)> = None;
+    let mut offset = 0;
+    for line in source.split_inclusive('\n') {
+        let line_start = offset;
+        offset += line.len();
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+        let fence: String = trimmed.chars().take_while(|&c| c == '`' || c == '~').collect();
+        let is_fence = indent <= 3
+            && fence.len() >= 3
+            && fence.chars().all(|c| c == fence.chars().next().unwrap_or('`'));
+
+        match open.take() {
+            None if is_fence => {
+                let info = trimmed[fence.len()..].split_whitespace().next().unwrap_or_default();
+                open = Some((fence, fence_language(info), offset));
+            }
+            None => {}
+            Some((opening, language, start)) => {
+                let closes = is_fence
+                    && fence.starts_with(&opening[..1])
+                    && fence.len() >= opening.len()
+                    && trimmed[fence.len()..].trim().is_empty();
+                if !closes {
+                    open = Some((opening, language, start));
+                    continue;
+                }
+                if !language.is_empty() {
+                    regions.push(Region {
+                        language,
+                        source: format!("{}{}", pad(source, start), &source[start..line_start]),
+                        location: format!("line {}", line_of(source, start)),
+                        required: false,
+                    });
+                }
+            }
+        }
+    }
+    regions
+}
+
+/// Extension for a fence's language name (`python` -> `py`).
+fn fence_language(info: &str) -> String {
+    let info = info.trim_start_matches('{').trim_end_matches('}').to_lowercase();
+    let extension = match info.as_str() {
+        "typescript" => "ts",
+        "javascript" => "js",
+        "python" | "python3" => "py",
+        "rust" => "rs",
+        "golang" => "go",
+        "kotlin" => "kt",
+        "csharp" | "c#" => "cs",
+        "c++" => "cpp",
+        "ruby" => "rb",
+        "elixir" => "ex",
+        "protobuf" => "proto",
+        "shell" | "bash" | "sh" | "console" | "text" | "plaintext" => "",
+        other => other,
+    };
+    extension.to_string()
+}
+
+/// The code cells of a Jupyter notebook, joined into one region since cells
+/// share a namespace (imports in the first cell serve the others).
+fn notebook_cells(source: &str) -> Result<Vec<Region>> {
+    let notebook: Value = serde_json::from_str(source).context("Failed to parse notebook")?;
+    let metadata = &notebook["metadata"];
+    let language = metadata["language_info"]["file_extension"]
+        .as_str()
+        .map(|ext| ext.trim_start_matches('.').to_string())
+        .or_else(|| metadata["kernelspec"]["language"].as_str().map(fence_language))
+        .unwrap_or_else(|| "py".to_string());
+
+    let mut code = String::new();
+    let mut cells = 0;
+    for cell in notebook["cells"].as_array().into_iter().flatten() {
+        if cell["cell_type"] != "code" {
+            continue;
+        }
+        let text = match &cell["source"] {
+            Value::String(text) => text.clone(),
+            Value::Array(lines) => lines.iter().filter_map(|l| l.as_str()).collect(),
+            _ => continue,
+        };
+        for line in text.lines() {
+            // IPython magics and shell escapes are not Python
+            if language == "py" && (line.starts_with('%') || line.starts_with('!')) {
+                code.push_str("# ");
+            }
+            code.push_str(line);
+            code.push('\n');
+        }
+        code.push('\n');
+        cells += 1;
+    }
+
+    if cells == 0 {
+        return Ok(Vec::new());
+    }
+    Ok(vec![Region {
+        language,
+        source: code,
+        location: format!("{} code cells", cells),
+        required: true,
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_component_keeps_host_lines() {
+        let source = r#"<template>
+  <div>{{ user.name }}</div>
+</template>
+
+<script setup lang="ts">
+import { ref } from "vue";
+const user = ref({ name: "Ada" });
+</script>
+
+<style scoped>
+div { color: red; }
+</style>
+"#;
+        let regions = split(Path::new("src/UserCard.vue"), source).unwrap();
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].language, "ts");
+        assert_eq!(regions[0].location, "line 5");
+        // The import is on line 6 of the component, and of the region
+        let lines: Vec<&str> = regions[0].source.lines().collect();
+        assert_eq!(lines[5], "import { ref } from \"vue\";");
+        assert!(lines[..4].iter().all(|l| l.trim().is_empty()));
+    }
+
+    #[test]
+    fn test_extract_markdown_fences() {
+        let source = "# Users\n\n```python\ndef greet(name: str) -> str:\n    return name\n```\n\n\
+                      ```bash\npip install users\n```\n\n~~~graphql\nquery { me }\n~~~\n";
+        let registry = PluginRegistry::new();
+        let spec = registry.extract(source, Path::new("docs/users.md")).unwrap().unwrap();
+        assert_eq!(spec.file, "docs/users.md");
+        assert_eq!(spec.package, "users");
+        // The GraphQL block is a query, not a schema, and is skipped
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(spec.functions[0].signature, "def greet(name: str) -> str");
+
+        let prose = "# Nothing to see\n\n```text\nhello\n```\n";
+        assert!(registry.extract(prose, Path::new("README.md")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_extract_notebook_cells() {
+        let source = r##"{
+  "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+  "cells": [
+    {"cell_type": "markdown", "source": ["# Analysis"]},
+    {"cell_type": "code", "source": ["%matplotlib inline\n", "import pandas as pd\n"]},
+    {"cell_type": "code", "source": "def load(path):\n    return pd.read_csv(path)\n"}
+  ]
+}"##;
+        let regions = split(Path::new("analysis.ipynb"), source).unwrap();
+        assert_eq!(regions[0].language, "py");
+        assert!(regions[0].source.starts_with("# %matplotlib inline\nimport pandas as pd\n"));
+
+        let registry = PluginRegistry::new();
+        let spec = registry.extract(source, Path::new("analysis.ipynb")).unwrap().unwrap();
+        assert_eq!(spec.file, "analysis.ipynb");
+        assert_eq!(spec.imports, vec!["pandas as pd"]);
+        assert_eq!(spec.functions[0].signature, "def load(path)");
+    }
+}
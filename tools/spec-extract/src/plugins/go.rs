@@ -268,6 +268,8 @@ impl GoPlugin {
                         type_name,
                         value,
                         doc: doc.clone(),
+                        export: None,
+                        visibility: None,
                     });
                 }
             }
@@ -294,6 +296,8 @@ impl GoPlugin {
                         name,
                         type_name,
                         doc: doc.clone(),
+                        export: None,
+                        visibility: None,
                     });
                 }
             }
@@ -362,6 +366,7 @@ impl LanguagePlugin for GoPlugin {
             constants: self.extract_constants(root, source),
             variables: self.extract_variables(root, source),
             errors: self.extract_errors(root, source),
+            ..Default::default()
         })
     }
 }
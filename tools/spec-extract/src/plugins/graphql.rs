@@ -0,0 +1,465 @@
+use anyhow::{anyhow, Result};
+use graphql_parser::schema::{
+    parse_schema, Definition, Directive, EnumValue, Field, InputValue, TypeDefinition,
+    TypeExtension,
+};
+use std::path::Path;
+
+use crate::parser::LanguagePlugin;
+use crate::spec::{AttributeSpec, FileSpec, FuncSpec, TypeSpec};
+
+/// Plugin for extracting specifications from GraphQL SDL files.
+///
+/// Object, interface, input, enum, union and scalar definitions become types.
+/// The fields of the root operation types (`Query`, `Mutation`, `Subscription`,
+/// or those named by a `schema` definition) are the API's operations, so they
+/// become methods with the root type as receiver.
+pub struct GraphQlPlugin;
+
+/// Root operation type names, by operation kind.
+struct Roots {
+    query: String,
+    mutation: String,
+    subscription: String,
+}
+
+impl Roots {
+    fn kind_of(&self, name: &str) -> Option<&'static str> {
+        if name == self.query {
+            Some("query")
+        } else if name == self.mutation {
+            Some("mutation")
+        } else if name == self.subscription {
+            Some("subscription")
+        } else {
+            None
+        }
+    }
+}
+
+impl GraphQlPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Add an object or interface (or an extension of one) to the spec.
+    #[allow(clippy::too_many_arguments)]
+    fn object(
+        &self,
+        spec: &mut FileSpec,
+        roots: &Roots,
+        name: &str,
+        kind: &str,
+        doc: Option<&String>,
+        interfaces: &[String],
+        directives: &[Directive<'_, String>],
+        fields: &[Field<'_, String>],
+    ) {
+        let root = roots.kind_of(name);
+        let index = type_index(spec, name, root.unwrap_or(kind));
+        let ty = &mut spec.types[index];
+        if ty.doc.is_none() {
+            ty.doc = doc.map(|d| d.trim().to_string());
+        }
+        if !interfaces.is_empty() {
+            let mut implements: Vec<String> =
+                ty.implements.iter().flat_map(|i| i.split(", ")).map(String::from).collect();
+            implements.extend(interfaces.iter().cloned());
+            ty.implements = Some(implements.join(", "));
+        }
+        ty.attributes.extend(attributes(directives, "type", None));
+
+        for field in fields {
+            let signature = field_signature(field);
+            let member = Some(field.name.clone());
+            spec.types[index].attributes.extend(attributes(&field.directives, "field", member));
+            for argument in &field.arguments {
+                let member = Some(format!("{}.{}", field.name, argument.name));
+                let attrs = attributes(&argument.directives, "argument", member);
+                spec.types[index].attributes.extend(attrs);
+            }
+
+            // Operations of the API, documented on their own
+            if root.is_some() {
+                spec.methods.push(FuncSpec {
+                    signature,
+                    doc: field.description.as_ref().map(|d| d.trim().to_string()),
+                    receiver: Some(name.to_string()),
+                    ..Default::default()
+                });
+            } else if kind == "interface" {
+                spec.types[index].methods.push(signature);
+            } else {
+                spec.types[index].fields.push(signature);
+            }
+        }
+    }
+
+    fn input(
+        &self,
+        spec: &mut FileSpec,
+        name: &str,
+        doc: Option<&String>,
+        directives: &[Directive<'_, String>],
+        fields: &[InputValue<'_, String>],
+    ) {
+        let index = type_index(spec, name, "input");
+        let ty = &mut spec.types[index];
+        if ty.doc.is_none() {
+            ty.doc = doc.map(|d| d.trim().to_string());
+        }
+        ty.attributes.extend(attributes(directives, "type", None));
+        for field in fields {
+            ty.fields.push(input_value(field));
+            let member = Some(field.name.clone());
+            ty.attributes.extend(attributes(&field.directives, "field", member));
+        }
+    }
+
+    fn enumeration(
+        &self,
+        spec: &mut FileSpec,
+        name: &str,
+        doc: Option<&String>,
+        directives: &[Directive<'_, String>],
+        values: &[EnumValue<'_, String>],
+    ) {
+        let index = type_index(spec, name, "enum");
+        let ty = &mut spec.types[index];
+        if ty.doc.is_none() {
+            ty.doc = doc.map(|d| d.trim().to_string());
+        }
+        ty.attributes.extend(attributes(directives, "type", None));
+        for value in values {
+            ty.variants.push(value.name.clone());
+            let member = Some(value.name.clone());
+            ty.attributes.extend(attributes(&value.directives, "variant", member));
+        }
+    }
+
+    fn simple(
+        &self,
+        spec: &mut FileSpec,
+        name: &str,
+        kind: &str,
+        doc: Option<&String>,
+        directives: &[Directive<'_, String>],
+        variants: &[String],
+    ) {
+        let index = type_index(spec, name, kind);
+        let ty = &mut spec.types[index];
+        if ty.doc.is_none() {
+            ty.doc = doc.map(|d| d.trim().to_string());
+        }
+        ty.attributes.extend(attributes(directives, "type", None));
+        ty.variants.extend(variants.iter().cloned());
+    }
+}
+
+/// Index of the type `name`, added with `kind` unless an earlier definition or
+/// extension already declared it.
+fn type_index(spec: &mut FileSpec, name: &str, kind: &str) -> usize {
+    let type_name = format!("{} {}", name, kind);
+    if let Some(index) = spec.types.iter().position(|t| t.name == type_name) {
+        return index;
+    }
+    spec.types.push(TypeSpec { name: type_name, kind: kind.to_string(), ..Default::default() });
+    spec.types.len() - 1
+}
+
+/// `user(id: ID!, first: Int = 10): User`
+fn field_signature(field: &Field<'_, String>) -> String {
+    if field.arguments.is_empty() {
+        return format!("{}: {}", field.name, field.field_type);
+    }
+    let arguments: Vec<String> = field.arguments.iter().map(input_value).collect();
+    format!("{}({}): {}", field.name, arguments.join(", "), field.field_type)
+}
+
+/// `first: Int = 10`
+fn input_value(value: &InputValue<'_, String>) -> String {
+    match &value.default_value {
+        Some(default) => format!("{}: {} = {}", value.name, value.value_type, default),
+        None => format!("{}: {}", value.name, value.value_type),
+    }
+}
+
+fn attributes(
+    directives: &[Directive<'_, String>],
+    target: &str,
+    member: Option<String>,
+) -> Vec<AttributeSpec> {
+    directives
+        .iter()
+        .map(|directive| AttributeSpec {
+            name: directive.name.clone(),
+            args: directive
+                .arguments
+                .iter()
+                .map(|(name, value)| format!("{}: {}", name, value))
+                .collect(),
+            target: target.to_string(),
+            member: member.clone(),
+        })
+        .collect()
+}
+
+impl Default for GraphQlPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for GraphQlPlugin {
+    fn name(&self) -> &'static str {
+        "graphql"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["graphql", "graphqls", "gql"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let document = parse_schema::<String>(source)
+            .map_err(|e| anyhow!("Failed to parse GraphQL schema {}: {}", path.display(), e))?;
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string(),
+            ..Default::default()
+        };
+
+        // A `schema { query: RootQuery }` definition renames the root types
+        let mut roots = Roots {
+            query: "Query".to_string(),
+            mutation: "Mutation".to_string(),
+            subscription: "Subscription".to_string(),
+        };
+        for definition in &document.definitions {
+            if let Definition::SchemaDefinition(schema) = definition {
+                roots.query = schema.query.clone().unwrap_or(roots.query);
+                roots.mutation = schema.mutation.clone().unwrap_or(roots.mutation);
+                roots.subscription = schema.subscription.clone().unwrap_or(roots.subscription);
+            }
+        }
+
+        for definition in &document.definitions {
+            match definition {
+                Definition::TypeDefinition(TypeDefinition::Object(t)) => self.object(
+                    &mut spec,
+                    &roots,
+                    &t.name,
+                    "type",
+                    t.description.as_ref(),
+                    &t.implements_interfaces,
+                    &t.directives,
+                    &t.fields,
+                ),
+                Definition::TypeDefinition(TypeDefinition::Interface(t)) => self.object(
+                    &mut spec,
+                    &roots,
+                    &t.name,
+                    "interface",
+                    t.description.as_ref(),
+                    &t.implements_interfaces,
+                    &t.directives,
+                    &t.fields,
+                ),
+                Definition::TypeDefinition(TypeDefinition::InputObject(t)) => {
+                    self.input(&mut spec, &t.name, t.description.as_ref(), &t.directives, &t.fields)
+                }
+                Definition::TypeDefinition(TypeDefinition::Enum(t)) => self.enumeration(
+                    &mut spec,
+                    &t.name,
+                    t.description.as_ref(),
+                    &t.directives,
+                    &t.values,
+                ),
+                Definition::TypeDefinition(TypeDefinition::Union(t)) => self.simple(
+                    &mut spec,
+                    &t.name,
+                    "union",
+                    t.description.as_ref(),
+                    &t.directives,
+                    &t.types,
+                ),
+                Definition::TypeDefinition(TypeDefinition::Scalar(t)) => self.simple(
+                    &mut spec,
+                    &t.name,
+                    "scalar",
+                    t.description.as_ref(),
+                    &t.directives,
+                    &[],
+                ),
+                // `extend type Query { ... }` adds to the type it extends
+                Definition::TypeExtension(TypeExtension::Object(t)) => self.object(
+                    &mut spec,
+                    &roots,
+                    &t.name,
+                    "type",
+                    None,
+                    &t.implements_interfaces,
+                    &t.directives,
+                    &t.fields,
+                ),
+                Definition::TypeExtension(TypeExtension::Interface(t)) => self.object(
+                    &mut spec,
+                    &roots,
+                    &t.name,
+                    "interface",
+                    None,
+                    &t.implements_interfaces,
+                    &t.directives,
+                    &t.fields,
+                ),
+                Definition::TypeExtension(TypeExtension::InputObject(t)) => {
+                    self.input(&mut spec, &t.name, None, &t.directives, &t.fields)
+                }
+                Definition::TypeExtension(TypeExtension::Enum(t)) => {
+                    self.enumeration(&mut spec, &t.name, None, &t.directives, &t.values)
+                }
+                Definition::TypeExtension(TypeExtension::Union(t)) => {
+                    self.simple(&mut spec, &t.name, "union", None, &t.directives, &t.types)
+                }
+                Definition::TypeExtension(TypeExtension::Scalar(t)) => {
+                    self.simple(&mut spec, &t.name, "scalar", None, &t.directives, &[])
+                }
+                Definition::SchemaDefinition(_) | Definition::DirectiveDefinition(_) => {}
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_types() {
+        let plugin = GraphQlPlugin::new();
+        let source = r#"
+"Something with an id."
+interface Node {
+  id: ID!
+}
+
+"""
+A registered user.
+"""
+type User implements Node @key(fields: "id") {
+  id: ID!
+  email: String! @deprecated(reason: "use contacts")
+  posts(first: Int = 10, after: String): [Post!]!
+}
+
+input CreateUserInput {
+  email: String!
+  role: Role = MEMBER
+}
+
+enum Role {
+  ADMIN
+  MEMBER
+}
+
+union SearchResult = User | Post
+
+scalar DateTime
+"#;
+        let spec = plugin.extract(source, Path::new("schema.graphql")).unwrap();
+        assert_eq!(spec.package, "schema");
+
+        let node = &spec.types[0];
+        assert_eq!(node.name, "Node interface");
+        assert_eq!(node.doc.as_deref(), Some("Something with an id."));
+        assert_eq!(node.methods, vec!["id: ID!"]);
+
+        let user = &spec.types[1];
+        assert_eq!(user.name, "User type");
+        assert_eq!(user.doc.as_deref(), Some("A registered user."));
+        assert_eq!(user.implements.as_deref(), Some("Node"));
+        assert_eq!(
+            user.fields,
+            vec!["id: ID!", "email: String!", "posts(first: Int = 10, after: String): [Post!]!"]
+        );
+        let attrs: Vec<(&str, &str, Option<&str>)> = user
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.target.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![("key", "type", None), ("deprecated", "field", Some("email"))]
+        );
+        assert_eq!(user.attributes[1].args, vec!["reason: \"use contacts\""]);
+
+        assert_eq!(spec.types[2].name, "CreateUserInput input");
+        assert_eq!(spec.types[2].fields, vec!["email: String!", "role: Role = MEMBER"]);
+        assert_eq!(spec.types[3].variants, vec!["ADMIN", "MEMBER"]);
+        assert_eq!(spec.types[4].name, "SearchResult union");
+        assert_eq!(spec.types[4].variants, vec!["User", "Post"]);
+        assert_eq!(spec.types[5].name, "DateTime scalar");
+    }
+
+    #[test]
+    fn test_extract_operations() {
+        let plugin = GraphQlPlugin::new();
+        let source = r#"
+schema {
+  query: RootQuery
+  mutation: Mutation
+}
+
+type RootQuery {
+  "Look up a user."
+  user(id: ID!): User
+}
+
+type Mutation {
+  createUser(input: CreateUserInput!): User!
+}
+
+extend type RootQuery {
+  users(first: Int): [User!]!
+}
+
+type Subscription {
+  userCreated: User!
+}
+"#;
+        let spec = plugin.extract(source, Path::new("api.graphql")).unwrap();
+        let kinds: Vec<(&str, &str)> =
+            spec.types.iter().map(|t| (t.name.as_str(), t.kind.as_str())).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ("RootQuery query", "query"),
+                ("Mutation mutation", "mutation"),
+                ("Subscription subscription", "subscription"),
+            ]
+        );
+
+        let methods: Vec<(&str, Option<&str>)> = spec
+            .methods
+            .iter()
+            .map(|m| (m.signature.as_str(), m.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                ("user(id: ID!): User", Some("RootQuery")),
+                ("createUser(input: CreateUserInput!): User!", Some("Mutation")),
+                ("users(first: Int): [User!]!", Some("RootQuery")),
+                ("userCreated: User!", Some("Subscription")),
+            ]
+        );
+        assert_eq!(spec.methods[0].doc.as_deref(), Some("Look up a user."));
+    }
+
+    #[test]
+    fn test_invalid_schema_is_an_error() {
+        let plugin = GraphQlPlugin::new();
+        assert!(plugin.extract("type {", Path::new("broken.graphql")).is_err());
+    }
+}
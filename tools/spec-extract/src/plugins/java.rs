@@ -0,0 +1,784 @@
+use anyhow::Result;
+use std::path::Path;
+use tree_sitter::Node;
+
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
+use crate::spec::{AttributeSpec, ConstSpec, ErrorSpec, FileSpec, FuncSpec, TypeSpec};
+
+/// Declarations that introduce a named type.
+const TYPE_DECLARATIONS: &[&str] = &[
+    "class_declaration",
+    "interface_declaration",
+    "record_declaration",
+    "enum_declaration",
+    "annotation_type_declaration",
+];
+
+/// Nodes whose contents are implementation details (local and anonymous classes).
+const BODY_KINDS: &[&str] = &[
+    "block",
+    "constructor_body",
+    "lambda_expression",
+    "object_creation_expression",
+];
+
+/// Superclasses that make a class an exception type.
+const THROWABLE_SUFFIXES: &[&str] = &["Exception", "Error", "Throwable"];
+
+/// Plugin for extracting specifications from Java source files.
+pub struct JavaPlugin;
+
+impl JavaPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn extract_package(&self, root: Node, source: &str, path: &Path) -> String {
+        NodeHelper::child_by_kind(root, "package_declaration")
+            .and_then(|pkg| pkg.named_children(&mut pkg.walk()).find(|c| c.kind() != "annotation"))
+            .map(|name| NodeHelper::text(name, source).to_string())
+            .unwrap_or_else(|| {
+                // Default package: fall back to the file name
+                path.file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("unknown")
+                    .to_string()
+            })
+    }
+
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<String> {
+        NodeHelper::children_by_kind(root, "import_declaration")
+            .into_iter()
+            .map(|import| {
+                let text = NodeHelper::text(import, source);
+                text.trim_start_matches("import")
+                    .trim_end_matches(';')
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect()
+    }
+
+    fn extract_types(&self, root: Node, source: &str) -> Vec<TypeSpec> {
+        let mut types = Vec::new();
+
+        for kind in TYPE_DECLARATIONS {
+            for node in NodeHelper::find_all(root, kind) {
+                if self.is_local(node) {
+                    continue;
+                }
+                if let Some(spec) = self.extract_type(node, source) {
+                    types.push((node.start_byte(), spec));
+                }
+            }
+        }
+
+        // Keep document order across declaration kinds
+        types.sort_by_key(|(start, _)| *start);
+        types.into_iter().map(|(_, spec)| spec).collect()
+    }
+
+    fn extract_type(&self, node: Node, source: &str) -> Option<TypeSpec> {
+        let name = NodeHelper::field_text(node, "name", source)?;
+        let name = self.qualified_name(node, name, source);
+        let doc = NodeHelper::preceding_comment(node, source);
+
+        let is_abstract = self.modifiers(node, source).split_whitespace().any(|m| m == "abstract");
+        let kind = match node.kind() {
+            "class_declaration" if is_abstract => "abstract_class",
+            "class_declaration" => "class",
+            "interface_declaration" => "interface",
+            "record_declaration" => "record",
+            "enum_declaration" => "enum",
+            _ => "annotation",
+        };
+
+        let type_params = NodeHelper::field(node, "type_parameters")
+            .map(|params| {
+                NodeHelper::children_by_kind(params, "type_parameter")
+                    .into_iter()
+                    .map(|p| NodeHelper::text(p, source).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Superclass, or the interfaces an interface extends
+        let mut embeds = Vec::new();
+        if let Some(superclass) = NodeHelper::field(node, "superclass") {
+            let base = superclass.named_child(0).map(|t| NodeHelper::text(t, source).to_string());
+            embeds.extend(base);
+        }
+        if let Some(extends) = NodeHelper::child_by_kind(node, "extends_interfaces") {
+            embeds.extend(self.type_list(extends, source));
+        }
+
+        let implements = NodeHelper::field(node, "interfaces")
+            .map(|interfaces| self.type_list(interfaces, source))
+            .filter(|list| !list.is_empty())
+            .map(|list| list.join(", "));
+
+        let mut attributes = self.annotations(node, source, "class", None);
+        let mut fields = Vec::new();
+        let mut methods = Vec::new();
+        let mut variants = Vec::new();
+
+        // Record components are its fields
+        if let Some(params) = NodeHelper::field(node, "parameters") {
+            fields.extend(self.parameter_list(params, source));
+        }
+
+        if let Some(body) = NodeHelper::field(node, "body") {
+            let mut members: Vec<Node> = Vec::new();
+            let mut cursor = body.walk();
+            for child in body.named_children(&mut cursor) {
+                if child.kind() == "enum_body_declarations" {
+                    members.extend(child.named_children(&mut child.walk()));
+                } else {
+                    members.push(child);
+                }
+            }
+
+            for member in members {
+                match member.kind() {
+                    "field_declaration" if !self.is_constant(member, source) => {
+                        let modifiers = self.modifiers(member, source);
+                        let type_name =
+                            NodeHelper::field_text(member, "type", source).unwrap_or("");
+                        for name in self.declarator_names(member, source) {
+                            let prefix = if modifiers.is_empty() {
+                                String::new()
+                            } else {
+                                format!("{} ", modifiers)
+                            };
+                            fields.push(format!("{}{} {}", prefix, type_name, name));
+                            let annotations = self.annotations(member, source, "field", Some(name));
+                            attributes.extend(annotations);
+                        }
+                    }
+                    "method_declaration" | "constructor_declaration" => {
+                        let method = NodeHelper::field_text(member, "name", source).unwrap_or("");
+                        let member_name = Some(method.to_string());
+                        attributes.extend(self.annotations(member, source, "method", member_name));
+                        if let Some(params) = NodeHelper::field(member, "parameters") {
+                            attributes.extend(self.parameter_annotations(params, method, source));
+                        }
+                        // Class methods become FileSpec methods; interfaces list signatures
+                        if kind == "interface" {
+                            methods.push(self.method_signature(member, source));
+                        }
+                    }
+                    "annotation_type_element_declaration" => {
+                        methods.push(self.element_signature(member, source));
+                    }
+                    "enum_constant" => {
+                        let constant = NodeHelper::field_text(member, "name", source).unwrap_or("");
+                        let args = NodeHelper::field(member, "arguments")
+                            .map(|a| NodeHelper::text(a, source))
+                            .unwrap_or("");
+                        variants.push(format!("{}{}", constant, args));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Some(TypeSpec {
+            name: format!("{} {}", name, if kind == "abstract_class" { "class" } else { kind }),
+            doc,
+            kind: kind.to_string(),
+            fields,
+            methods,
+            embeds,
+            implements,
+            type_params,
+            variants,
+            attributes,
+            visibility: Some(self.visibility(node, source)),
+            ..Default::default()
+        })
+    }
+
+    fn extract_methods(&self, root: Node, source: &str) -> Vec<FuncSpec> {
+        let mut methods = Vec::new();
+
+        for kind in ["method_declaration", "constructor_declaration"] {
+            for node in NodeHelper::find_all(root, kind) {
+                let owner = match self.enclosing_type(node) {
+                    Some(owner) => owner,
+                    None => continue,
+                };
+                // Interface and annotation members are listed on the type itself
+                if matches!(owner.kind(), "interface_declaration" | "annotation_type_declaration") {
+                    continue;
+                }
+
+                let receiver = NodeHelper::field_text(owner, "name", source)
+                    .map(|name| self.qualified_name(owner, name, source));
+
+                methods.push((
+                    node.start_byte(),
+                    FuncSpec {
+                        signature: self.method_signature(node, source),
+                        doc: NodeHelper::preceding_comment(node, source),
+                        receiver,
+                        visibility: Some(self.visibility(node, source)),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+
+        methods.sort_by_key(|(start, _)| *start);
+        methods.into_iter().map(|(_, spec)| spec).collect()
+    }
+
+    /// `[modifiers] [<T>] [type] name(params) [throws ...]`, without annotations.
+    fn method_signature(&self, node: Node, source: &str) -> String {
+        let mut parts = Vec::new();
+
+        let modifiers = self.modifiers(node, source);
+        if !modifiers.is_empty() {
+            parts.push(modifiers);
+        }
+        if let Some(type_params) = NodeHelper::field_text(node, "type_parameters", source) {
+            parts.push(type_params.to_string());
+        }
+        if let Some(return_type) = NodeHelper::field_text(node, "type", source) {
+            let dimensions = NodeHelper::field_text(node, "dimensions", source).unwrap_or("");
+            parts.push(format!("{}{}", return_type, dimensions));
+        }
+
+        let name = NodeHelper::field_text(node, "name", source).unwrap_or("");
+        let params = NodeHelper::field(node, "parameters")
+            .map(|p| self.parameter_list(p, source).join(", "))
+            .unwrap_or_default();
+        parts.push(format!("{}({})", name, params));
+
+        let thrown = self.thrown_types(node, source);
+        if !thrown.is_empty() {
+            parts.push(format!("throws {}", thrown.join(", ")));
+        }
+
+        parts.join(" ")
+    }
+
+    /// `Type name() default value` for an annotation element.
+    fn element_signature(&self, node: Node, source: &str) -> String {
+        let type_name = NodeHelper::field_text(node, "type", source).unwrap_or("");
+        let name = NodeHelper::field_text(node, "name", source).unwrap_or("");
+        match NodeHelper::field_text(node, "value", source) {
+            Some(value) => format!("{} {}() default {}", type_name, name, value),
+            None => format!("{} {}()", type_name, name),
+        }
+    }
+
+    /// Parameters as `Type name`, dropping annotations and `final`.
+    fn parameter_list(&self, params: Node, source: &str) -> Vec<String> {
+        let mut cursor = params.walk();
+        params
+            .named_children(&mut cursor)
+            .filter(|p| matches!(p.kind(), "formal_parameter" | "spread_parameter"))
+            .map(|param| {
+                let mut inner = param.walk();
+                let start = param
+                    .children(&mut inner)
+                    .find(|c| c.kind() != "modifiers")
+                    .map(|c| c.start_byte())
+                    .unwrap_or(param.start_byte());
+                source[start..param.end_byte()].to_string()
+            })
+            .collect()
+    }
+
+    fn parameter_annotations(
+        &self,
+        params: Node,
+        method: &str,
+        source: &str,
+    ) -> Vec<AttributeSpec> {
+        let mut attributes = Vec::new();
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            let name = NodeHelper::field_text(param, "name", source)
+                .or_else(|| {
+                    // Varargs keep their name inside the declarator
+                    NodeHelper::child_by_kind(param, "variable_declarator")
+                        .and_then(|d| NodeHelper::field_text(d, "name", source))
+                })
+                .unwrap_or("");
+            let member = Some(format!("{}.{}", method, name));
+            attributes.extend(self.annotations(param, source, "parameter", member));
+        }
+        attributes
+    }
+
+    /// Annotations in the `modifiers` of a declaration, as attributes.
+    fn annotations(
+        &self,
+        node: Node,
+        source: &str,
+        target: &str,
+        member: Option<String>,
+    ) -> Vec<AttributeSpec> {
+        let modifiers = match NodeHelper::child_by_kind(node, "modifiers") {
+            Some(m) => m,
+            None => return Vec::new(),
+        };
+
+        let mut attributes = Vec::new();
+        let mut cursor = modifiers.walk();
+        for annotation in modifiers.named_children(&mut cursor) {
+            if !matches!(annotation.kind(), "annotation" | "marker_annotation") {
+                continue;
+            }
+
+            let name = NodeHelper::field_text(annotation, "name", source).unwrap_or("");
+            let mut args = Vec::new();
+            if let Some(arguments) = NodeHelper::field(annotation, "arguments") {
+                let mut inner = arguments.walk();
+                for arg in arguments.named_children(&mut inner) {
+                    let text = NodeHelper::text(arg, source);
+                    if arg.kind() == "string_literal" {
+                        args.push(text.trim_matches('"').to_string());
+                    } else if !arg.is_extra() {
+                        args.push(text.to_string());
+                    }
+                }
+            }
+
+            attributes.push(AttributeSpec {
+                name: name.to_string(),
+                args,
+                target: target.to_string(),
+                member: member.clone(),
+            });
+        }
+        attributes
+    }
+
+    /// The access modifier, or what applies without one: members of interfaces and
+    /// annotations are public, everything else is package-private.
+    fn visibility(&self, node: Node, source: &str) -> String {
+        let modifiers = self.modifiers(node, source);
+        let access = modifiers
+            .split_whitespace()
+            .find(|m| matches!(*m, "public" | "protected" | "private"));
+        if let Some(access) = access {
+            return access.to_string();
+        }
+        let in_interface = self.enclosing_type(node).is_some_and(|owner| {
+            matches!(owner.kind(), "interface_declaration" | "annotation_type_declaration")
+        });
+        if in_interface { "public" } else { "package" }.to_string()
+    }
+
+    /// Keyword modifiers (`public static final`), without annotations.
+    fn modifiers(&self, node: Node, source: &str) -> String {
+        let modifiers = match NodeHelper::child_by_kind(node, "modifiers") {
+            Some(m) => m,
+            None => return String::new(),
+        };
+
+        let mut cursor = modifiers.walk();
+        let keywords: Vec<&str> = modifiers
+            .children(&mut cursor)
+            .filter(|c| !matches!(c.kind(), "annotation" | "marker_annotation") && !c.is_extra())
+            .map(|c| NodeHelper::text(c, source))
+            .collect();
+        keywords.join(" ")
+    }
+
+    fn thrown_types(&self, node: Node, source: &str) -> Vec<String> {
+        NodeHelper::child_by_kind(node, "throws")
+            .map(|throws| {
+                let mut cursor = throws.walk();
+                throws
+                    .named_children(&mut cursor)
+                    .map(|t| NodeHelper::text(t, source).to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn type_list(&self, node: Node, source: &str) -> Vec<String> {
+        NodeHelper::child_by_kind(node, "type_list")
+            .map(|list| {
+                let mut cursor = list.walk();
+                list.named_children(&mut cursor)
+                    .map(|t| NodeHelper::text(t, source).to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn declarator_names(&self, node: Node, source: &str) -> Vec<String> {
+        let mut cursor = node.walk();
+        node.children_by_field_name("declarator", &mut cursor)
+            .filter_map(|d| NodeHelper::field_text(d, "name", source))
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// `static final` fields and interface constants.
+    fn is_constant(&self, node: Node, source: &str) -> bool {
+        if node.kind() == "constant_declaration" {
+            return true;
+        }
+        let modifiers = self.modifiers(node, source);
+        let words: Vec<&str> = modifiers.split_whitespace().collect();
+        words.contains(&"static") && words.contains(&"final")
+    }
+
+    fn extract_constants(&self, root: Node, source: &str) -> Vec<ConstSpec> {
+        let mut constants = Vec::new();
+
+        for kind in ["field_declaration", "constant_declaration"] {
+            for node in NodeHelper::find_all(root, kind) {
+                if !self.is_constant(node, source) {
+                    continue;
+                }
+                let owner = match self.enclosing_type(node) {
+                    Some(owner) => owner,
+                    None => continue,
+                };
+                let owner_name = NodeHelper::field_text(owner, "name", source)
+                    .map(|name| self.qualified_name(owner, name, source))
+                    .unwrap_or_default();
+
+                let type_name = NodeHelper::field_text(node, "type", source).map(|t| t.to_string());
+                let doc = NodeHelper::preceding_comment(node, source);
+
+                let mut cursor = node.walk();
+                for declarator in node.children_by_field_name("declarator", &mut cursor) {
+                    if let Some(name) = NodeHelper::field_text(declarator, "name", source) {
+                        constants.push((
+                            node.start_byte(),
+                            ConstSpec {
+                                name: format!("{}.{}", owner_name, name),
+                                type_name: type_name.clone(),
+                                value: NodeHelper::field_text(declarator, "value", source)
+                                    .map(|v| v.to_string()),
+                                doc: doc.clone(),
+                                export: None,
+                                visibility: Some(self.visibility(node, source)),
+                            },
+                        ));
+                    }
+                }
+            }
+        }
+
+        constants.sort_by_key(|(start, _)| *start);
+        constants.into_iter().map(|(_, spec)| spec).collect()
+    }
+
+    /// Exception classes declared in the file, then any other types named in `throws`.
+    fn extract_errors(&self, root: Node, source: &str) -> Vec<ErrorSpec> {
+        let classes: Vec<Node> = NodeHelper::find_all(root, "class_declaration")
+            .into_iter()
+            .filter(|c| !self.is_local(*c))
+            .collect();
+
+        // A class is an exception if it extends a Throwable, directly or through
+        // another exception class in this file
+        let mut exception_names: Vec<&str> = Vec::new();
+        loop {
+            let before = exception_names.len();
+            for class in &classes {
+                let name = NodeHelper::field_text(*class, "name", source).unwrap_or("");
+                let superclass = NodeHelper::field(*class, "superclass")
+                    .and_then(|s| s.named_child(0))
+                    .map(|t| NodeHelper::text(t, source))
+                    .unwrap_or("");
+                let base = superclass.split('<').next().unwrap_or("");
+                let base = base.rsplit('.').next().unwrap_or("");
+                let is_exception = THROWABLE_SUFFIXES.iter().any(|s| base.ends_with(s))
+                    || exception_names.contains(&base);
+                if is_exception && !exception_names.contains(&name) {
+                    exception_names.push(name);
+                }
+            }
+            if exception_names.len() == before {
+                break;
+            }
+        }
+
+        let mut errors = Vec::new();
+        for class in &classes {
+            let name = NodeHelper::field_text(*class, "name", source).unwrap_or("");
+            if !exception_names.contains(&name) {
+                continue;
+            }
+            errors.push(ErrorSpec {
+                name: self.qualified_name(*class, name, source),
+                message: self.super_message(*class, source),
+                doc: NodeHelper::preceding_comment(*class, source),
+            });
+        }
+
+        for kind in ["method_declaration", "constructor_declaration"] {
+            for method in NodeHelper::find_all(root, kind) {
+                let doc = NodeHelper::preceding_comment(method, source);
+                for thrown in self.thrown_types(method, source) {
+                    let declared = errors.iter().any(|e: &ErrorSpec| {
+                        e.name == thrown || e.name.ends_with(&format!(".{}", thrown))
+                    });
+                    if declared {
+                        continue;
+                    }
+                    errors.push(ErrorSpec {
+                        doc: doc.as_deref().and_then(|d| throws_description(d, &thrown)),
+                        name: thrown,
+                        message: None,
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// The string literal an exception passes to `super(...)`, if any.
+    fn super_message(&self, class: Node, source: &str) -> Option<String> {
+        NodeHelper::find_all(class, "explicit_constructor_invocation")
+            .into_iter()
+            .filter(|call| {
+                NodeHelper::field(*call, "constructor").map(|c| c.kind()) == Some("super")
+            })
+            .filter_map(|call| NodeHelper::field(call, "arguments"))
+            .flat_map(|args| NodeHelper::find_all(args, "string_literal"))
+            .map(|s| NodeHelper::text(s, source).trim_matches('"').to_string())
+            .next()
+    }
+
+    /// Prefix a type name with the types enclosing it (`Outer.Inner`).
+    fn qualified_name(&self, node: Node, name: &str, source: &str) -> String {
+        let mut parts = vec![name.to_string()];
+        let mut current = node;
+        while let Some(owner) = self.enclosing_type(current) {
+            if let Some(owner_name) = NodeHelper::field_text(owner, "name", source) {
+                parts.push(owner_name.to_string());
+            }
+            current = owner;
+        }
+        parts.reverse();
+        parts.join(".")
+    }
+
+    /// The type declaring a member, unless the member sits in a method body.
+    fn enclosing_type<'a>(&self, node: Node<'a>) -> Option<Node<'a>> {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if BODY_KINDS.contains(&parent.kind()) {
+                return None;
+            }
+            if TYPE_DECLARATIONS.contains(&parent.kind()) {
+                return Some(parent);
+            }
+            current = parent.parent();
+        }
+        None
+    }
+
+    /// Local and anonymous classes are not part of the API.
+    fn is_local(&self, node: Node) -> bool {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if BODY_KINDS.contains(&parent.kind()) {
+                return true;
+            }
+            current = parent.parent();
+        }
+        false
+    }
+}
+
+/// Description given for `exception` in a Javadoc `@throws` (or `@exception`) tag.
+fn throws_description(doc: &str, exception: &str) -> Option<String> {
+    doc.lines().find_map(|line| {
+        let rest = line
+            .trim()
+            .strip_prefix("@throws")
+            .or_else(|| line.trim().strip_prefix("@exception"))?;
+        let mut words = rest.split_whitespace();
+        let name = words.next()?;
+        if name != exception && !name.ends_with(&format!(".{}", exception)) {
+            return None;
+        }
+        let description: Vec<&str> = words.collect();
+        if description.is_empty() {
+            None
+        } else {
+            Some(description.join(" "))
+        }
+    })
+}
+
+impl Default for JavaPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for JavaPlugin {
+    fn name(&self) -> &'static str {
+        "java"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["java"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let language = tree_sitter_java::LANGUAGE;
+        let mut parser = TreeSitterParser::new(language.into())?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+
+        Ok(FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: self.extract_package(root, source, path),
+            imports: self.extract_imports(root, source),
+            types: self.extract_types(root, source),
+            methods: self.extract_methods(root, source),
+            constants: self.extract_constants(root, source),
+            errors: self.extract_errors(root, source),
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_class() {
+        let plugin = JavaPlugin::new();
+        let source = r#"
+package com.acme.users;
+
+import java.util.List;
+import static java.util.Objects.requireNonNull;
+
+/** Serves users. */
+@Service
+public class UserService extends BaseService<User> implements Finder<User>, Closeable {
+    public static final int MAX = 10;
+    private final Repo repo;
+
+    /**
+     * Finds a user.
+     * @throws NotFoundException when the user is missing
+     */
+    @GetMapping("/users/{id}")
+    public User find(@PathVariable("id") String id) throws NotFoundException {
+        return repo.find(id);
+    }
+
+    static class Page {}
+}
+"#;
+        let spec = plugin.extract(source, Path::new("UserService.java")).unwrap();
+        assert_eq!(spec.package, "com.acme.users");
+        assert_eq!(spec.imports, vec!["java.util.List", "static java.util.Objects.requireNonNull"]);
+
+        let class = &spec.types[0];
+        assert_eq!(class.name, "UserService class");
+        assert_eq!(class.doc.as_deref(), Some("Serves users."));
+        assert_eq!(class.embeds, vec!["BaseService<User>"]);
+        assert_eq!(class.implements.as_deref(), Some("Finder<User>, Closeable"));
+        assert_eq!(class.fields, vec!["private final Repo repo"]);
+        assert_eq!(spec.types[1].name, "UserService.Page class");
+
+        let attrs: Vec<(&str, &str, Option<&str>)> = class
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.target.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                ("Service", "class", None),
+                ("GetMapping", "method", Some("find")),
+                ("PathVariable", "parameter", Some("find.id")),
+            ]
+        );
+        assert_eq!(class.attributes[1].args, vec!["/users/{id}"]);
+
+        assert_eq!(spec.methods.len(), 1);
+        assert_eq!(
+            spec.methods[0].signature,
+            "public User find(String id) throws NotFoundException"
+        );
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("UserService"));
+
+        assert_eq!(spec.constants[0].name, "UserService.MAX");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("10"));
+
+        assert_eq!(spec.errors.len(), 1);
+        assert_eq!(spec.errors[0].name, "NotFoundException");
+        assert_eq!(spec.errors[0].doc.as_deref(), Some("when the user is missing"));
+    }
+
+    #[test]
+    fn test_extract_interface_record_enum() {
+        let plugin = JavaPlugin::new();
+        let source = r#"
+interface Finder<T> {
+    int LIMIT = 5;
+    T find(String id) throws NotFoundException;
+    default void close() {}
+}
+
+public record Point(int x, int y) implements Shape {
+    double length() { return 0; }
+}
+
+enum Color { RED("r"), GREEN; }
+
+@interface Audited { String value() default ""; }
+"#;
+        let spec = plugin.extract(source, Path::new("Shapes.java")).unwrap();
+        let kinds: Vec<&str> = spec.types.iter().map(|t| t.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["interface", "record", "enum", "annotation"]);
+
+        assert_eq!(spec.types[0].type_params, vec!["T"]);
+        assert_eq!(
+            spec.types[0].methods,
+            vec!["T find(String id) throws NotFoundException", "default void close()"]
+        );
+        assert_eq!(spec.types[1].fields, vec!["int x", "int y"]);
+        assert_eq!(spec.types[2].variants, vec!["RED(\"r\")", "GREEN"]);
+        assert_eq!(spec.types[3].methods, vec!["String value() default \"\""]);
+
+        assert_eq!(spec.constants[0].name, "Finder.LIMIT");
+        assert_eq!(spec.methods[0].signature, "double length()");
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("Point"));
+    }
+
+    #[test]
+    fn test_extract_custom_exceptions() {
+        let plugin = JavaPlugin::new();
+        let source = r#"
+/** Base failure of the users module. */
+public class UserException extends RuntimeException {
+    public UserException(String message) { super(message); }
+}
+
+class NotFoundException extends UserException {
+    NotFoundException(String id) { super("User not found: " + id); }
+}
+
+class Store {
+    void save() throws java.io.IOException, NotFoundException {}
+}
+"#;
+        let spec = plugin.extract(source, Path::new("Errors.java")).unwrap();
+        let names: Vec<&str> = spec.errors.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["UserException", "NotFoundException", "java.io.IOException"]);
+        assert_eq!(spec.errors[0].doc.as_deref(), Some("Base failure of the users module."));
+        assert_eq!(spec.errors[1].message.as_deref(), Some("User not found: "));
+    }
+}
@@ -0,0 +1,154 @@
+use anyhow::Result;
+use std::path::Path;
+
+use super::TypeScriptPlugin;
+use crate::parser::LanguagePlugin;
+use crate::spec::FileSpec;
+
+/// File extensions handled by the JavaScript plugin.
+pub(crate) const JS_EXTENSIONS: &[&str] = &["js", "jsx", "mjs", "cjs"];
+
+/// Plugin for extracting specifications from JavaScript source files.
+///
+/// The JavaScript grammar produces the same node kinds as TypeScript for everything
+/// JavaScript has, so extraction is shared with the TypeScript plugin. Types come
+/// from JSDoc comments (`@param`, `@returns`, `@type`, `@typedef`).
+pub struct JavaScriptPlugin;
+
+impl JavaScriptPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for JavaScriptPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for JavaScriptPlugin {
+    fn name(&self) -> &'static str {
+        "javascript"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        JS_EXTENSIONS
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        // The JavaScript grammar parses JSX as well
+        TypeScriptPlugin::new().extract_with_language(
+            source,
+            path,
+            tree_sitter_javascript::LANGUAGE.into(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_esm_with_jsdoc() {
+        let plugin = JavaScriptPlugin::new();
+        let source = r#"
+import { join } from './path.js';
+
+/**
+ * Greets someone.
+ * @param {string} name - who to greet
+ * @param {number} [times]
+ * @returns {string}
+ */
+export function greet(name, times) {
+    return name;
+}
+
+/** @type {number} */
+export const LIMIT = 10;
+
+/**
+ * A user.
+ * @typedef {Object} User
+ * @property {string} name
+ */
+
+export default class Store extends Base {
+    /** @type {Map<string, User>} */
+    items = new Map();
+
+    /**
+     * @param {string} key
+     * @returns {User | undefined}
+     */
+    get(key) {
+        return this.items.get(key);
+    }
+}
+"#;
+        let spec = plugin.extract(source, Path::new("store.js")).unwrap();
+        assert_eq!(spec.imports, vec!["./path.js"]);
+        assert_eq!(
+            spec.functions[0].signature,
+            "function greet(name: string, times?: number): string"
+        );
+        assert_eq!(spec.functions[0].export.as_deref(), Some("named"));
+        assert_eq!(spec.constants[0].type_name.as_deref(), Some("number"));
+
+        let user = spec.types.iter().find(|t| t.name == "User type").unwrap();
+        assert_eq!(user.fields, vec!["name: string"]);
+
+        let store = spec.types.iter().find(|t| t.name == "Store class").unwrap();
+        assert_eq!(store.embeds, vec!["Base"]);
+        assert_eq!(store.fields, vec!["items: Map<string, User>"]);
+        assert_eq!(store.methods, vec!["get(key: string): User | undefined"]);
+        assert_eq!(store.export.as_deref(), Some("default"));
+    }
+
+    #[test]
+    fn test_extract_jsx_component() {
+        let plugin = JavaScriptPlugin::new();
+        let source = r#"
+/** @param {{ title: string }} props */
+export const Header = (props) => <h1>{props.title}</h1>;
+"#;
+        let spec = plugin.extract(source, Path::new("Header.jsx")).unwrap();
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(
+            spec.functions[0].signature,
+            "const Header = (props: { title: string })"
+        );
+    }
+
+    #[test]
+    fn test_extract_commonjs() {
+        let plugin = JavaScriptPlugin::new();
+        let source = r#"
+const fs = require('fs');
+
+/**
+ * @param {string} path
+ */
+exports.load = function (path) {
+    return fs.readFileSync(path);
+};
+
+function save(path, data) {}
+
+module.exports = { save, write: save, close() {} };
+"#;
+        let spec = plugin.extract(source, Path::new("io.cjs")).unwrap();
+        assert_eq!(spec.imports, vec!["fs"]);
+
+        let signatures: Vec<&str> = spec.functions.iter().map(|f| f.signature.as_str()).collect();
+        assert_eq!(signatures, vec!["exports.load = function(path: string)", "function save(path, data)"]);
+        assert_eq!(spec.functions[0].export.as_deref(), Some("named"));
+        assert_eq!(spec.functions[1].export.as_deref(), Some("named"));
+
+        let names: Vec<&str> = spec.exports.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["load", "save", "write", "close"]);
+        assert_eq!(spec.exports[2].local.as_deref(), Some("save"));
+    }
+}
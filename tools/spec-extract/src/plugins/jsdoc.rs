@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+
+use crate::spec::TypeSpec;
+
+/// Type information read from a JSDoc comment.
+///
+/// Works on comments already cleaned by `NodeHelper::clean_comment`, where each
+/// line holds either description text or a single `@tag`.
+#[derive(Debug, Default)]
+pub struct JsDoc {
+    params: HashMap<String, (String, bool)>,
+    returns: Option<String>,
+    type_name: Option<String>,
+}
+
+impl JsDoc {
+    /// Parse the `@param`, `@returns` and `@type` tags of a doc comment.
+    pub fn parse(doc: &str) -> Self {
+        let mut jsdoc = Self::default();
+
+        for line in doc.lines() {
+            let line = line.trim();
+            if let Some(rest) = tag(line, &["@param", "@arg", "@argument"]) {
+                if let Some((ty, rest)) = braced_type(rest) {
+                    let (name, optional) = param_name(rest);
+                    // Nested properties (opts.name) describe the parent param
+                    if !name.is_empty() && !name.contains('.') {
+                        jsdoc.params.insert(name, (ty, optional));
+                    }
+                }
+            } else if let Some(rest) = tag(line, &["@returns", "@return"]) {
+                jsdoc.returns = braced_type(rest).map(|(ty, _)| ty);
+            } else if let Some(rest) = tag(line, &["@type"]) {
+                jsdoc.type_name = braced_type(rest).map(|(ty, _)| ty);
+            }
+        }
+
+        jsdoc
+    }
+
+    /// Type declared with `@type`, if any.
+    pub fn type_name(&self) -> Option<&str> {
+        self.type_name.as_deref()
+    }
+
+    /// Add JSDoc types to the untyped parameters and return type of a signature.
+    ///
+    /// Parameters that already carry a type annotation are left untouched.
+    pub fn annotate(&self, signature: &str) -> String {
+        if self.params.is_empty() && self.returns.is_none() {
+            return signature.to_string();
+        }
+
+        let open = match signature.find('(') {
+            Some(i) => i,
+            None => return signature.to_string(),
+        };
+        let close = match matching_paren(signature, open) {
+            Some(i) => i,
+            None => return signature.to_string(),
+        };
+
+        let params: Vec<String> = split_top_level(&signature[open + 1..close])
+            .into_iter()
+            .map(|p| self.annotate_param(p))
+            .collect();
+
+        let rest = &signature[close + 1..];
+        let return_type = match &self.returns {
+            Some(ty) if !rest.trim_start().starts_with(':') => format!(": {}", ty),
+            _ => String::new(),
+        };
+
+        format!(
+            "{}({}){}{}",
+            &signature[..open],
+            params.join(", "),
+            return_type,
+            rest
+        )
+    }
+
+    fn annotate_param(&self, param: &str) -> String {
+        let param = param.trim();
+        let (binding, default) = match param.split_once('=') {
+            Some((b, d)) => (b.trim(), Some(d.trim())),
+            None => (param, None),
+        };
+
+        // Already typed (TypeScript) or destructured
+        if binding.contains(':') || binding.starts_with('{') || binding.starts_with('[') {
+            return param.to_string();
+        }
+
+        let name = binding.trim_start_matches("...");
+        match (self.params.get(name), default) {
+            (Some((ty, _)), Some(default)) => format!("{}: {} = {}", binding, ty, default),
+            (Some((ty, optional)), None) => {
+                let marker = if *optional { "?" } else { "" };
+                format!("{}{}: {}", binding, marker, ty)
+            }
+            (None, _) => param.to_string(),
+        }
+    }
+}
+
+/// Build a type from a `@typedef` comment, with `@property` tags as fields.
+pub fn typedef(doc: &str) -> Option<TypeSpec> {
+    let mut name = None;
+    let mut fields = Vec::new();
+    let mut description = Vec::new();
+
+    for line in doc.lines() {
+        let line = line.trim();
+        if let Some(rest) = tag(line, &["@typedef"]) {
+            let (_, rest) = braced_type(rest).unwrap_or((String::new(), rest));
+            name = rest.split_whitespace().next().map(|n| n.to_string());
+        } else if let Some(rest) = tag(line, &["@property", "@prop"]) {
+            if let Some((ty, rest)) = braced_type(rest) {
+                let (field, optional) = param_name(rest);
+                if !field.is_empty() {
+                    let marker = if optional { "?" } else { "" };
+                    fields.push(format!("{}{}: {}", field, marker, ty));
+                }
+            }
+        } else if !line.starts_with('@') && !line.is_empty() {
+            description.push(line);
+        }
+    }
+
+    let name = name?;
+    Some(TypeSpec {
+        name: format!("{} type", name),
+        doc: if description.is_empty() { None } else { Some(description.join("\n")) },
+        kind: "type_alias".to_string(),
+        fields,
+        ..Default::default()
+    })
+}
+
+/// Strip one of the given tags from the start of a line.
+fn tag<'a>(line: &'a str, names: &[&str]) -> Option<&'a str> {
+    names.iter().find_map(|name| {
+        line.strip_prefix(name)
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace))
+            .map(|rest| rest.trim_start())
+    })
+}
+
+/// Split a leading `{Type}` (which may itself contain braces) from the rest of the line.
+fn braced_type(text: &str) -> Option<(String, &str)> {
+    if !text.starts_with('{') {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some((text[1..i].trim().to_string(), text[i + 1..].trim_start()));
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parameter name from `name`, `[name]` or `[name=default]`, and whether it is optional.
+fn param_name(text: &str) -> (String, bool) {
+    let word = text.split_whitespace().next().unwrap_or("");
+    match word.strip_prefix('[') {
+        Some(inner) => {
+            let inner = inner.trim_end_matches(']');
+            let name = inner.split('=').next().unwrap_or(inner);
+            (name.to_string(), true)
+        }
+        None => (word.to_string(), false),
+    }
+}
+
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text[open..].char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Split a parameter list on commas that are not nested in brackets.
+fn split_top_level(text: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    let mut prev = ' ';
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' | '{' | '<' => depth += 1,
+            // `=>` in a default value is not a closing bracket
+            '>' if prev == '=' => {}
+            ')' | ']' | '}' | '>' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&text[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+        prev = c;
+    }
+    if !text[start..].trim().is_empty() {
+        parts.push(&text[start..]);
+    }
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotate_signature() {
+        let doc = "Greets someone.\n@param {string} name - who\n@param {Object} [opts]\n@returns {string}";
+        let jsdoc = JsDoc::parse(doc);
+        assert_eq!(
+            jsdoc.annotate("function greet(name, opts = {})"),
+            "function greet(name: string, opts: Object = {}): string"
+        );
+        assert_eq!(
+            jsdoc.annotate("const greet = (name, opts, cb = () => 1)"),
+            "const greet = (name: string, opts?: Object, cb = () => 1): string"
+        );
+        assert_eq!(jsdoc.annotate("greet(name: Name)"), "greet(name: Name): string");
+    }
+
+    #[test]
+    fn test_typedef() {
+        let doc = "A user.\n@typedef {Object} User\n@property {string} name\n@property {number} [age]";
+        let spec = typedef(doc).unwrap();
+        assert_eq!(spec.name, "User type");
+        assert_eq!(spec.fields, vec!["name: string", "age?: number"]);
+        assert_eq!(spec.doc.as_deref(), Some("A user."));
+    }
+}
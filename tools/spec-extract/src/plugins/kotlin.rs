@@ -0,0 +1,859 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::lexer::{
+    is_modifier, parse_header, parse_param, qualify, split_top_level, squash, strip_default,
+    Header, LexOptions, Param, Scope, TokenCursor, TokenKind,
+};
+use super::sealed::link_sealed_variants;
+use crate::parser::LanguagePlugin;
+use crate::spec::{ConstSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+
+/// Keywords that may precede a declaration.
+const MODIFIERS: &[&str] = &[
+    "public", "private", "protected", "internal", "open", "final", "abstract", "sealed", "data",
+    "enum", "annotation", "inner", "value", "inline", "suspend", "override", "lateinit", "const",
+    "external", "operator", "infix", "tailrec", "companion", "expect", "actual", "vararg",
+    "noinline", "crossinline", "reified",
+];
+
+/// Keywords that start a declaration.
+const DECLARATION_KEYWORDS: &[&str] = &[
+    "package", "import", "class", "interface", "object", "fun", "val", "var", "typealias",
+    "constructor", "init",
+];
+
+/// Plugin for extracting specifications from Kotlin source files.
+///
+/// There is no Kotlin grammar among our tree-sitter dependencies, so this plugin walks
+/// declarations over the token stream from [`super::lexer`] and skips bodies wholesale.
+pub struct KotlinPlugin;
+
+impl KotlinPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn lex_options() -> LexOptions {
+        LexOptions::default()
+    }
+
+    /// Parse declarations until the closing brace of `scope` (or the end of input).
+    fn parse_members(
+        &self,
+        cursor: &mut TokenCursor,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+        constructors: &mut HashMap<usize, String>,
+    ) {
+        while !cursor.is_eof() {
+            match cursor.peek_text() {
+                "}" if scope.is_some() => return,
+                "(" | "[" | "{" => {
+                    cursor.skip_balanced();
+                    continue;
+                }
+                _ => {}
+            }
+
+            let header = self.parse_header(cursor);
+            match cursor.peek_text() {
+                "package" if scope.is_none() => {
+                    cursor.next();
+                    spec.package = cursor.dotted_name().unwrap_or_default();
+                }
+                "import" if scope.is_none() => {
+                    cursor.next();
+                    let mut import = cursor.dotted_name().unwrap_or_default();
+                    if cursor.at(".") && cursor.peek_at(1).is_some_and(|t| t.text == "*") {
+                        cursor.next();
+                        cursor.next();
+                        import.push_str(".*");
+                    }
+                    if cursor.eat("as") {
+                        import = format!("{} as {}", import, cursor.ident().unwrap_or(""));
+                    }
+                    spec.imports.push(import);
+                }
+                "class" | "interface" | "object" => {
+                    self.parse_class(cursor, header, scope, spec, constructors);
+                }
+                "fun" if cursor.peek_at(1).is_some_and(|t| t.text == "interface") => {
+                    cursor.next();
+                    self.parse_class(cursor, header, scope, spec, constructors);
+                }
+                "fun" => self.parse_function(cursor, header, scope, spec),
+                "val" | "var" => self.parse_property(cursor, header, scope, spec),
+                "typealias" => {
+                    cursor.next();
+                    let name = cursor.ident().unwrap_or("").to_string();
+                    let type_params = self.type_params(cursor);
+                    self.skip_rest(cursor);
+                    spec.types.push(TypeSpec {
+                        name: format!("{} type", qualify(scope, &name)),
+                        doc: header.doc,
+                        kind: "type_alias".to_string(),
+                        type_params,
+                        ..Default::default()
+                    });
+                }
+                "constructor" | "init" => {
+                    // Secondary constructors and initializers are implementation
+                    cursor.next();
+                    self.skip_rest(cursor);
+                }
+                _ if header.annotations.is_empty() && header.modifiers.is_empty() => {
+                    // Script statements and anything else we do not model
+                    cursor.next();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Parse annotations and modifiers, keeping the doc comment in front of them.
+    fn parse_header(&self, cursor: &mut TokenCursor) -> Header {
+        parse_header(cursor, |cursor, offset| is_modifier(cursor, offset, MODIFIERS))
+    }
+
+    /// Whether the current token starts a new declaration.
+    fn at_declaration(&self, cursor: &TokenCursor) -> bool {
+        let text = cursor.peek_text();
+        if text == "@" || DECLARATION_KEYWORDS.contains(&text) {
+            return true;
+        }
+        // `private set` is a property accessor, not a declaration
+        let mut offset = 0;
+        while is_modifier(cursor, offset, MODIFIERS) {
+            offset += 1;
+        }
+        offset > 0 && !matches!(cursor.peek_at(offset).map(|t| t.text), Some("get" | "set"))
+    }
+
+    /// Skip an initializer, body or accessor up to the next declaration.
+    fn skip_rest(&self, cursor: &mut TokenCursor) {
+        while let Some(token) = cursor.peek() {
+            let newline_before = token.newline_before;
+            match token.text {
+                ";" => {
+                    cursor.next();
+                    return;
+                }
+                "}" | ")" | "]" => return,
+                "(" | "[" | "{" => {
+                    cursor.skip_balanced();
+                    continue;
+                }
+                _ => {}
+            }
+            if newline_before && self.at_declaration(cursor) {
+                return;
+            }
+            cursor.next();
+        }
+    }
+
+    /// Read a type up to the end of its line or the next delimiter.
+    fn parse_type(&self, cursor: &mut TokenCursor) -> String {
+        let start = cursor.position();
+        while let Some(token) = cursor.peek() {
+            let text = token.text;
+            let stop = matches!(text, "=" | "{" | "}" | ";" | "," | ")" | "by" | "where")
+                || (token.newline_before && cursor.position() > start);
+            if stop {
+                break;
+            }
+            if matches!(text, "(" | "<") {
+                cursor.skip_balanced();
+            } else {
+                cursor.next();
+            }
+        }
+        squash(cursor.text(start, cursor.position()))
+    }
+
+    /// `<T : Comparable<T>, R>` as a list of parameters.
+    fn type_params(&self, cursor: &mut TokenCursor) -> Vec<String> {
+        if !cursor.at("<") {
+            return Vec::new();
+        }
+        let text = cursor.skip_balanced();
+        split_top_level(&text[1..text.len().saturating_sub(1)])
+            .iter()
+            .map(|p| squash(p))
+            .collect()
+    }
+
+
+    /// Parse a parenthesized parameter list at the cursor.
+    fn parse_params(&self, cursor: &mut TokenCursor) -> Vec<Param> {
+        let text = cursor.skip_balanced();
+        let inner = &text[1..text.len().saturating_sub(1)];
+        let options = Self::lex_options();
+
+        split_top_level(inner)
+            .iter()
+            .map(|param| {
+                parse_param(param, &options, |cursor, offset| {
+                    is_modifier(cursor, offset, MODIFIERS)
+                })
+            })
+            .collect()
+    }
+
+    fn parse_class(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+        constructors: &mut HashMap<usize, String>,
+    ) {
+        let keyword = cursor.next().map(|t| t.text).unwrap_or("");
+        let companion = header.has("companion");
+        let name = match cursor.peek() {
+            Some(t) if t.kind == TokenKind::Ident && !t.newline_before => t.text.to_string(),
+            _ => "Companion".to_string(),
+        };
+        if name != "Companion" || cursor.at("Companion") {
+            cursor.next();
+        }
+        let qualified = qualify(scope, &name);
+
+        let kind = match keyword {
+            "interface" if header.has("sealed") => "sealed_interface",
+            "interface" => "interface",
+            "object" if companion => "companion_object",
+            "object" => "object",
+            _ if header.has("enum") => "enum",
+            _ if header.has("annotation") => "annotation",
+            _ if header.has("data") => "data_class",
+            _ if header.has("sealed") => "sealed_class",
+            _ if header.has("abstract") => "abstract_class",
+            _ if header.has("value") || header.has("inline") => "value_class",
+            _ => "class",
+        };
+        let suffix = match kind {
+            "interface" | "sealed_interface" => "interface",
+            "object" | "companion_object" => "object",
+            "enum" | "annotation" => kind,
+            _ => "class",
+        };
+
+        let type_params = self.type_params(cursor);
+        let mut fields = Vec::new();
+        let mut attributes = header.attributes("class", None);
+
+        // Primary constructor, possibly `@Inject constructor(...)`
+        let saved = cursor.position();
+        self.parse_header(cursor);
+        let has_keyword = cursor.eat("constructor");
+        let has_params = cursor.peek().is_some_and(|t| t.text == "(" && !t.newline_before);
+        if has_params {
+            let params = self.parse_params(cursor);
+            for param in &params {
+                if param.property {
+                    fields.push(strip_default(&param.text, true));
+                }
+                let member = format!("constructor.{}", param.name);
+                attributes.extend(param.annotations.iter().cloned().map(|mut attr| {
+                    attr.member = Some(member.clone());
+                    attr
+                }));
+            }
+            let texts: Vec<&str> = params.iter().map(|p| p.text.as_str()).collect();
+            constructors.insert(spec.types.len(), format!("({})", texts.join(", ")));
+        } else if !has_keyword {
+            cursor.reset(saved);
+        }
+
+        // Supertypes: a constructor call marks the superclass
+        let mut embeds = Vec::new();
+        let mut interfaces = Vec::new();
+        if cursor.eat(":") {
+            loop {
+                let start = cursor.position();
+                while let Some(token) = cursor.peek() {
+                    let text = token.text;
+                    let end = matches!(text, "," | "{" | "(" | "}" | "by" | "where")
+                        || (token.newline_before && cursor.position() > start);
+                    if end {
+                        break;
+                    }
+                    if text == "<" {
+                        cursor.skip_balanced();
+                    } else {
+                        cursor.next();
+                    }
+                }
+                let base = squash(cursor.text(start, cursor.position()));
+                let is_class = cursor.at("(");
+                if is_class {
+                    cursor.skip_balanced();
+                }
+                if cursor.eat("by") {
+                    self.parse_type(cursor);
+                }
+                if !base.is_empty() {
+                    if is_class || suffix == "interface" {
+                        embeds.push(base);
+                    } else {
+                        interfaces.push(base);
+                    }
+                }
+                if !cursor.eat(",") {
+                    break;
+                }
+            }
+        }
+        if cursor.eat("where") {
+            self.parse_type(cursor);
+            while cursor.eat(",") {
+                self.parse_type(cursor);
+            }
+        }
+
+        let index = spec.types.len();
+        spec.types.push(TypeSpec {
+            name: format!("{} {}", qualified, suffix),
+            doc: header.doc,
+            kind: kind.to_string(),
+            fields,
+            embeds,
+            implements: Some(interfaces.join(", ")).filter(|s| !s.is_empty()),
+            type_params,
+            attributes,
+            ..Default::default()
+        });
+
+        if cursor.eat("{") {
+            let owner = match scope {
+                Some(outer) if companion => outer.name.clone(),
+                _ => qualified.clone(),
+            };
+            let inner = Scope { index, name: qualified, kind, owner };
+            if kind == "enum" {
+                let variants = self.parse_enum_entries(cursor);
+                spec.types[index].variants = variants;
+            }
+            self.parse_members(cursor, Some(&inner), spec, constructors);
+            cursor.eat("}");
+        }
+    }
+
+    /// Enum entries up to the `;` that separates them from members.
+    fn parse_enum_entries(&self, cursor: &mut TokenCursor) -> Vec<String> {
+        let mut variants = Vec::new();
+        while !cursor.at("}") && !cursor.eat(";") && !self.at_declaration(cursor) {
+            self.parse_header(cursor);
+            let name = match cursor.ident() {
+                Some(name) => name,
+                None => break,
+            };
+            let args = if cursor.at("(") { squash(cursor.skip_balanced()) } else { String::new() };
+            variants.push(format!("{}{}", name, args));
+            if cursor.at("{") {
+                cursor.skip_balanced();
+            }
+            if !cursor.eat(",") {
+                cursor.eat(";");
+                break;
+            }
+        }
+        variants
+    }
+
+    fn parse_function(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        cursor.next();
+        let type_params = if cursor.at("<") {
+            format!("{} ", squash(cursor.skip_balanced()))
+        } else {
+            String::new()
+        };
+
+        // `name` or `Receiver.name`
+        let start = cursor.position();
+        while let Some(token) = cursor.peek() {
+            let text = token.text;
+            if matches!(text, "(" | "{" | "=" | "}") {
+                break;
+            }
+            if text == "<" {
+                cursor.skip_balanced();
+            } else {
+                cursor.next();
+            }
+        }
+        let head = squash(cursor.text(start, cursor.position()));
+        let (receiver, name) = split_receiver(&head);
+
+        let params = if cursor.at("(") { self.parse_params(cursor) } else { Vec::new() };
+        let returns = if cursor.eat(":") { Some(self.parse_type(cursor)) } else { None };
+        if cursor.eat("where") {
+            self.parse_type(cursor);
+            while cursor.eat(",") {
+                self.parse_type(cursor);
+            }
+        }
+        if cursor.at("{") {
+            cursor.skip_balanced();
+        } else if cursor.eat("=") {
+            self.skip_rest(cursor);
+        }
+
+        let texts: Vec<&str> = params.iter().map(|p| p.text.as_str()).collect();
+        let signature = format!(
+            "{}fun {}{}({}){}",
+            header.prefix(),
+            type_params,
+            head,
+            texts.join(", "),
+            returns.map(|r| format!(": {}", r)).unwrap_or_default()
+        );
+
+        let scope = match scope {
+            Some(scope) => scope,
+            None => {
+                let func = FuncSpec {
+                    signature,
+                    doc: header.doc,
+                    receiver: receiver.clone(),
+                    ..Default::default()
+                };
+                // Extension functions are methods on their receiver type
+                if receiver.is_some() {
+                    spec.methods.push(func);
+                } else {
+                    spec.functions.push(func);
+                }
+                return;
+            }
+        };
+
+        let owner = &mut spec.types[scope.index];
+        owner.attributes.extend(header.attributes("method", Some(name.clone())));
+        for param in params {
+            let member = format!("{}.{}", name, param.name);
+            owner.attributes.extend(param.annotations.into_iter().map(|mut attr| {
+                attr.member = Some(member.clone());
+                attr
+            }));
+        }
+
+        // Class methods become FileSpec methods; interfaces list signatures
+        if matches!(scope.kind, "interface" | "sealed_interface") {
+            owner.methods.push(signature);
+        } else {
+            spec.methods.push(FuncSpec {
+                signature,
+                doc: header.doc,
+                receiver: Some(scope.name.clone()),
+                ..Default::default()
+            });
+        }
+    }
+
+    fn parse_property(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        let keyword = cursor.next().map(|t| t.text).unwrap_or("");
+        let type_params = if cursor.at("<") {
+            format!("{} ", squash(cursor.skip_balanced()))
+        } else {
+            String::new()
+        };
+
+        let start = cursor.position();
+        while let Some(token) = cursor.peek() {
+            let text = token.text;
+            let end = matches!(text, ":" | "=" | "by" | ";" | "}")
+                || (token.newline_before && cursor.position() > start);
+            if end {
+                break;
+            }
+            if text == "<" {
+                cursor.skip_balanced();
+            } else {
+                cursor.next();
+            }
+        }
+        let head = squash(cursor.text(start, cursor.position()));
+        let (_, name) = split_receiver(&head);
+
+        let type_name = if cursor.eat(":") { Some(self.parse_type(cursor)) } else { None };
+        let value = if cursor.eat("=") {
+            let start = cursor.position();
+            self.skip_rest(cursor);
+            let text = squash(cursor.text(start, cursor.position()));
+            Some(text.trim_end_matches(';').trim_end().to_string())
+        } else {
+            self.skip_rest(cursor);
+            None
+        };
+
+        if header.has("const") {
+            let name = match scope {
+                Some(scope) => format!("{}.{}", scope.owner, name),
+                None => name,
+            };
+            spec.constants.push(ConstSpec {
+                name,
+                type_name,
+                value,
+                doc: header.doc,
+                ..Default::default()
+            });
+            return;
+        }
+
+        match scope {
+            Some(scope) => {
+                let field = format!(
+                    "{}{} {}{}{}",
+                    header.prefix(),
+                    keyword,
+                    type_params,
+                    head,
+                    type_name.map(|t| format!(": {}", t)).unwrap_or_default()
+                );
+                let owner = &mut spec.types[scope.index];
+                owner.fields.push(field);
+                owner.attributes.extend(header.attributes("property", Some(name)));
+            }
+            None => spec.variables.push(VarSpec {
+                name: head,
+                type_name,
+                doc: header.doc,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+/// Split `List<T>.second` into the receiver type and the member name.
+fn split_receiver(head: &str) -> (Option<String>, String) {
+    let mut depth = 0;
+    let mut split = None;
+    for (i, c) in head.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            '.' if depth == 0 => split = Some(i),
+            _ => {}
+        }
+    }
+    match split {
+        Some(i) => (Some(head[..i].to_string()), head[i + 1..].to_string()),
+        None => (None, head.to_string()),
+    }
+}
+
+impl Default for KotlinPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for KotlinPlugin {
+    fn name(&self) -> &'static str {
+        "kotlin"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["kt", "kts"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let mut cursor = TokenCursor::new(source, &Self::lex_options());
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let mut constructors = HashMap::new();
+        self.parse_members(&mut cursor, None, &mut spec, &mut constructors);
+        link_sealed_variants(&mut spec.types, &constructors);
+
+        if spec.package.is_empty() {
+            // Default package: fall back to the file name
+            spec.package =
+                path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_classes_and_functions() {
+        let plugin = KotlinPlugin::new();
+        let source = r#"
+package com.acme.users
+
+import kotlinx.coroutines.flow.*
+import com.acme.db.Repo as Repository
+
+const val MAX_USERS = 100
+val defaultTimeout: Long = 30_000
+
+/** Serves users. */
+@Service
+class UserService @Inject constructor(
+    private val repo: Repository,
+    @Named("cache") val cache: Cache = Cache(),
+) : BaseService(repo), Closeable {
+    var lastSeen: String? = null
+        private set
+
+    /** Finds a user. */
+    @Get("/users/{id}")
+    suspend fun find(@Path("id") id: String): User? = repo.find(id)
+
+    override fun close() {
+        cache.clear()
+    }
+
+    companion object {
+        const val PAGE_SIZE: Int = 20
+        fun create(): UserService = TODO()
+    }
+}
+
+/** Turns a name into a URL slug. */
+fun String.slug(): String = lowercase().replace(" ", "-")
+
+fun <T> List<T>.second(): T = this[1]
+
+inline fun <reified T> typeName(): String = T::class.simpleName ?: ""
+"#;
+        let spec = plugin.extract(source, Path::new("UserService.kt")).unwrap();
+        assert_eq!(spec.package, "com.acme.users");
+        assert_eq!(
+            spec.imports,
+            vec!["kotlinx.coroutines.flow.*", "com.acme.db.Repo as Repository"]
+        );
+
+        assert_eq!(spec.constants[0].name, "MAX_USERS");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("100"));
+        assert_eq!(spec.constants[1].name, "UserService.PAGE_SIZE");
+        assert_eq!(spec.constants[1].type_name.as_deref(), Some("Int"));
+        assert_eq!(spec.variables[0].name, "defaultTimeout");
+        assert_eq!(spec.variables[0].type_name.as_deref(), Some("Long"));
+
+        let class = &spec.types[0];
+        assert_eq!(class.name, "UserService class");
+        assert_eq!(class.doc.as_deref(), Some("Serves users."));
+        assert_eq!(class.embeds, vec!["BaseService"]);
+        assert_eq!(class.implements.as_deref(), Some("Closeable"));
+        assert_eq!(
+            class.fields,
+            vec!["private val repo: Repository", "val cache: Cache", "var lastSeen: String?"]
+        );
+
+        let attrs: Vec<(&str, &str, Option<&str>)> = class
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.target.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                ("Service", "class", None),
+                ("Named", "parameter", Some("constructor.cache")),
+                ("Get", "method", Some("find")),
+                ("Path", "parameter", Some("find.id")),
+            ]
+        );
+        assert_eq!(class.attributes[2].args, vec!["/users/{id}"]);
+
+        assert_eq!(spec.types[1].name, "UserService.Companion object");
+        assert_eq!(spec.types[1].kind, "companion_object");
+
+        let methods: Vec<(&str, Option<&str>)> = spec
+            .methods
+            .iter()
+            .map(|m| (m.signature.as_str(), m.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                ("suspend fun find(id: String): User?", Some("UserService")),
+                ("override fun close()", Some("UserService")),
+                ("fun create(): UserService", Some("UserService.Companion")),
+                ("fun String.slug(): String", Some("String")),
+                ("fun <T> List<T>.second(): T", Some("List<T>")),
+            ]
+        );
+        assert_eq!(spec.methods[0].doc.as_deref(), Some("Finds a user."));
+        assert_eq!(spec.methods[3].doc.as_deref(), Some("Turns a name into a URL slug."));
+
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(spec.functions[0].signature, "inline fun <reified T> typeName(): String");
+    }
+
+    #[test]
+    fn test_extract_templates_annotations_and_generics() {
+        let plugin = KotlinPlugin::new();
+        let source = r#"
+const val GREETING = "Hello, ${user.name ?: "guest"} {not a block}"
+const val TEMPLATE = """{ "id": ${id}, "tags": [${tags.joinToString { "\"$it\"" }}] }"""
+
+@Table(
+    name = "users",
+    indexes = [Index(columns = ["email"])]
+)
+@JsonIgnoreProperties(ignoreUnknown = true)
+data class UserRow(
+    val id: Long,
+    val tags: Map<String, List<Set<String>>> = emptyMap(),
+) {
+    fun label(): String = "${id}: ${tags.keys.joinToString(", ") { "<$it>" }}"
+}
+
+fun group(rows: List<Pair<String, Map<Int, List<Row>>>>): Map<String, List<Row>> = TODO()
+"#;
+        let spec = plugin.extract(source, Path::new("Rows.kt")).unwrap();
+        let constants: Vec<(&str, Option<&str>)> =
+            spec.constants.iter().map(|c| (c.name.as_str(), c.value.as_deref())).collect();
+        assert_eq!(
+            constants,
+            vec![
+                ("GREETING", Some(r#""Hello, ${user.name ?: "guest"} {not a block}""#)),
+                (
+                    "TEMPLATE",
+                    Some(r#""""{ "id": ${id}, "tags": [${tags.joinToString { "\"$it\"" }}] }""""#),
+                ),
+            ]
+        );
+
+        let row = &spec.types[0];
+        assert_eq!(row.name, "UserRow class");
+        assert_eq!(row.fields, vec!["val id: Long", "val tags: Map<String, List<Set<String>>>"]);
+        let attrs: Vec<&str> = row.attributes.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(attrs, vec!["Table", "JsonIgnoreProperties"]);
+        assert_eq!(
+            row.attributes[0].args,
+            vec!["name = \"users\"", "indexes = [Index(columns = [\"email\"])]"]
+        );
+
+        assert_eq!(spec.methods[0].signature, "fun label(): String");
+        assert_eq!(
+            spec.functions[0].signature,
+            "fun group(rows: List<Pair<String, Map<Int, List<Row>>>>): Map<String, List<Row>>"
+        );
+    }
+
+    #[test]
+    fn test_extract_sealed_hierarchy() {
+        let plugin = KotlinPlugin::new();
+        let source = r#"
+sealed class Result<out T> {
+    data class Success<T>(val data: T) : Result<T>()
+    data class Failure(val error: Throwable) : Result<Nothing>()
+    object Loading : Result<Nothing>()
+}
+
+sealed interface Event
+data class Click(val x: Int, val y: Int) : Event
+object Close : Event
+
+enum class Color(val rgb: Int) {
+    RED(0xFF0000),
+    GREEN(0x00FF00) {
+        override fun label() = "green"
+    };
+
+    open fun label(): String = name.lowercase()
+}
+
+interface Repo<T> : AutoCloseable {
+    val size: Int
+    suspend fun load(id: String): T
+}
+
+fun interface Handler {
+    fun handle(event: Event)
+}
+
+typealias Handlers = List<Handler>
+"#;
+        let spec = plugin.extract(source, Path::new("Result.kt")).unwrap();
+        let names: Vec<(&str, &str)> =
+            spec.types.iter().map(|t| (t.name.as_str(), t.kind.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("Result class", "sealed_class"),
+                ("Result.Success class", "data_class"),
+                ("Result.Failure class", "data_class"),
+                ("Result.Loading object", "object"),
+                ("Event interface", "sealed_interface"),
+                ("Click class", "data_class"),
+                ("Close object", "object"),
+                ("Color enum", "enum"),
+                ("Repo interface", "interface"),
+                ("Handler interface", "interface"),
+                ("Handlers type", "type_alias"),
+            ]
+        );
+
+        assert_eq!(spec.types[0].type_params, vec!["out T"]);
+        assert_eq!(
+            spec.types[0].variants,
+            vec!["Success(val data: T)", "Failure(val error: Throwable)", "Loading"]
+        );
+        assert_eq!(spec.types[4].variants, vec!["Click(val x: Int, val y: Int)", "Close"]);
+        assert_eq!(spec.types[5].fields, vec!["val x: Int", "val y: Int"]);
+
+        let color = &spec.types[7];
+        assert_eq!(color.variants, vec!["RED(0xFF0000)", "GREEN(0x00FF00)"]);
+        assert_eq!(color.fields, vec!["val rgb: Int"]);
+
+        let repo = &spec.types[8];
+        assert_eq!(repo.embeds, vec!["AutoCloseable"]);
+        assert_eq!(repo.fields, vec!["val size: Int"]);
+        assert_eq!(repo.methods, vec!["suspend fun load(id: String): T"]);
+        assert_eq!(spec.types[9].methods, vec!["fun handle(event: Event)"]);
+
+        assert_eq!(spec.methods.len(), 1);
+        assert_eq!(spec.methods[0].signature, "open fun label(): String");
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("Color"));
+    }
+
+    #[test]
+    fn test_extract_script() {
+        let plugin = KotlinPlugin::new();
+        let source = r#"
+plugins {
+    kotlin("jvm") version "2.0.0"
+}
+
+val ktorVersion: String by project
+
+dependencies {
+    implementation("io.ktor:ktor-server-core:$ktorVersion")
+}
+
+fun isCi() = System.getenv("CI") != null
+"#;
+        let spec = plugin.extract(source, Path::new("build.gradle.kts")).unwrap();
+        assert_eq!(spec.package, "build.gradle");
+        assert_eq!(spec.variables[0].name, "ktorVersion");
+        assert_eq!(spec.functions[0].signature, "fun isCi()");
+        assert!(spec.types.is_empty());
+    }
+}
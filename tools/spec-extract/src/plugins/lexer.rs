@@ -0,0 +1,649 @@
+//! A small tokenizer for brace-delimited languages we have no tree-sitter grammar for.
+//!
+//! It only knows enough to walk declarations: identifiers, literals, punctuation,
+//! and comments (with doc comments attached to the token that follows them).
+//! Plugins built on it parse the declaration structure themselves and skip over
+//! bodies and expressions with [`TokenCursor::skip_balanced`]; the annotations,
+//! modifiers and parameters in front of and inside declarations are read the same
+//! way in all of them, with [`parse_header`] and [`parse_param`].
+
+use crate::parser::NodeHelper;
+use crate::spec::AttributeSpec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenKind {
+    Ident,
+    Number,
+    Str,
+    Punct,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    pub start: usize,
+    pub end: usize,
+    /// A line break separates this token from the previous one
+    pub newline_before: bool,
+    /// Doc comment directly before this token, with comment markers removed
+    pub doc: Option<String>,
+}
+
+/// Lexical details that differ between languages.
+#[derive(Debug, Clone)]
+pub(crate) struct LexOptions {
+    /// Comment prefixes that mark documentation (`/**`, `///`)
+    pub doc_prefixes: &'static [&'static str],
+    /// Whether `/* /* */ */` nests
+    pub nested_comments: bool,
+    /// Whether `"""` starts a multi-line string
+    pub triple_quotes: bool,
+    /// Whether `${...}` inside strings holds code (and may contain quotes)
+    pub templates: bool,
+    /// Whether `'` delimits strings rather than single characters
+    pub single_quote_strings: bool,
+    /// Punctuation made of several characters, longest first
+    pub operators: &'static [&'static str],
+}
+
+impl Default for LexOptions {
+    fn default() -> Self {
+        Self {
+            doc_prefixes: &["/**", "///"],
+            nested_comments: true,
+            triple_quotes: true,
+            templates: true,
+            single_quote_strings: false,
+            operators: &["->", "=>", "::", "?."],
+        }
+    }
+}
+
+/// Split `source` into tokens, dropping whitespace and comments.
+pub(crate) fn tokenize<'a>(source: &'a str, options: &LexOptions) -> Vec<Token<'a>> {
+    let bytes = source.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    let mut newline = false;
+    let mut doc: Option<String> = None;
+
+    while pos < bytes.len() {
+        let c = bytes[pos];
+
+        if c.is_ascii_whitespace() {
+            newline |= c == b'\n';
+            pos += 1;
+            continue;
+        }
+
+        if source[pos..].starts_with("//") {
+            let end = source[pos..].find('\n').map(|i| pos + i).unwrap_or(bytes.len());
+            let text = &source[pos..end];
+            let is_doc = options.doc_prefixes.iter().any(|p| text.starts_with(p));
+            if is_doc && !text.starts_with("////") {
+                let line = NodeHelper::clean_comment(text);
+                // Consecutive `///` lines form one comment
+                doc = Some(match doc.take() {
+                    Some(previous) if !line.is_empty() => format!("{}\n{}", previous, line),
+                    Some(previous) => previous,
+                    None => line,
+                });
+            } else {
+                doc = None;
+            }
+            pos = end;
+            continue;
+        }
+
+        if source[pos..].starts_with("/*") {
+            let end = block_comment_end(source, pos, options.nested_comments);
+            let text = &source[pos..end];
+            let is_doc = options.doc_prefixes.iter().any(|p| text.starts_with(p)) && text != "/**/";
+            doc = if is_doc { Some(NodeHelper::clean_comment(text)) } else { None };
+            pos = end;
+            continue;
+        }
+
+        let start = pos;
+        let kind = if c == b'"' || (c == b'\'' && options.single_quote_strings) {
+            pos = string_end(source, pos, options);
+            TokenKind::Str
+        } else if c == b'\'' {
+            // Character literal
+            pos += 1;
+            while pos < bytes.len() && bytes[pos] != b'\'' && bytes[pos] != b'\n' {
+                pos += if bytes[pos] == b'\\' { 2 } else { 1 };
+            }
+            pos = (pos + 1).min(bytes.len());
+            TokenKind::Str
+        } else if c == b'`' {
+            // Backquoted identifier
+            pos += 1;
+            while pos < bytes.len() && bytes[pos] != b'`' && bytes[pos] != b'\n' {
+                pos += 1;
+            }
+            pos = (pos + 1).min(bytes.len());
+            TokenKind::Ident
+        } else if c.is_ascii_digit() {
+            while pos < bytes.len()
+                && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_' || bytes[pos] == b'.')
+            {
+                // `1..10` is a range, not a decimal point
+                if bytes[pos] == b'.' && !bytes.get(pos + 1).is_some_and(|b| b.is_ascii_digit()) {
+                    break;
+                }
+                pos += 1;
+            }
+            TokenKind::Number
+        } else if c.is_ascii_alphabetic() || c == b'_' || c == b'$' || c >= 0x80 {
+            while pos < bytes.len() {
+                let b = bytes[pos];
+                if b.is_ascii_alphanumeric() || b == b'_' || b == b'$' {
+                    pos += 1;
+                } else if b >= 0x80 {
+                    // Step over a whole UTF-8 character
+                    pos += source[pos..].chars().next().map(|ch| ch.len_utf8()).unwrap_or(1);
+                } else {
+                    break;
+                }
+            }
+            TokenKind::Ident
+        } else {
+            let op = options.operators.iter().find(|op| source[pos..].starts_with(*op));
+            pos += op.map(|op| op.len()).unwrap_or(1);
+            TokenKind::Punct
+        };
+
+        tokens.push(Token {
+            kind,
+            text: &source[start..pos],
+            start,
+            end: pos,
+            newline_before: newline,
+            doc: doc.take(),
+        });
+        newline = false;
+    }
+
+    tokens
+}
+
+fn block_comment_end(source: &str, start: usize, nested: bool) -> usize {
+    let mut depth = 0;
+    let mut pos = start;
+    while pos < source.len() {
+        if source[pos..].starts_with("/*") {
+            depth += 1;
+            pos += 2;
+            if !nested && depth > 1 {
+                depth = 1;
+            }
+        } else if source[pos..].starts_with("*/") {
+            depth -= 1;
+            pos += 2;
+            if depth == 0 {
+                return pos;
+            }
+        } else {
+            pos += source[pos..].chars().next().map(|c| c.len_utf8()).unwrap_or(1);
+        }
+    }
+    source.len()
+}
+
+fn string_end(source: &str, start: usize, options: &LexOptions) -> usize {
+    let bytes = source.as_bytes();
+    let quote = bytes[start];
+
+    if options.triple_quotes && bytes[start..].starts_with(&[quote, quote, quote]) {
+        let delimiter = &source[start..start + 3];
+        return source[start + 3..]
+            .find(delimiter)
+            .map(|i| start + 3 + i + 3)
+            .unwrap_or(source.len());
+    }
+
+    let mut pos = start + 1;
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'\\' => pos += 2,
+            b'\n' => return pos,
+            b'$' if options.templates && bytes.get(pos + 1) == Some(&b'{') => {
+                // Skip the template expression, which may itself contain strings
+                let mut depth = 0;
+                while pos < bytes.len() {
+                    match bytes[pos] {
+                        b'{' => depth += 1,
+                        b'}' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                break;
+                            }
+                        }
+                        b'"' | b'\'' => {
+                            pos = string_end(source, pos, options);
+                            continue;
+                        }
+                        _ => {}
+                    }
+                    pos += 1;
+                }
+                pos += 1;
+            }
+            b if b == quote => return pos + 1,
+            _ => pos += 1,
+        }
+    }
+    bytes.len()
+}
+
+/// Cursor over a token stream with helpers for declaration parsing.
+pub(crate) struct TokenCursor<'a> {
+    tokens: Vec<Token<'a>>,
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> TokenCursor<'a> {
+    pub fn new(source: &'a str, options: &LexOptions) -> Self {
+        Self {
+            tokens: tokenize(source, options),
+            source,
+            pos: 0,
+        }
+    }
+
+    pub fn is_eof(&self) -> bool {
+        self.pos >= self.tokens.len()
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Move back to a position returned by [`TokenCursor::position`].
+    pub fn reset(&mut self, position: usize) {
+        self.pos = position;
+    }
+
+    pub fn peek(&self) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos)
+    }
+
+    pub fn peek_at(&self, offset: usize) -> Option<&Token<'a>> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    /// Column of the current token (0-based), or 0 at the end of input.
+    pub fn column(&self) -> usize {
+        self.peek()
+            .map(|t| t.start - self.source[..t.start].rfind('\n').map(|i| i + 1).unwrap_or(0))
+            .unwrap_or(0)
+    }
+
+    /// Text of the current token, or "" at the end of input.
+    pub fn peek_text(&self) -> &'a str {
+        self.tokens.get(self.pos).map(|t| t.text).unwrap_or("")
+    }
+
+    pub fn next(&mut self) -> Option<Token<'a>> {
+        let token = self.tokens.get(self.pos).cloned();
+        if token.is_some() {
+            self.pos += 1;
+        }
+        token
+    }
+
+    pub fn at(&self, text: &str) -> bool {
+        self.peek_text() == text
+    }
+
+    /// Consume the current token if it is `text`.
+    pub fn eat(&mut self, text: &str) -> bool {
+        if self.at(text) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Consume an identifier.
+    pub fn ident(&mut self) -> Option<&'a str> {
+        match self.peek() {
+            Some(token) if token.kind == TokenKind::Ident => {
+                let text = token.text;
+                self.pos += 1;
+                Some(text)
+            }
+            _ => None,
+        }
+    }
+
+    /// Consume a dotted name (`a.b.c`), returning it.
+    pub fn dotted_name(&mut self) -> Option<String> {
+        let mut name = self.ident()?.to_string();
+        while self.at(".") && self.peek_at(1).is_some_and(|t| t.kind == TokenKind::Ident) {
+            self.pos += 1;
+            name.push('.');
+            name.push_str(self.ident().unwrap_or(""));
+        }
+        Some(name)
+    }
+
+    /// Skip a bracketed group starting at the current token (`(`, `[`, `{` or `<`),
+    /// returning the source text it spans.
+    pub fn skip_balanced(&mut self) -> &'a str {
+        let open = self.peek_text();
+        let close = match open {
+            "(" => ")",
+            "[" => "]",
+            "{" => "}",
+            "<" => ">",
+            _ => return "",
+        };
+        let start = self.pos;
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            if token.text == open {
+                depth += 1;
+            } else if token.text == close {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            } else if open == "<" && matches!(token.text, ";" | "{" | "}" | "=") {
+                // Not a type argument list after all (`a < b`)
+                self.pos -= 1;
+                break;
+            }
+        }
+        self.text(start, self.pos)
+    }
+
+    /// Source text covering tokens `from..to`.
+    pub fn text(&self, from: usize, to: usize) -> &'a str {
+        if from >= to || from >= self.tokens.len() {
+            return "";
+        }
+        let end = self.tokens[(to - 1).min(self.tokens.len() - 1)].end;
+        &self.source[self.tokens[from].start..end]
+    }
+}
+
+/// Collapse runs of whitespace (including line breaks) into single spaces.
+pub(crate) fn squash(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Split on commas that are not nested in brackets or strings.
+pub(crate) fn split_top_level(text: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut prev = ' ';
+
+    for c in text.chars() {
+        match quote {
+            Some(q) => {
+                if c == q && prev != '\\' {
+                    quote = None;
+                }
+            }
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' | '{' | '<' => depth += 1,
+                // `->` and `=>` are not closing brackets
+                '>' if prev == '-' || prev == '=' => {}
+                ')' | ']' | '}' | '>' => depth -= 1,
+                ',' if depth == 0 => {
+                    parts.push(current.trim().to_string());
+                    current.clear();
+                    prev = c;
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        current.push(c);
+        prev = c;
+    }
+
+    if !current.trim().is_empty() {
+        parts.push(current.trim().to_string());
+    }
+    parts
+}
+
+/// A string literal's content without its quotes.
+pub(crate) fn unquote(text: &str) -> String {
+    for delimiter in ["\"\"\"", "'''", "\"", "'"] {
+        if let Some(inner) = text.strip_prefix(delimiter).and_then(|t| t.strip_suffix(delimiter)) {
+            return inner.to_string();
+        }
+    }
+    text.to_string()
+}
+
+/// Annotations, modifiers and doc comment in front of a declaration.
+#[derive(Default)]
+pub(crate) struct Header {
+    pub doc: Option<String>,
+    pub annotations: Vec<(String, Vec<String>)>,
+    pub modifiers: Vec<String>,
+    /// Column of the declaration's first token, which an indented body must exceed
+    pub column: usize,
+}
+
+impl Header {
+    pub fn has(&self, modifier: &str) -> bool {
+        self.modifiers.iter().any(|m| m == modifier)
+    }
+
+    pub fn annotated(&self, name: &str) -> bool {
+        self.annotations.iter().any(|(a, _)| a.eq_ignore_ascii_case(name))
+    }
+
+    pub fn prefix(&self) -> String {
+        self.modifiers.iter().map(|m| format!("{} ", m)).collect()
+    }
+
+    pub fn attributes(&self, target: &str, member: Option<String>) -> Vec<AttributeSpec> {
+        self.annotations
+            .iter()
+            .map(|(name, args)| AttributeSpec {
+                name: name.clone(),
+                args: args.clone(),
+                target: target.to_string(),
+                member: member.clone(),
+            })
+            .collect()
+    }
+}
+
+/// Parse annotations and modifiers, keeping the doc comment in front of them.
+///
+/// `is_modifier` tells whether the token at an offset is used as a modifier; see
+/// [`is_modifier`] for the common case.
+pub(crate) fn parse_header(
+    cursor: &mut TokenCursor,
+    is_modifier: impl Fn(&TokenCursor, usize) -> bool,
+) -> Header {
+    let mut header = Header {
+        doc: cursor.peek().and_then(|t| t.doc.clone()),
+        column: cursor.column(),
+        ..Default::default()
+    };
+
+    loop {
+        if cursor.at("@") {
+            cursor.next();
+            // Use-site targets: `@file:JvmName`, `@get:Rule`
+            if cursor.peek_at(1).is_some_and(|t| t.text == ":") {
+                cursor.next();
+                cursor.next();
+            }
+            let name = cursor.dotted_name().unwrap_or_default();
+            // `@throws[IOException]`
+            if cursor.peek().is_some_and(|t| t.text == "[" && !t.newline_before) {
+                cursor.skip_balanced();
+            }
+            let mut args = Vec::new();
+            if cursor.peek().is_some_and(|t| t.text == "(" && !t.newline_before) {
+                let text = cursor.skip_balanced();
+                let inner = &text[1..text.len().saturating_sub(1)];
+                args = split_top_level(inner).iter().map(|a| unquote(a)).collect();
+            }
+            header.annotations.push((name, args));
+        } else if is_modifier(cursor, 0) {
+            let mut modifier = cursor.next().map(|t| t.text.to_string()).unwrap_or_default();
+            // `private[users]`
+            if cursor.at("[") {
+                modifier.push_str(&squash(cursor.skip_balanced()));
+            }
+            header.modifiers.push(modifier);
+        } else {
+            return header;
+        }
+    }
+}
+
+/// Whether the token at `offset` is one of `modifiers` used as a modifier rather
+/// than a name (`value: Int`): it must be followed by a name or an annotation.
+pub(crate) fn is_modifier(cursor: &TokenCursor, offset: usize, modifiers: &[&str]) -> bool {
+    let is_keyword = cursor.peek_at(offset).is_some_and(|t| modifiers.contains(&t.text));
+    is_keyword
+        && cursor
+            .peek_at(offset + 1)
+            .is_some_and(|t| t.kind == TokenKind::Ident || t.text == "@")
+}
+
+/// A parameter of a function or constructor.
+pub(crate) struct Param {
+    pub annotations: Vec<AttributeSpec>,
+    pub name: String,
+    /// Declared with `val`/`var`, making it a property
+    pub property: bool,
+    /// Parameter as written, without annotations
+    pub text: String,
+}
+
+/// Parse a single `name: Type` parameter, optionally declared with `val`/`var`.
+pub(crate) fn parse_param(
+    param: &str,
+    options: &LexOptions,
+    is_modifier: impl Fn(&TokenCursor, usize) -> bool,
+) -> Param {
+    let mut tokens = TokenCursor::new(param, options);
+    let header = parse_header(&mut tokens, is_modifier);
+    let start = tokens.peek().map(|t| t.start).unwrap_or(param.len());
+    let property = tokens.eat("val") || tokens.eat("var");
+    Param {
+        annotations: header.attributes("parameter", None),
+        name: tokens.ident().unwrap_or("").to_string(),
+        property,
+        text: format!("{}{}", header.prefix(), squash(&param[start..])),
+    }
+}
+
+/// The type whose body is being parsed.
+pub(crate) struct Scope {
+    /// Index into `FileSpec::types`
+    pub index: usize,
+    /// Qualified type name
+    pub name: String,
+    pub kind: &'static str,
+    /// Type the members belong to: the type itself, the class of a companion object,
+    /// or the extended type of an extension
+    pub owner: String,
+}
+
+/// `name` qualified with the name of the enclosing type, if any.
+pub(crate) fn qualify(scope: Option<&Scope>, name: &str) -> String {
+    match scope {
+        Some(scope) => format!("{}.{}", scope.name, name),
+        None => name.to_string(),
+    }
+}
+
+/// A parameter without its default value.
+///
+/// `angle_brackets` says whether `<...>` encloses type arguments; it does not in
+/// languages with `<:` bounds. `==`, `=>` and `->` never start or end a default.
+pub(crate) fn strip_default(param: &str, angle_brackets: bool) -> String {
+    let mut depth = 0;
+    for (i, c) in param.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            '<' if angle_brackets => depth += 1,
+            '>' if angle_brackets && !param[..i].ends_with(['-', '=']) => depth -= 1,
+            '=' if depth == 0 && !param[i + 1..].starts_with(['=', '>']) => {
+                return param[..i].trim_end().to_string()
+            }
+            _ => {}
+        }
+    }
+    param.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let source = "/** Docs. */\nval x = \"a ${b + \"}\"} c\" // trailing\nfun f() -> 1..2";
+        let tokens = tokenize(source, &LexOptions::default());
+        let texts: Vec<&str> = tokens.iter().map(|t| t.text).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "val", "x", "=", "\"a ${b + \"}\"} c\"", "fun", "f", "(", ")", "->",
+                "1", ".", ".", "2",
+            ]
+        );
+        assert_eq!(tokens[0].doc.as_deref(), Some("Docs."));
+        assert!(tokens[4].newline_before);
+        assert!(tokens[4].doc.is_none());
+    }
+
+    #[test]
+    fn test_skip_balanced() {
+        let source = "Map<String, List<Int>> { a { b } } rest";
+        let mut cursor = TokenCursor::new(source, &LexOptions::default());
+        cursor.next();
+        assert_eq!(cursor.skip_balanced(), "<String, List<Int>>");
+        assert_eq!(cursor.skip_balanced(), "{ a { b } }");
+        assert_eq!(cursor.peek_text(), "rest");
+        assert_eq!(
+            split_top_level("a: Int, b: Map<K, V> = f(1, 2)"),
+            vec!["a: Int", "b: Map<K, V> = f(1, 2)"]
+        );
+    }
+
+    #[test]
+    fn test_parse_header() {
+        let source =
+            "/** Docs. */ @get:Rule @throws[IOException] @Named(\"a\") private[users] val x";
+        let mut cursor = TokenCursor::new(source, &LexOptions::default());
+        let header =
+            parse_header(&mut cursor, |c, offset| c.peek_at(offset).unwrap().text == "private");
+        let names: Vec<&str> = header.annotations.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["Rule", "throws", "Named"]);
+        assert_eq!(header.annotations[2].1, vec!["a"]);
+        assert_eq!(header.prefix(), "private[users] ");
+        assert_eq!(header.doc.as_deref(), Some("Docs."));
+        assert_eq!(cursor.peek_text(), "val");
+
+        let param = parse_param("@Id val id: Long = 0", &LexOptions::default(), |_, _| false);
+        assert!(param.property);
+        assert_eq!((param.name.as_str(), param.text.as_str()), ("id", "val id: Long = 0"));
+        assert_eq!(strip_default(&param.text, true), "val id: Long");
+        assert_eq!(strip_default("f: (Int) -> Unit = {}", true), "f: (Int) -> Unit");
+        assert_eq!(strip_default("T <: Animal = Dog", false), "T <: Animal");
+        assert_eq!(strip_default("f: Int => Int", false), "f: Int => Int");
+    }
+}
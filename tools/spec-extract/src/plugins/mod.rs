@@ -1,22 +1,70 @@
+mod c;
+mod cpp;
+mod csharp;
+mod dart;
+mod doctags;
+mod elixir;
+mod embedded;
 mod go;
+mod graphql;
+mod java;
+mod javascript;
+mod jsdoc;
+mod kotlin;
+mod lexer;
+mod openapi;
+mod php;
+mod process;
+mod proto;
 mod python;
+mod query;
+mod ruby;
 mod rust;
+mod scala;
+mod sealed;
+mod sql;
+mod swift;
+mod tsconfig;
 mod typescript;
+mod visibility;
 
+pub use c::CPlugin;
+pub use cpp::CppPlugin;
+pub use csharp::CSharpPlugin;
+pub use dart::DartPlugin;
+pub use elixir::ElixirPlugin;
 pub use go::GoPlugin;
+pub use graphql::GraphQlPlugin;
+pub use java::JavaPlugin;
+pub use javascript::JavaScriptPlugin;
+pub use kotlin::KotlinPlugin;
+pub use openapi::OpenApiPlugin;
+pub use php::PhpPlugin;
+pub use process::{ProcessPlugin, PROTOCOL_VERSION};
+pub use proto::ProtoPlugin;
 pub use python::PythonPlugin;
+pub use query::QueryPlugin;
+pub use ruby::RubyPlugin;
 pub use rust::RustPlugin;
+pub use scala::ScalaPlugin;
+pub use sql::SqlPlugin;
+pub use swift::SwiftPlugin;
 pub use typescript::TypeScriptPlugin;
+pub use visibility::Visibility;
 
 use crate::parser::LanguagePlugin;
+use crate::spec::FileSpec;
+use anyhow::{bail, Context, Result};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Registry of all available language plugins.
 pub struct PluginRegistry {
     plugins: HashMap<String, Arc<dyn LanguagePlugin>>,
     ext_map: HashMap<String, String>,
+    visibility: Visibility,
+    semantics: bool,
 }
 
 impl Default for PluginRegistry {
@@ -31,12 +79,30 @@ impl PluginRegistry {
         let mut registry = Self {
             plugins: HashMap::new(),
             ext_map: HashMap::new(),
+            visibility: Visibility::All,
+            semantics: true,
         };
 
         registry.register(Arc::new(GoPlugin::new()));
         registry.register(Arc::new(RustPlugin::new()));
         registry.register(Arc::new(PythonPlugin::new()));
         registry.register(Arc::new(TypeScriptPlugin::new()));
+        registry.register(Arc::new(JavaScriptPlugin::new()));
+        registry.register(Arc::new(JavaPlugin::new()));
+        registry.register(Arc::new(CSharpPlugin::new()));
+        registry.register(Arc::new(KotlinPlugin::new()));
+        registry.register(Arc::new(CPlugin::new()));
+        registry.register(Arc::new(CppPlugin::new()));
+        registry.register(Arc::new(SwiftPlugin::new()));
+        registry.register(Arc::new(RubyPlugin::new()));
+        registry.register(Arc::new(PhpPlugin::new()));
+        registry.register(Arc::new(ElixirPlugin::new()));
+        registry.register(Arc::new(ScalaPlugin::new()));
+        registry.register(Arc::new(DartPlugin::new()));
+        registry.register(Arc::new(ProtoPlugin::new()));
+        registry.register(Arc::new(OpenApiPlugin::new()));
+        registry.register(Arc::new(GraphQlPlugin::new()));
+        registry.register(Arc::new(SqlPlugin::new()));
 
         registry
     }
@@ -50,16 +116,166 @@ impl PluginRegistry {
         self.plugins.insert(name, plugin);
     }
 
+    /// Route files with extension `ext` to the plugin named `language`, e.g.
+    /// `.mjs` to typescript.
+    pub fn map_extension(&mut self, ext: &str, language: &str) -> Result<()> {
+        if !self.plugins.contains_key(language) {
+            bail!("Unknown language: {}", language);
+        }
+        let ext = ext.trim_start_matches('.');
+        if ext.is_empty() {
+            bail!("Empty extension mapped to {}", language);
+        }
+        self.ext_map.insert(ext.to_string(), language.to_string());
+        Ok(())
+    }
+
+    /// Keep only the declarations `visibility` lets through in extracted specs.
+    pub fn set_visibility(&mut self, visibility: Visibility) {
+        self.visibility = visibility;
+    }
+
+    /// Whether extraction runs each plugin's `analyze_semantics` pass.
+    pub fn set_semantics(&mut self, enabled: bool) {
+        self.semantics = enabled;
+    }
+
+    /// Register the plugins described by the `*.toml` manifests in `dir`.
+    ///
+    /// Manifests with a `command` describe external process plugins, the others
+    /// query plugins. A manifest reusing a built-in plugin's name or extensions
+    /// replaces it. Returns the names of the loaded plugins, in manifest order.
+    pub fn load_plugins(&mut self, dir: &Path) -> Result<Vec<String>> {
+        let entries = std::fs::read_dir(dir)
+            .with_context(|| format!("Failed to read plugin directory: {}", dir.display()))?;
+        let mut manifests: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("toml"))
+            .collect();
+        manifests.sort();
+
+        let mut names = Vec::new();
+        for manifest in manifests {
+            let content = std::fs::read_to_string(&manifest).with_context(|| {
+                format!("Failed to read plugin manifest: {}", manifest.display())
+            })?;
+            let table: toml::Table = toml::from_str(&content)
+                .with_context(|| format!("Invalid plugin manifest: {}", manifest.display()))?;
+            let plugin: Arc<dyn LanguagePlugin> = if table.contains_key("command") {
+                Arc::new(ProcessPlugin::load(&manifest)?)
+            } else {
+                Arc::new(QueryPlugin::load(&manifest)?)
+            };
+            names.push(plugin.name().to_string());
+            self.register(plugin);
+        }
+        Ok(names)
+    }
+
     /// Get a plugin by name.
     pub fn get(&self, name: &str) -> Option<Arc<dyn LanguagePlugin>> {
         self.plugins.get(name).cloned()
     }
 
     /// Get a plugin that can handle the given file path.
+    ///
+    /// Extensions are looked up first; plugins that recognize files by name
+    /// (`openapi.yaml`) claim no extension and are asked through `can_handle`.
     pub fn get_for_file(&self, path: &Path) -> Option<Arc<dyn LanguagePlugin>> {
-        let ext = path.extension()?.to_str()?;
-        let name = self.ext_map.get(ext)?;
-        self.plugins.get(name).cloned()
+        let ext = path.extension().and_then(|ext| ext.to_str());
+        if let Some(name) = ext.and_then(|ext| self.ext_map.get(ext)) {
+            return self.plugins.get(name).cloned();
+        }
+        let mut names: Vec<&String> = self.plugins.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| &self.plugins[name])
+            .find(|plugin| plugin.extensions().is_empty() && plugin.can_handle(path))
+            .cloned()
+    }
+
+    /// Extract a spec from `source` with the plugin for `path`.
+    ///
+    /// Files no plugin claims may be host documents (Vue and Svelte components,
+    /// Markdown, Jupyter notebooks), whose embedded regions are extracted with
+    /// the plugins for their languages and merged into one spec for the host.
+    /// Returns `None` when nothing in the file could be extracted.
+    pub fn extract(&self, source: &str, path: &Path) -> Result<Option<FileSpec>> {
+        match self.get_for_file(path) {
+            Some(plugin) => self.extract_with(plugin.as_ref(), source, path).map(Some),
+            None => embedded::extract(self, source, path),
+        }
+    }
+
+    /// What besides a file's content decides its spec: the name and version of
+    /// the plugins involved and the extraction options. `None` when no plugin
+    /// handles the file.
+    pub fn fingerprint(&self, path: &Path) -> Option<String> {
+        let plugins = match self.get_for_file(path) {
+            Some(plugin) => format!("{}@{}", plugin.name(), plugin.version()),
+            // Host documents may use any plugin for their regions
+            None if embedded::host_kind(path).is_some() => {
+                let mut names: Vec<&String> = self.plugins.keys().collect();
+                names.sort();
+                let versions: Vec<String> = names
+                    .into_iter()
+                    .map(|name| format!("{}@{}", name, self.plugins[name].version()))
+                    .collect();
+                format!("{}:{}", embedded::host_kind(path)?, versions.join(","))
+            }
+            None => return None,
+        };
+        Some(format!(
+            "{};visibility={:?};semantics={}",
+            plugins, self.visibility, self.semantics
+        ))
+    }
+
+    /// Extract with `plugin`, then apply the semantic pass and visibility policy.
+    fn extract_with(
+        &self,
+        plugin: &dyn LanguagePlugin,
+        source: &str,
+        path: &Path,
+    ) -> Result<FileSpec> {
+        let mut spec = plugin.extract(source, path)?;
+        if self.semantics {
+            plugin.analyze_semantics(&mut spec, source)?;
+        }
+        if self.visibility == Visibility::Public {
+            visibility::retain_public(&mut spec, plugin.name());
+        }
+        Ok(spec)
+    }
+
+    /// The language of a file: its plugin's name, or the kind of host document.
+    pub fn language_for_file(&self, path: &Path) -> Option<String> {
+        match self.get_for_file(path) {
+            Some(plugin) => Some(plugin.name().to_string()),
+            None => embedded::host_kind(path).map(String::from),
+        }
+    }
+
+    /// Run every plugin's cross-file `link` pass over the extracted specs.
+    ///
+    /// Plugins run in name order so the result does not depend on registration order.
+    pub fn link(&self, specs: &mut Vec<FileSpec>) -> Result<()> {
+        let mut names: Vec<&String> = self.plugins.keys().collect();
+        names.sort();
+        for name in names {
+            self.plugins[name].link(specs)?;
+        }
+        Ok(())
+    }
+
+    /// Every language a file can be extracted as: the plugins' names and the
+    /// kinds of host documents, sorted.
+    pub fn languages(&self) -> Vec<String> {
+        let mut languages: Vec<String> = self.plugins.keys().cloned().collect();
+        languages.extend(embedded::HOST_KINDS.iter().map(|k| k.to_string()));
+        languages.sort();
+        languages
     }
 
     /// Get all registered plugin names.
@@ -90,6 +306,22 @@ mod tests {
         assert!(registry.get("rust").is_some());
         assert!(registry.get("python").is_some());
         assert!(registry.get("typescript").is_some());
+        assert!(registry.get("javascript").is_some());
+        assert!(registry.get("java").is_some());
+        assert!(registry.get("csharp").is_some());
+        assert!(registry.get("kotlin").is_some());
+        assert!(registry.get("c").is_some());
+        assert!(registry.get("cpp").is_some());
+        assert!(registry.get("swift").is_some());
+        assert!(registry.get("ruby").is_some());
+        assert!(registry.get("php").is_some());
+        assert!(registry.get("elixir").is_some());
+        assert!(registry.get("scala").is_some());
+        assert!(registry.get("dart").is_some());
+        assert!(registry.get("proto").is_some());
+        assert!(registry.get("openapi").is_some());
+        assert!(registry.get("graphql").is_some());
+        assert!(registry.get("sql").is_some());
     }
 
     #[test]
@@ -105,11 +337,83 @@ mod tests {
         let py_file = PathBuf::from("app.py");
         assert_eq!(registry.get_for_file(&py_file).unwrap().name(), "python");
 
+        let pyi_file = PathBuf::from("app.pyi");
+        assert_eq!(registry.get_for_file(&pyi_file).unwrap().name(), "python");
+
         let ts_file = PathBuf::from("index.ts");
         assert_eq!(registry.get_for_file(&ts_file).unwrap().name(), "typescript");
 
         let tsx_file = PathBuf::from("App.tsx");
         assert_eq!(registry.get_for_file(&tsx_file).unwrap().name(), "typescript");
+
+        let mts_file = PathBuf::from("index.mts");
+        assert_eq!(registry.get_for_file(&mts_file).unwrap().name(), "typescript");
+
+        let js_file = PathBuf::from("index.js");
+        assert_eq!(registry.get_for_file(&js_file).unwrap().name(), "javascript");
+
+        let cjs_file = PathBuf::from("config.cjs");
+        assert_eq!(registry.get_for_file(&cjs_file).unwrap().name(), "javascript");
+
+        let java_file = PathBuf::from("UserService.java");
+        assert_eq!(registry.get_for_file(&java_file).unwrap().name(), "java");
+
+        let cs_file = PathBuf::from("UserService.cs");
+        assert_eq!(registry.get_for_file(&cs_file).unwrap().name(), "csharp");
+
+        let kt_file = PathBuf::from("UserService.kt");
+        assert_eq!(registry.get_for_file(&kt_file).unwrap().name(), "kotlin");
+
+        let kts_file = PathBuf::from("build.gradle.kts");
+        assert_eq!(registry.get_for_file(&kts_file).unwrap().name(), "kotlin");
+
+        let c_file = PathBuf::from("db.c");
+        assert_eq!(registry.get_for_file(&c_file).unwrap().name(), "c");
+
+        let h_file = PathBuf::from("db.h");
+        assert_eq!(registry.get_for_file(&h_file).unwrap().name(), "c");
+
+        let cpp_file = PathBuf::from("user.cpp");
+        assert_eq!(registry.get_for_file(&cpp_file).unwrap().name(), "cpp");
+
+        let hpp_file = PathBuf::from("user.hpp");
+        assert_eq!(registry.get_for_file(&hpp_file).unwrap().name(), "cpp");
+
+        let swift_file = PathBuf::from("User.swift");
+        assert_eq!(registry.get_for_file(&swift_file).unwrap().name(), "swift");
+
+        let rb_file = PathBuf::from("user_service.rb");
+        assert_eq!(registry.get_for_file(&rb_file).unwrap().name(), "ruby");
+
+        let php_file = PathBuf::from("UserService.php");
+        assert_eq!(registry.get_for_file(&php_file).unwrap().name(), "php");
+
+        let ex_file = PathBuf::from("user.ex");
+        assert_eq!(registry.get_for_file(&ex_file).unwrap().name(), "elixir");
+
+        let scala_file = PathBuf::from("UserService.scala");
+        assert_eq!(registry.get_for_file(&scala_file).unwrap().name(), "scala");
+
+        let dart_file = PathBuf::from("user.dart");
+        assert_eq!(registry.get_for_file(&dart_file).unwrap().name(), "dart");
+
+        let proto_file = PathBuf::from("users.proto");
+        assert_eq!(registry.get_for_file(&proto_file).unwrap().name(), "proto");
+
+        let openapi_file = PathBuf::from("api/openapi.yaml");
+        assert_eq!(registry.get_for_file(&openapi_file).unwrap().name(), "openapi");
+
+        let openapi_json = PathBuf::from("users.openapi.json");
+        assert_eq!(registry.get_for_file(&openapi_json).unwrap().name(), "openapi");
+
+        let other_yaml = PathBuf::from("docker-compose.yaml");
+        assert!(registry.get_for_file(&other_yaml).is_none());
+
+        let graphql_file = PathBuf::from("schema.graphql");
+        assert_eq!(registry.get_for_file(&graphql_file).unwrap().name(), "graphql");
+
+        let sql_file = PathBuf::from("migrations/0001_init.sql");
+        assert_eq!(registry.get_for_file(&sql_file).unwrap().name(), "sql");
     }
 
     #[test]
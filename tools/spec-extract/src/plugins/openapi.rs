@@ -0,0 +1,539 @@
+use anyhow::{Context, Result};
+use openapiv3::{
+    AdditionalProperties, Components, Content, OpenAPI, Operation, Parameter,
+    ParameterSchemaOrContent, ReferenceOr, RequestBody, Response, Schema, SchemaKind, Type,
+};
+use std::path::Path;
+
+use crate::parser::LanguagePlugin;
+use crate::spec::{ErrorSpec, FileSpec, FuncSpec, TypeSpec};
+
+/// Plugin for extracting specifications from OpenAPI 3 documents.
+///
+/// Component schemas become types, and each operation becomes a function (or a
+/// method of its first tag) whose signature reads
+/// `GET /users/{id} getUser(id: string, expand?: boolean) -> User`. Client and
+/// server error responses are listed as errors of the operation.
+///
+/// YAML and JSON files belong to many tools, so the plugin claims no extension
+/// and recognizes `openapi.yaml`, `openapi.json` and `*.openapi.yaml` by name.
+pub struct OpenApiPlugin;
+
+impl OpenApiPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn parse(&self, source: &str, path: &Path) -> Result<OpenAPI> {
+        let is_json = path.extension().and_then(|ext| ext.to_str()) == Some("json");
+        let document = if is_json {
+            serde_json::from_str(source).map_err(anyhow::Error::from)
+        } else {
+            serde_yaml::from_str(source).map_err(anyhow::Error::from)
+        };
+        document.with_context(|| format!("Failed to parse OpenAPI document: {}", path.display()))
+    }
+
+    /// A component schema as a type.
+    fn schema_type(&self, name: &str, schema: &ReferenceOr<Schema>) -> TypeSpec {
+        let schema = match schema {
+            ReferenceOr::Item(schema) => schema,
+            ReferenceOr::Reference { reference } => {
+                return TypeSpec {
+                    name: format!("{} schema", name),
+                    kind: "type_alias".to_string(),
+                    embeds: vec![ref_name(reference).to_string()],
+                    ..Default::default()
+                }
+            }
+        };
+
+        let mut spec = TypeSpec {
+            name: format!("{} schema", name),
+            doc: schema.schema_data.description.clone().or(schema.schema_data.title.clone()),
+            kind: "type_alias".to_string(),
+            ..Default::default()
+        };
+
+        match &schema.schema_kind {
+            SchemaKind::Type(Type::Object(object)) => {
+                spec.kind = "object".to_string();
+                spec.fields = properties(&object.properties, &object.required);
+            }
+            SchemaKind::Type(Type::String(string)) if !string.enumeration.is_empty() => {
+                spec.kind = "enum".to_string();
+                spec.variants = string.enumeration.iter().flatten().cloned().collect();
+            }
+            SchemaKind::Type(Type::Integer(integer)) if !integer.enumeration.is_empty() => {
+                spec.kind = "enum".to_string();
+                spec.variants =
+                    integer.enumeration.iter().flatten().map(|v| v.to_string()).collect();
+            }
+            SchemaKind::OneOf { one_of: schemas } | SchemaKind::AnyOf { any_of: schemas } => {
+                spec.kind = "union".to_string();
+                spec.variants = schemas.iter().map(type_of).collect();
+            }
+            SchemaKind::AllOf { all_of } => {
+                // Composition: references are bases, inline parts contribute fields
+                spec.kind = "object".to_string();
+                for part in all_of {
+                    match part {
+                        ReferenceOr::Reference { reference } => {
+                            spec.embeds.push(ref_name(reference).to_string());
+                        }
+                        ReferenceOr::Item(Schema {
+                            schema_kind: SchemaKind::Type(Type::Object(object)),
+                            ..
+                        }) => spec.fields.extend(properties(&object.properties, &object.required)),
+                        ReferenceOr::Item(_) => {}
+                    }
+                }
+            }
+            SchemaKind::Any(any) if !any.properties.is_empty() => {
+                spec.kind = "object".to_string();
+                spec.fields = properties(&any.properties, &any.required);
+            }
+            _ => spec.embeds.push(schema_type_of(schema)),
+        }
+
+        spec
+    }
+
+    /// An operation as a function, with its error responses.
+    fn operation(
+        &self,
+        method: &str,
+        route: &str,
+        shared: &[ReferenceOr<Parameter>],
+        operation: &Operation,
+        components: Option<&Components>,
+    ) -> (FuncSpec, Vec<ErrorSpec>) {
+        let mut params = Vec::new();
+        // Operation parameters override path-level ones with the same name
+        let own: Vec<&Parameter> =
+            operation.parameters.iter().filter_map(|p| resolve_parameter(p, components)).collect();
+        let inherited = shared.iter().filter_map(|p| resolve_parameter(p, components));
+        for parameter in inherited.filter(|p| {
+            !own.iter().any(|o| o.parameter_data_ref().name == p.parameter_data_ref().name)
+        }) {
+            params.push(parameter_text(parameter));
+        }
+        params.extend(own.into_iter().map(parameter_text));
+
+        let body = operation.request_body.as_ref().and_then(|b| resolve_body(b, components));
+        if let Some(body) = body {
+            let ty = content_type(&body.content).unwrap_or_else(|| "any".to_string());
+            params.push(format!("body{}: {}", if body.required { "" } else { "?" }, ty));
+        }
+
+        // The first successful response is the result
+        let mut returns = None;
+        let mut errors = Vec::new();
+        let name = operation.operation_id.clone().unwrap_or_default();
+        let label = match &operation.operation_id {
+            Some(id) => id.clone(),
+            None => format!("{} {}", method.to_uppercase(), route),
+        };
+        let responses = operation
+            .responses
+            .responses
+            .iter()
+            .map(|(status, response)| (status.to_string(), response))
+            .chain(operation.responses.default.iter().map(|r| ("default".to_string(), r)));
+        for (status, response) in responses {
+            let Some(response) = resolve_response(response, components) else { continue };
+            if status.starts_with('2') {
+                if returns.is_none() {
+                    returns = Some(content_type(&response.content).unwrap_or("void".to_string()));
+                }
+                continue;
+            }
+            if !(status.starts_with('4') || status.starts_with('5') || status == "default") {
+                continue;
+            }
+            let schema = content_type(&response.content);
+            errors.push(ErrorSpec {
+                name: format!("{} {}", label, status),
+                message: Some(response.description.clone()).filter(|d| !d.is_empty()),
+                doc: schema,
+            });
+        }
+
+        let mut signature = format!(
+            "{} {} {}({})",
+            method.to_uppercase(),
+            route,
+            name,
+            params.join(", ")
+        );
+        if let Some(returns) = returns {
+            signature.push_str(&format!(" -> {}", returns));
+        }
+        if operation.deprecated {
+            signature = format!("deprecated {}", signature);
+        }
+
+        let doc = match (&operation.summary, &operation.description) {
+            (Some(summary), Some(description)) => Some(format!("{}\n\n{}", summary, description)),
+            (summary, description) => summary.clone().or(description.clone()),
+        };
+        let func = FuncSpec {
+            signature,
+            doc: doc.map(|d| d.trim().to_string()),
+            receiver: operation.tags.first().cloned(),
+            ..Default::default()
+        };
+        (func, errors)
+    }
+}
+
+/// The last segment of a `$ref` (`#/components/schemas/User` -> `User`).
+fn ref_name(reference: &str) -> &str {
+    reference.rsplit('/').next().unwrap_or(reference)
+}
+
+/// Object properties as fields, optional ones marked with `?`.
+fn properties<'a>(
+    properties: impl IntoIterator<Item = (&'a String, &'a ReferenceOr<Box<Schema>>)>,
+    required: &[String],
+) -> Vec<String> {
+    properties
+        .into_iter()
+        .map(|(name, schema)| {
+            let optional = if required.contains(name) { "" } else { "?" };
+            format!("{}{}: {}", name, optional, boxed_type_of(schema))
+        })
+        .collect()
+}
+
+fn type_of(schema: &ReferenceOr<Schema>) -> String {
+    match schema {
+        ReferenceOr::Reference { reference } => ref_name(reference).to_string(),
+        ReferenceOr::Item(schema) => schema_type_of(schema),
+    }
+}
+
+fn boxed_type_of(schema: &ReferenceOr<Box<Schema>>) -> String {
+    match schema {
+        ReferenceOr::Reference { reference } => ref_name(reference).to_string(),
+        ReferenceOr::Item(schema) => schema_type_of(schema),
+    }
+}
+
+/// A compact type expression for a schema (`User[]`, `map<string, integer>`, `A | B`).
+fn schema_type_of(schema: &Schema) -> String {
+    let ty = match &schema.schema_kind {
+        SchemaKind::Type(Type::String(_)) => "string".to_string(),
+        SchemaKind::Type(Type::Number(_)) => "number".to_string(),
+        SchemaKind::Type(Type::Integer(_)) => "integer".to_string(),
+        SchemaKind::Type(Type::Boolean(_)) => "boolean".to_string(),
+        SchemaKind::Type(Type::Array(array)) => match &array.items {
+            Some(items) => format!("{}[]", boxed_type_of(items)),
+            None => "any[]".to_string(),
+        },
+        SchemaKind::Type(Type::Object(object)) => match &object.additional_properties {
+            Some(AdditionalProperties::Schema(value)) => {
+                format!("map<string, {}>", type_of(value))
+            }
+            _ => "object".to_string(),
+        },
+        SchemaKind::OneOf { one_of: schemas } | SchemaKind::AnyOf { any_of: schemas } => {
+            schemas.iter().map(type_of).collect::<Vec<_>>().join(" | ")
+        }
+        SchemaKind::AllOf { all_of } => all_of.iter().map(type_of).collect::<Vec<_>>().join(" & "),
+        SchemaKind::Not { .. } => "any".to_string(),
+        SchemaKind::Any(any) => any.typ.clone().unwrap_or_else(|| "any".to_string()),
+    };
+    if schema.schema_data.nullable {
+        format!("{} | null", ty)
+    } else {
+        ty
+    }
+}
+
+/// The schema type of the preferred media type (JSON when there is a choice).
+fn content_type(content: &Content) -> Option<String> {
+    let media = content
+        .iter()
+        .find(|(media, _)| media.contains("json"))
+        .or_else(|| content.first())
+        .map(|(_, media)| media)?;
+    Some(media.schema.as_ref().map(type_of).unwrap_or_else(|| "any".to_string()))
+}
+
+/// `id: string`, or `limit?: integer` for optional parameters.
+fn parameter_text(parameter: &Parameter) -> String {
+    let data = parameter.parameter_data_ref();
+    let ty = match &data.format {
+        ParameterSchemaOrContent::Schema(schema) => type_of(schema),
+        ParameterSchemaOrContent::Content(content) => {
+            content_type(content).unwrap_or_else(|| "any".to_string())
+        }
+    };
+    let optional = if data.required { "" } else { "?" };
+    format!("{}{}: {}", data.name, optional, ty)
+}
+
+fn resolve_parameter<'a>(
+    parameter: &'a ReferenceOr<Parameter>,
+    components: Option<&'a Components>,
+) -> Option<&'a Parameter> {
+    match parameter {
+        ReferenceOr::Item(parameter) => Some(parameter),
+        ReferenceOr::Reference { reference } => {
+            components?.parameters.get(ref_name(reference))?.as_item()
+        }
+    }
+}
+
+fn resolve_body<'a>(
+    body: &'a ReferenceOr<RequestBody>,
+    components: Option<&'a Components>,
+) -> Option<&'a RequestBody> {
+    match body {
+        ReferenceOr::Item(body) => Some(body),
+        ReferenceOr::Reference { reference } => {
+            components?.request_bodies.get(ref_name(reference))?.as_item()
+        }
+    }
+}
+
+fn resolve_response<'a>(
+    response: &'a ReferenceOr<Response>,
+    components: Option<&'a Components>,
+) -> Option<&'a Response> {
+    match response {
+        ReferenceOr::Item(response) => Some(response),
+        ReferenceOr::Reference { reference } => {
+            components?.responses.get(ref_name(reference))?.as_item()
+        }
+    }
+}
+
+impl Default for OpenApiPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for OpenApiPlugin {
+    fn name(&self) -> &'static str {
+        "openapi"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &[]
+    }
+
+    fn can_handle(&self, path: &Path) -> bool {
+        let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+        match name.rsplit_once('.') {
+            Some((stem, "yaml" | "yml" | "json")) => {
+                stem == "openapi" || stem.ends_with(".openapi")
+            }
+            _ => false,
+        }
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let document = self.parse(source, path)?;
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: document.info.title.clone(),
+            ..Default::default()
+        };
+
+        let components = document.components.as_ref();
+        if let Some(components) = components {
+            for (name, schema) in &components.schemas {
+                spec.types.push(self.schema_type(name, schema));
+            }
+        }
+
+        for (route, item) in document.paths.iter() {
+            let Some(item) = item.as_item() else { continue };
+            for (method, operation) in item.iter() {
+                let (func, errors) =
+                    self.operation(method, route, &item.parameters, operation, components);
+                if func.receiver.is_some() {
+                    spec.methods.push(func);
+                } else {
+                    spec.functions.push(func);
+                }
+                spec.errors.extend(errors);
+            }
+        }
+
+        if spec.package.is_empty() {
+            spec.package =
+                path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = r##"
+openapi: 3.0.3
+info:
+  title: Users API
+  version: 1.0.0
+paths:
+  /users/{id}:
+    parameters:
+      - name: id
+        in: path
+        required: true
+        schema:
+          type: string
+    get:
+      tags: [users]
+      operationId: getUser
+      summary: Fetch a user.
+      parameters:
+        - $ref: '#/components/parameters/Expand'
+      responses:
+        '200':
+          description: The user
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/User'
+        '404':
+          $ref: '#/components/responses/NotFound'
+  /users:
+    post:
+      operationId: createUser
+      requestBody:
+        required: true
+        content:
+          application/json:
+            schema:
+              $ref: '#/components/schemas/NewUser'
+      responses:
+        '201':
+          description: Created
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/User'
+        default:
+          description: Unexpected error
+          content:
+            application/json:
+              schema:
+                $ref: '#/components/schemas/Error'
+components:
+  parameters:
+    Expand:
+      name: expand
+      in: query
+      schema:
+        type: boolean
+  responses:
+    NotFound:
+      description: No such user
+      content:
+        application/json:
+          schema:
+            $ref: '#/components/schemas/Error'
+  schemas:
+    User:
+      description: A registered user.
+      type: object
+      required: [id, email]
+      properties:
+        id:
+          type: string
+        email:
+          type: string
+        tags:
+          type: array
+          items:
+            type: string
+        nickname:
+          type: string
+          nullable: true
+    NewUser:
+      allOf:
+        - $ref: '#/components/schemas/User'
+        - type: object
+          properties:
+            password:
+              type: string
+    Role:
+      type: string
+      enum: [admin, member]
+    Error:
+      type: object
+      properties:
+        message:
+          type: string
+"##;
+
+    #[test]
+    fn test_extract_schemas() {
+        let plugin = OpenApiPlugin::new();
+        let spec = plugin.extract(DOCUMENT, Path::new("api/openapi.yaml")).unwrap();
+        assert_eq!(spec.package, "Users API");
+
+        let user = &spec.types[0];
+        assert_eq!(user.name, "User schema");
+        assert_eq!(user.kind, "object");
+        assert_eq!(user.doc.as_deref(), Some("A registered user."));
+        assert_eq!(
+            user.fields,
+            vec!["id: string", "email: string", "tags?: string[]", "nickname?: string | null"]
+        );
+
+        let new_user = &spec.types[1];
+        assert_eq!(new_user.embeds, vec!["User"]);
+        assert_eq!(new_user.fields, vec!["password?: string"]);
+
+        assert_eq!(spec.types[2].kind, "enum");
+        assert_eq!(spec.types[2].variants, vec!["admin", "member"]);
+    }
+
+    #[test]
+    fn test_extract_operations_and_errors() {
+        let plugin = OpenApiPlugin::new();
+        let spec = plugin.extract(DOCUMENT, Path::new("api/openapi.yaml")).unwrap();
+
+        assert_eq!(spec.methods.len(), 1);
+        let get = &spec.methods[0];
+        assert_eq!(get.signature, "GET /users/{id} getUser(id: string, expand?: boolean) -> User");
+        assert_eq!(get.receiver.as_deref(), Some("users"));
+        assert_eq!(get.doc.as_deref(), Some("Fetch a user."));
+
+        assert_eq!(
+            spec.functions[0].signature,
+            "POST /users createUser(body: NewUser) -> User"
+        );
+
+        let errors: Vec<(&str, Option<&str>, Option<&str>)> = spec
+            .errors
+            .iter()
+            .map(|e| (e.name.as_str(), e.message.as_deref(), e.doc.as_deref()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("getUser 404", Some("No such user"), Some("Error")),
+                ("createUser default", Some("Unexpected error"), Some("Error")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_json_document() {
+        let plugin = OpenApiPlugin::new();
+        let source = r#"{"openapi": "3.0.0", "info": {"title": "", "version": "1"},
+            "paths": {"/health": {"get": {"responses": {"204": {"description": "ok"}}}}}}"#;
+        let spec = plugin.extract(source, Path::new("status.openapi.json")).unwrap();
+        assert_eq!(spec.package, "status.openapi");
+        assert_eq!(spec.functions[0].signature, "GET /health () -> void");
+    }
+}
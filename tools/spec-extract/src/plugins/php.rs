@@ -0,0 +1,625 @@
+use anyhow::Result;
+use std::path::Path;
+use tree_sitter::Node;
+
+use super::doctags::DocTags;
+use super::lexer::{squash, unquote};
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
+use crate::spec::{AttributeSpec, ConstSpec, ErrorSpec, FileSpec, FuncSpec, TypeSpec};
+
+/// Declarations that introduce a named type.
+const TYPE_DECLARATIONS: &[&str] = &[
+    "class_declaration",
+    "interface_declaration",
+    "trait_declaration",
+    "enum_declaration",
+];
+
+/// Base class suffixes that make a class an exception type.
+const THROWABLE_SUFFIXES: &[&str] = &["Exception", "Error", "Throwable"];
+
+/// Plugin for extracting specifications from PHP source files.
+pub struct PhpPlugin;
+
+impl PhpPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn extract_package(&self, root: Node, source: &str, path: &Path) -> String {
+        NodeHelper::child_by_kind(root, "namespace_definition")
+            .and_then(|ns| NodeHelper::field_text(ns, "name", source))
+            .map(|name| name.to_string())
+            .unwrap_or_else(|| {
+                path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string()
+            })
+    }
+
+    /// Top-level declarations, looking inside braced `namespace X { ... }` blocks.
+    fn declarations<'a>(&self, root: Node<'a>) -> Vec<Node<'a>> {
+        let mut declarations = Vec::new();
+        let mut cursor = root.walk();
+        for child in root.named_children(&mut cursor) {
+            match NodeHelper::field(child, "body") {
+                Some(body) if child.kind() == "namespace_definition" => {
+                    declarations.extend(body.named_children(&mut body.walk()))
+                }
+                _ => declarations.push(child),
+            }
+        }
+        declarations
+    }
+
+    /// `use` statements, expanding group uses (`use A\{B, C as D}`).
+    fn extract_imports(&self, declarations: &[Node], source: &str) -> Vec<String> {
+        let mut imports = Vec::new();
+        for decl in declarations.iter().filter(|d| d.kind() == "namespace_use_declaration") {
+            let kind = NodeHelper::field_text(*decl, "type", source);
+            let prefix = NodeHelper::child_by_kind(*decl, "namespace_name")
+                .map(|p| NodeHelper::text(p, source));
+            let clauses = match NodeHelper::field(*decl, "body") {
+                Some(group) => NodeHelper::children_by_kind(group, "namespace_use_clause"),
+                None => NodeHelper::children_by_kind(*decl, "namespace_use_clause"),
+            };
+
+            for clause in clauses {
+                let text = squash(NodeHelper::text(clause, source));
+                let name = match prefix {
+                    Some(prefix) => format!("{}\\{}", prefix, text),
+                    None => text,
+                };
+                imports.push(match kind {
+                    Some(kind) => format!("{} {}", kind, name),
+                    None => name,
+                });
+            }
+        }
+        imports
+    }
+
+    fn extract_type(&self, node: Node, source: &str, spec: &mut FileSpec) {
+        let name = match NodeHelper::field_text(node, "name", source) {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let kind = match node.kind() {
+            "class_declaration" if NodeHelper::has_child_kind(node, "abstract_modifier") => {
+                "abstract_class"
+            }
+            "class_declaration" => "class",
+            "interface_declaration" => "interface",
+            "trait_declaration" => "trait",
+            _ => "enum",
+        };
+
+        // Parent class, or the interfaces an interface extends
+        let embeds = NodeHelper::child_by_kind(node, "base_clause")
+            .map(|base| self.name_list(base, source))
+            .unwrap_or_default();
+        let implements = NodeHelper::child_by_kind(node, "class_interface_clause")
+            .map(|clause| self.name_list(clause, source))
+            .filter(|list| !list.is_empty())
+            .map(|list| list.join(", "));
+
+        let mut spec_type = TypeSpec {
+            name: format!("{} {}", name, if kind == "abstract_class" { "class" } else { kind }),
+            doc: NodeHelper::preceding_comment(node, source),
+            kind: kind.to_string(),
+            embeds,
+            implements,
+            attributes: self.attributes(node, source, "class", None),
+            ..Default::default()
+        };
+
+        if let Some(body) = NodeHelper::field(node, "body") {
+            let mut cursor = body.walk();
+            for member in body.named_children(&mut cursor) {
+                self.extract_member(member, source, &name, &mut spec_type, spec);
+            }
+        }
+
+        spec.types.push(spec_type);
+    }
+
+    fn extract_member(
+        &self,
+        member: Node,
+        source: &str,
+        owner: &str,
+        spec_type: &mut TypeSpec,
+        spec: &mut FileSpec,
+    ) {
+        let doc = NodeHelper::preceding_comment(member, source);
+        match member.kind() {
+            // Trait uses
+            "use_declaration" => spec_type.embeds.extend(self.name_list(member, source)),
+            "const_declaration" => {
+                spec.constants.extend(self.constants(member, source, Some(owner)))
+            }
+            "property_declaration" => {
+                let tags = DocTags::phpdoc(doc.as_deref().unwrap_or(""));
+                let modifiers = self.modifiers(member, source);
+                let type_name = NodeHelper::field_text(member, "type", source).or(tags.var());
+                for element in NodeHelper::children_by_kind(member, "property_element") {
+                    let variable = NodeHelper::field_text(element, "name", source).unwrap_or("");
+                    let field = [modifiers.as_str(), type_name.unwrap_or(""), variable]
+                        .iter()
+                        .filter(|part| !part.is_empty())
+                        .copied()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    spec_type.fields.push(field);
+                    let property = Some(variable.trim_start_matches('$').to_string());
+                    let attributes = self.attributes(member, source, "property", property);
+                    spec_type.attributes.extend(attributes);
+                }
+            }
+            "method_declaration" => {
+                let method = NodeHelper::field_text(member, "name", source).unwrap_or("");
+                let attributes = self.attributes(member, source, "method", Some(method.into()));
+                spec_type.attributes.extend(attributes);
+
+                if let Some(params) = NodeHelper::field(member, "parameters") {
+                    let mut cursor = params.walk();
+                    for param in params.named_children(&mut cursor) {
+                        let name = NodeHelper::field_text(param, "name", source).unwrap_or("");
+                        let member = format!("{}.{}", method, name.trim_start_matches('$'));
+                        let attributes = self.attributes(param, source, "parameter", Some(member));
+                        spec_type.attributes.extend(attributes);
+                        // Constructor promotion declares a property
+                        if param.kind() == "property_promotion_parameter" {
+                            spec_type.fields.push(self.parameter(param, source, None));
+                        }
+                    }
+                }
+
+                // Class and trait methods become FileSpec methods; interfaces list signatures
+                let signature = self.signature(member, source, doc.as_deref());
+                if spec_type.kind == "interface" {
+                    spec_type.methods.push(signature);
+                } else {
+                    spec.methods.push(FuncSpec {
+                        signature,
+                        doc,
+                        receiver: Some(owner.to_string()),
+                        ..Default::default()
+                    });
+                }
+            }
+            "enum_case" => {
+                let case = NodeHelper::field_text(member, "name", source).unwrap_or("");
+                spec_type.variants.push(match NodeHelper::field_text(member, "value", source) {
+                    Some(value) => format!("{} = {}", case, value),
+                    None => case.to_string(),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    /// `[modifiers] function name(params): Return`, with PHPDoc types for untyped parts.
+    fn signature(&self, node: Node, source: &str, doc: Option<&str>) -> String {
+        let tags = DocTags::phpdoc(doc.unwrap_or(""));
+        let modifiers = self.modifiers(node, source);
+        let name = NodeHelper::field_text(node, "name", source).unwrap_or("");
+        let params = NodeHelper::field(node, "parameters")
+            .map(|params| {
+                let mut cursor = params.walk();
+                params
+                    .named_children(&mut cursor)
+                    .filter(|p| p.kind() != "comment")
+                    .map(|p| self.parameter(p, source, Some(&tags)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+        let returns = NodeHelper::field_text(node, "return_type", source)
+            .or(tags.returns())
+            .map(|ty| format!(": {}", ty))
+            .unwrap_or_default();
+
+        let prefix = if modifiers.is_empty() { String::new() } else { format!("{} ", modifiers) };
+        format!("{}function {}({}){}", prefix, name, params, returns)
+    }
+
+    /// A parameter as written without its attributes, typed from PHPDoc when untyped.
+    fn parameter(&self, param: Node, source: &str, tags: Option<&DocTags>) -> String {
+        let mut cursor = param.walk();
+        let start = param
+            .children(&mut cursor)
+            .find(|c| c.kind() != "attribute_list")
+            .map(|c| c.start_byte())
+            .unwrap_or(param.start_byte());
+        let text = squash(&source[start..param.end_byte()]);
+
+        let documented = NodeHelper::field(param, "type")
+            .is_none()
+            .then(|| NodeHelper::field_text(param, "name", source))
+            .flatten()
+            .and_then(|name| tags?.param(name.trim_start_matches('$')));
+        match documented {
+            Some(ty) if param.kind() != "property_promotion_parameter" => {
+                format!("{} {}", ty, text)
+            }
+            _ => text,
+        }
+    }
+
+    /// Keyword modifiers (`public static`, `final`, `readonly`) in source order.
+    fn modifiers(&self, node: Node, source: &str) -> String {
+        let mut cursor = node.walk();
+        let modifiers: Vec<&str> = node
+            .children(&mut cursor)
+            .filter(|c| c.kind().ends_with("_modifier"))
+            .map(|c| NodeHelper::text(c, source))
+            .collect();
+        modifiers.join(" ")
+    }
+
+    /// Names listed in an `extends`, `implements` or trait `use` clause.
+    fn name_list(&self, node: Node, source: &str) -> Vec<String> {
+        let mut cursor = node.walk();
+        node.named_children(&mut cursor)
+            .filter(|c| matches!(c.kind(), "name" | "qualified_name"))
+            .map(|c| NodeHelper::text(c, source).to_string())
+            .collect()
+    }
+
+    /// PHP 8 `#[Attribute(args)]` groups on a declaration.
+    fn attributes(
+        &self,
+        node: Node,
+        source: &str,
+        target: &str,
+        member: Option<String>,
+    ) -> Vec<AttributeSpec> {
+        let list = match NodeHelper::field(node, "attributes") {
+            Some(list) => list,
+            None => return Vec::new(),
+        };
+
+        let mut attributes = Vec::new();
+        for group in NodeHelper::children_by_kind(list, "attribute_group") {
+            for attribute in NodeHelper::children_by_kind(group, "attribute") {
+                let name = NodeHelper::child_by_kind(attribute, "name")
+                    .or_else(|| NodeHelper::child_by_kind(attribute, "qualified_name"))
+                    .map(|n| NodeHelper::text(n, source))
+                    .unwrap_or("");
+                let args = NodeHelper::field(attribute, "parameters")
+                    .map(|args| {
+                        NodeHelper::children_by_kind(args, "argument")
+                            .into_iter()
+                            .map(|arg| {
+                                let text = NodeHelper::text(arg, source);
+                                let literal = arg.named_child(0).is_some_and(|value| {
+                                    matches!(value.kind(), "string" | "encapsed_string")
+                                });
+                                if literal && NodeHelper::field(arg, "name").is_none() {
+                                    unquote(text)
+                                } else {
+                                    text.to_string()
+                                }
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                attributes.push(AttributeSpec {
+                    name: name.to_string(),
+                    args,
+                    target: target.to_string(),
+                    member: member.clone(),
+                });
+            }
+        }
+        attributes
+    }
+
+    /// `const A = 1, B = 2;`, named `Owner::A` inside a type.
+    fn constants(&self, node: Node, source: &str, owner: Option<&str>) -> Vec<ConstSpec> {
+        let doc = NodeHelper::preceding_comment(node, source);
+        let type_name = NodeHelper::field_text(node, "type", source).map(|t| t.to_string());
+        NodeHelper::children_by_kind(node, "const_element")
+            .into_iter()
+            .filter_map(|element| {
+                let name = NodeHelper::child_by_kind(element, "name")?;
+                let value = element
+                    .named_children(&mut element.walk())
+                    .find(|c| c.id() != name.id())
+                    .map(|v| NodeHelper::text(v, source).to_string());
+                let name = NodeHelper::text(name, source);
+                Some(ConstSpec {
+                    name: match owner {
+                        Some(owner) => format!("{}::{}", owner, name),
+                        None => name.to_string(),
+                    },
+                    type_name: type_name.clone(),
+                    value,
+                    doc: doc.clone(),
+                    export: None,
+                    visibility: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Exception classes declared in the file, then other types named by `@throws`.
+    fn extract_errors(
+        &self,
+        declarations: &[Node],
+        source: &str,
+        spec: &FileSpec,
+    ) -> Vec<ErrorSpec> {
+        let classes: Vec<Node> = declarations
+            .iter()
+            .copied()
+            .filter(|d| d.kind() == "class_declaration")
+            .collect();
+        // A class is an exception if it extends a Throwable, directly or through
+        // another exception class in this file
+        let mut exception_names: Vec<&str> = Vec::new();
+        loop {
+            let before = exception_names.len();
+            for class in &classes {
+                let name = NodeHelper::field_text(*class, "name", source).unwrap_or("");
+                let base = self.base_name(*class, source);
+                let is_exception = THROWABLE_SUFFIXES.iter().any(|s| base.ends_with(s))
+                    || exception_names.contains(&base);
+                if is_exception && !exception_names.contains(&name) {
+                    exception_names.push(name);
+                }
+            }
+            if exception_names.len() == before {
+                break;
+            }
+        }
+
+        let mut errors: Vec<ErrorSpec> = classes
+            .iter()
+            .filter_map(|class| {
+                let name = NodeHelper::field_text(*class, "name", source)?;
+                exception_names.contains(&name).then(|| ErrorSpec {
+                    name: name.to_string(),
+                    message: self.default_message(*class, source),
+                    doc: NodeHelper::preceding_comment(*class, source),
+                })
+            })
+            .collect();
+
+        for func in spec.functions.iter().chain(&spec.methods) {
+            let tags = DocTags::phpdoc(func.doc.as_deref().unwrap_or(""));
+            for (thrown, description) in tags.raises() {
+                let short = thrown.rsplit('\\').next().unwrap_or(thrown);
+                if errors.iter().any(|e| e.name == short || e.name == *thrown) {
+                    continue;
+                }
+                errors.push(ErrorSpec {
+                    name: thrown.clone(),
+                    message: None,
+                    doc: description.clone(),
+                });
+            }
+        }
+
+        errors
+    }
+
+    /// Unqualified name of the class a class extends.
+    fn base_name<'a>(&self, class: Node<'a>, source: &'a str) -> &'a str {
+        NodeHelper::child_by_kind(class, "base_clause")
+            .and_then(|base| base.named_child(0))
+            .map(|base| NodeHelper::text(base, source))
+            .map(|base| base.rsplit('\\').next().unwrap_or(base))
+            .unwrap_or("")
+    }
+
+    /// The string an exception assigns to `protected $message`, if any.
+    fn default_message(&self, class: Node, source: &str) -> Option<String> {
+        NodeHelper::find_all(class, "property_element")
+            .into_iter()
+            .filter(|e| NodeHelper::field_text(*e, "name", source) == Some("$message"))
+            .filter_map(|e| NodeHelper::field(e, "default_value"))
+            .find(|value| matches!(value.kind(), "string" | "encapsed_string"))
+            .map(|value| unquote(NodeHelper::text(value, source)))
+    }
+}
+
+impl Default for PhpPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for PhpPlugin {
+    fn name(&self) -> &'static str {
+        "php"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["php"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let language = tree_sitter_php::LANGUAGE_PHP;
+        let mut parser = TreeSitterParser::new(language.into())?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+        let declarations = self.declarations(root);
+
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: self.extract_package(root, source, path),
+            imports: self.extract_imports(&declarations, source),
+            ..Default::default()
+        };
+
+        for decl in &declarations {
+            match decl.kind() {
+                kind if TYPE_DECLARATIONS.contains(&kind) => {
+                    self.extract_type(*decl, source, &mut spec)
+                }
+                "function_definition" => {
+                    let doc = NodeHelper::preceding_comment(*decl, source);
+                    spec.functions.push(FuncSpec {
+                        signature: self.signature(*decl, source, doc.as_deref()),
+                        doc,
+                        ..Default::default()
+                    });
+                }
+                "const_declaration" => spec.constants.extend(self.constants(*decl, source, None)),
+                _ => {}
+            }
+        }
+        spec.errors = self.extract_errors(&declarations, source, &spec);
+
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_class() {
+        let plugin = PhpPlugin::new();
+        let source = r#"<?php
+namespace App\Users;
+
+use App\Models\User;
+use Psr\Log\{LoggerInterface, NullLogger as Quiet};
+
+/** Serves users. */
+#[Service, Route("/users")]
+final class UserService extends BaseService implements Finder, \Countable
+{
+    use LogsTrait;
+
+    public const MAX = 10;
+    private ?string $name = null;
+    /** @var array<int, User> */
+    protected $cache = [];
+
+    public function __construct(private readonly Repo $repo) {}
+
+    /**
+     * Finds a user.
+     * @param string $id
+     * @return User|null
+     */
+    #[Get("/{id}")]
+    public function find($id, #[FromQuery] bool $fresh = false)
+    {
+        return null;
+    }
+}
+"#;
+        let spec = plugin.extract(source, Path::new("UserService.php")).unwrap();
+        assert_eq!(spec.package, "App\\Users");
+        assert_eq!(
+            spec.imports,
+            vec!["App\\Models\\User", "Psr\\Log\\LoggerInterface", "Psr\\Log\\NullLogger as Quiet"]
+        );
+
+        let class = &spec.types[0];
+        assert_eq!(class.name, "UserService class");
+        assert_eq!(class.doc.as_deref(), Some("Serves users."));
+        assert_eq!(class.embeds, vec!["BaseService", "LogsTrait"]);
+        assert_eq!(class.implements.as_deref(), Some("Finder, \\Countable"));
+        assert_eq!(
+            class.fields,
+            vec![
+                "private ?string $name",
+                "protected array<int, User> $cache",
+                "private readonly Repo $repo",
+            ]
+        );
+
+        let attrs: Vec<(&str, &str, Option<&str>)> = class
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.target.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                ("Service", "class", None),
+                ("Route", "class", None),
+                ("Get", "method", Some("find")),
+                ("FromQuery", "parameter", Some("find.fresh")),
+            ]
+        );
+        assert_eq!(class.attributes[1].args, vec!["/users"]);
+
+        assert_eq!(
+            spec.methods[1].signature,
+            "public function find(string $id, bool $fresh = false): User|null"
+        );
+        assert_eq!(spec.methods[1].receiver.as_deref(), Some("UserService"));
+        assert_eq!(spec.constants[0].name, "UserService::MAX");
+    }
+
+    #[test]
+    fn test_extract_interface_trait_enum() {
+        let plugin = PhpPlugin::new();
+        let source = r#"<?php
+interface Finder extends Countable
+{
+    const LIMIT = 5;
+    public function find(string $id): ?User;
+}
+
+trait Logs
+{
+    public function log(string $message): void {}
+}
+
+enum Status: string implements HasLabel
+{
+    case Active = 'active';
+    case Banned = 'banned';
+}
+
+function helper(array $xs): int { return 0; }
+"#;
+        let spec = plugin.extract(source, Path::new("types.php")).unwrap();
+        assert_eq!(spec.package, "types");
+
+        let kinds: Vec<&str> = spec.types.iter().map(|t| t.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["interface", "trait", "enum"]);
+        assert_eq!(spec.types[0].embeds, vec!["Countable"]);
+        assert_eq!(spec.types[0].methods, vec!["public function find(string $id): ?User"]);
+        assert_eq!(spec.types[2].variants, vec!["Active = 'active'", "Banned = 'banned'"]);
+        assert_eq!(spec.types[2].implements.as_deref(), Some("HasLabel"));
+
+        assert_eq!(spec.constants[0].name, "Finder::LIMIT");
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("Logs"));
+        assert_eq!(spec.functions[0].signature, "function helper(array $xs): int");
+    }
+
+    #[test]
+    fn test_extract_exceptions() {
+        let plugin = PhpPlugin::new();
+        let source = r#"<?php
+/** Base failure of the users module. */
+class UserException extends \RuntimeException {}
+
+class NotFoundException extends UserException
+{
+    protected $message = 'User not found';
+}
+
+class Store
+{
+    /** @throws \IOException when the disk fails */
+    public function save(): void {}
+}
+"#;
+        let spec = plugin.extract(source, Path::new("Errors.php")).unwrap();
+        let names: Vec<&str> = spec.errors.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["UserException", "NotFoundException", "\\IOException"]);
+        assert_eq!(spec.errors[0].doc.as_deref(), Some("Base failure of the users module."));
+        assert_eq!(spec.errors[1].message.as_deref(), Some("User not found"));
+        assert_eq!(spec.errors[2].doc.as_deref(), Some("when the disk fails"));
+    }
+}
@@ -0,0 +1,288 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+use super::query::leak_names;
+use crate::cache::content_hash;
+use crate::parser::LanguagePlugin;
+use crate::spec::FileSpec;
+
+/// Version of the JSON protocol spoken with external plugins.
+///
+/// The request written to the plugin's stdin is
+/// `{"protocol": 1, "path": "...", "source": "...", "options": {...}}`, and the
+/// plugin answers on stdout with `{"protocol": 1, "spec": <FileSpec>}` or
+/// `{"protocol": 1, "error": {"message": "...", "line": 12}}`. A plugin that
+/// exits with a failure status, outlives its timeout or answers with another
+/// protocol version fails the file.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+const DEFAULT_TIMEOUT_SECS: f64 = 30.0;
+
+/// Manifest of an external plugin, e.g. `.opensdd/plugins/acme.toml`.
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    name: String,
+    extensions: Vec<String>,
+    /// Program and arguments, run from the manifest's directory
+    command: Vec<String>,
+    /// Seconds a single file may take
+    timeout: Option<f64>,
+    /// Version of the plugin's output; bump it to invalidate cached specs when
+    /// something besides the manifest and the files its command names changes
+    version: Option<String>,
+    /// Passed through to the plugin with every request
+    #[serde(default)]
+    options: toml::Table,
+}
+
+#[derive(Debug, Deserialize)]
+struct Response {
+    protocol: Option<u32>,
+    spec: Option<Value>,
+    error: Option<ResponseError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResponseError {
+    message: String,
+    line: Option<usize>,
+}
+
+/// Plugin that hands files to an external executable, for languages with no
+/// tree-sitter grammar (in-house DSLs, config formats). See `PROTOCOL_VERSION`
+/// for the protocol.
+pub struct ProcessPlugin {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    command: Vec<String>,
+    dir: PathBuf,
+    timeout: Duration,
+    options: Value,
+    version: String,
+}
+
+impl ProcessPlugin {
+    /// Load a plugin from its TOML manifest.
+    pub fn load(manifest_path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(manifest_path).with_context(|| {
+            format!("Failed to read plugin manifest: {}", manifest_path.display())
+        })?;
+        let manifest: Manifest = toml::from_str(&content)
+            .with_context(|| format!("Invalid plugin manifest: {}", manifest_path.display()))?;
+        if manifest.command.is_empty() {
+            bail!("Plugin {} has an empty command", manifest.name);
+        }
+        let timeout = manifest.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS);
+        if !timeout.is_finite() || timeout <= 0.0 {
+            bail!("Plugin {} has an invalid timeout: {}", manifest.name, timeout);
+        }
+
+        let extensions: Vec<&str> = manifest.extensions.iter().map(|e| e.as_str()).collect();
+        let (name, extensions) = leak_names(&manifest.name, &extensions);
+        let dir = manifest_path.parent().unwrap_or(Path::new("")).to_path_buf();
+        // Editing the manifest, or a script or program its command names, changes the output
+        let mut parts = vec![content.into_bytes()];
+        parts.extend(manifest.command.iter().filter_map(|word| fs::read(dir.join(word)).ok()));
+        let parts: Vec<&[u8]> = parts.iter().map(Vec::as_slice).collect();
+        let hash = content_hash(&parts);
+        let version = format!("{}+{}", manifest.version.as_deref().unwrap_or("0"), &hash[..16]);
+        Ok(Self {
+            name,
+            extensions,
+            command: manifest.command,
+            dir: if dir.as_os_str().is_empty() { PathBuf::from(".") } else { dir },
+            timeout: Duration::from_secs_f64(timeout),
+            options: serde_json::to_value(manifest.options)?,
+            version,
+        })
+    }
+
+    /// Run the plugin on one request and return what it wrote to stdout.
+    fn run(&self, request: &Value) -> Result<Vec<u8>> {
+        let mut child = Command::new(&self.command[0])
+            .args(&self.command[1..])
+            .current_dir(&self.dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to start plugin {}: {}", self.name, self.command[0]))?;
+
+        // Feed and drain the pipes on their own threads, so a plugin that
+        // writes before reading all of its input cannot deadlock us
+        let input = serde_json::to_vec(request)?;
+        let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("Plugin stdin unavailable"))?;
+        let writer = thread::spawn(move || stdin.write_all(&input));
+        let mut stdout = child.stdout.take().ok_or_else(|| anyhow!("Plugin stdout unavailable"))?;
+        let reader = thread::spawn(move || {
+            let mut output = Vec::new();
+            stdout.read_to_end(&mut output).map(|_| output)
+        });
+        let mut stderr = child.stderr.take().ok_or_else(|| anyhow!("Plugin stderr unavailable"))?;
+        let errors = thread::spawn(move || {
+            let mut output = String::new();
+            stderr.read_to_string(&mut output).map(|_| output)
+        });
+
+        let Some(status) = child.wait_timeout(self.timeout)? else {
+            // The pipe threads are left behind: children of the plugin may
+            // still hold its pipes open
+            let _ = child.kill();
+            let _ = child.wait();
+            bail!("Plugin {} timed out after {:?}", self.name, self.timeout);
+        };
+
+        // A plugin may exit without reading its input; that is its call
+        let _ = writer.join();
+        let output = reader.join().map_err(|_| anyhow!("Plugin stdout reader panicked"))??;
+        let errors = errors.join().map_err(|_| anyhow!("Plugin stderr reader panicked"))??;
+        if !status.success() {
+            bail!("Plugin {} failed ({}): {}", self.name, status, errors.trim());
+        }
+        Ok(output)
+    }
+}
+
+impl LanguagePlugin for ProcessPlugin {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        self.extensions
+    }
+
+    fn version(&self) -> String {
+        self.version.clone()
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let file = path.to_string_lossy().to_string();
+        let request = json!({
+            "protocol": PROTOCOL_VERSION,
+            "path": file,
+            "source": source,
+            "options": self.options,
+        });
+        let output = self.run(&request)?;
+        let response: Response = serde_json::from_slice(&output)
+            .with_context(|| format!("Plugin {} returned an invalid response", self.name))?;
+
+        match response.protocol {
+            Some(PROTOCOL_VERSION) => {}
+            Some(version) => bail!(
+                "Plugin {} speaks protocol {}, expected {}",
+                self.name,
+                version,
+                PROTOCOL_VERSION
+            ),
+            None => bail!("Plugin {} did not state its protocol version", self.name),
+        }
+        if let Some(error) = response.error {
+            match error.line {
+                Some(line) => bail!("{}:{}: {}", file, line, error.message),
+                None => bail!("{}: {}", file, error.message),
+            }
+        }
+
+        // The file is always the one we asked about; the package defaults to its stem
+        let mut spec = response
+            .spec
+            .ok_or_else(|| anyhow!("Plugin {} returned neither spec nor error", self.name))?;
+        let object = spec
+            .as_object_mut()
+            .ok_or_else(|| anyhow!("Plugin {} returned a spec that is not an object", self.name))?;
+        object.insert("file".to_string(), Value::String(file));
+        if !object.contains_key("package") {
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+            object.insert("package".to_string(), Value::String(stem.to_string()));
+        }
+        serde_json::from_value(spec)
+            .with_context(|| format!("Plugin {} returned an invalid spec", self.name))
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn plugin(dir: &Path, script: &str, extra: &str) -> ProcessPlugin {
+        fs::write(dir.join("plugin.sh"), script).unwrap();
+        let manifest = format!(
+            "name = \"acme\"\nextensions = [\"acme\"]\ncommand = [\"sh\", \"plugin.sh\"]\n{}",
+            extra
+        );
+        fs::write(dir.join("acme.toml"), manifest).unwrap();
+        ProcessPlugin::load(&dir.join("acme.toml")).unwrap()
+    }
+
+    #[test]
+    fn test_extract_through_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = r#"cat > request.json
+printf '{"protocol": 1, "spec": {"types": [{"name": "Order entity", "kind": "entity"}]}}'
+"#;
+        let plugin = plugin(dir.path(), script, "[options]\nstrict = true\n");
+        let spec = plugin.extract("entity Order {}", Path::new("models/order.acme")).unwrap();
+        assert_eq!(spec.file, "models/order.acme");
+        assert_eq!(spec.package, "order");
+        assert_eq!(spec.types[0].name, "Order entity");
+
+        let request: Value =
+            serde_json::from_str(&fs::read_to_string(dir.path().join("request.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            request,
+            json!({
+                "protocol": 1,
+                "path": "models/order.acme",
+                "source": "entity Order {}",
+                "options": {"strict": true},
+            })
+        );
+    }
+
+    #[test]
+    fn test_reports_plugin_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = r#"cat > /dev/null
+printf '{"protocol": 1, "error": {"message": "unexpected token", "line": 3}}'
+"#;
+        let err = plugin(dir.path(), script, "").extract("", Path::new("a.acme")).unwrap_err();
+        assert_eq!(err.to_string(), "a.acme:3: unexpected token");
+
+        let script = "cat > /dev/null\necho 'grammar missing' >&2\nexit 2\n";
+        let err = plugin(dir.path(), script, "").extract("", Path::new("a.acme")).unwrap_err();
+        assert!(err.to_string().ends_with("grammar missing"), "{}", err);
+
+        let script = "cat > /dev/null\nprintf '{\"protocol\": 2, \"spec\": {}}'\n";
+        let err = plugin(dir.path(), script, "").extract("", Path::new("a.acme")).unwrap_err();
+        assert_eq!(err.to_string(), "Plugin acme speaks protocol 2, expected 1");
+    }
+
+    #[test]
+    fn test_version_follows_script() {
+        let dir = tempfile::tempdir().unwrap();
+        let before = plugin(dir.path(), "cat > /dev/null\n", "").version();
+        assert_eq!(plugin(dir.path(), "cat > /dev/null\n", "").version(), before);
+        let after = plugin(dir.path(), "cat > /dev/null\necho '{}'\n", "").version();
+        assert_ne!(after, before);
+        assert!(after.starts_with("0+"));
+    }
+
+    #[test]
+    fn test_times_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let plugin = plugin(dir.path(), "exec sleep 5\n", "timeout = 0.2\n");
+        let err = plugin.extract("", Path::new("a.acme")).unwrap_err();
+        assert_eq!(err.to_string(), "Plugin acme timed out after 200ms");
+    }
+}
@@ -0,0 +1,514 @@
+use anyhow::Result;
+use std::path::Path;
+
+use super::lexer::{split_top_level, squash, unquote, LexOptions, TokenCursor};
+use crate::parser::LanguagePlugin;
+use crate::spec::{AttributeSpec, FileSpec, TypeSpec};
+
+/// Plugin for extracting specifications from Protocol Buffers (`.proto`) files.
+///
+/// Messages and enums become types, and each `service` becomes a type whose
+/// methods are its `rpc` signatures, so gRPC contracts can be compared with
+/// the services that implement them. There is no protobuf grammar among our
+/// tree-sitter dependencies, so this walks the [`super::lexer`] token stream.
+pub struct ProtoPlugin;
+
+impl ProtoPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn lex_options() -> LexOptions {
+        LexOptions {
+            // Proto files document declarations with plain comments
+            doc_prefixes: &["//", "/*"],
+            nested_comments: false,
+            triple_quotes: false,
+            templates: false,
+            single_quote_strings: true,
+            operators: &[],
+        }
+    }
+
+    /// Parse top-level statements and the bodies of messages (`scope` is the
+    /// qualified message name and its index).
+    fn parse_body(
+        &self,
+        cursor: &mut TokenCursor,
+        scope: Option<(&str, usize)>,
+        spec: &mut FileSpec,
+    ) {
+        while !cursor.is_eof() {
+            let doc = cursor.peek().and_then(|t| t.doc.clone());
+            match cursor.peek_text() {
+                "}" if scope.is_some() => return,
+                ";" => {
+                    cursor.next();
+                }
+                "syntax" | "edition" | "option" | "reserved" | "extensions" => {
+                    self.skip_statement(cursor);
+                }
+                "package" if scope.is_none() => {
+                    cursor.next();
+                    spec.package = cursor.dotted_name().unwrap_or_default();
+                    self.skip_statement(cursor);
+                }
+                "import" if scope.is_none() => {
+                    cursor.next();
+                    // `import public "..."` re-exports, `import weak "..."` is optional
+                    let modifier = match cursor.peek_text() {
+                        "public" | "weak" => cursor.next().map(|t| t.text),
+                        _ => None,
+                    };
+                    let path = cursor.next().map(|t| unquote(t.text)).unwrap_or_default();
+                    spec.imports.push(match modifier {
+                        Some(modifier) => format!("{} {}", modifier, path),
+                        None => path,
+                    });
+                    self.skip_statement(cursor);
+                }
+                "message" => self.parse_message(cursor, doc, scope, spec),
+                "enum" => self.parse_enum(cursor, doc, scope, spec),
+                "service" if scope.is_none() => self.parse_service(cursor, doc, spec),
+                "extend" => {
+                    // Extensions of other messages do not declare anything here
+                    while !cursor.is_eof() && !cursor.at("{") {
+                        cursor.next();
+                    }
+                    cursor.skip_balanced();
+                }
+                "oneof" => {
+                    cursor.next();
+                    let oneof = cursor.ident().unwrap_or("").to_string();
+                    if cursor.eat("{") {
+                        while !cursor.is_eof() && !cursor.at("}") {
+                            if cursor.at("option") {
+                                self.skip_statement(cursor);
+                            } else {
+                                self.parse_field(cursor, scope, Some(&oneof), spec);
+                            }
+                        }
+                        cursor.eat("}");
+                    }
+                }
+                _ if scope.is_some() => self.parse_field(cursor, scope, None, spec),
+                _ => {
+                    cursor.next();
+                }
+            }
+        }
+    }
+
+    /// Skip up to and including the `;` ending a statement.
+    fn skip_statement(&self, cursor: &mut TokenCursor) {
+        while let Some(token) = cursor.peek() {
+            match token.text {
+                ";" => {
+                    cursor.next();
+                    return;
+                }
+                "}" => return,
+                "{" | "[" | "(" => {
+                    cursor.skip_balanced();
+                }
+                _ => {
+                    cursor.next();
+                }
+            }
+        }
+    }
+
+    /// `[deprecated = true, json_name = "id"]` as (option, value) pairs.
+    fn parse_options(&self, cursor: &mut TokenCursor) -> Vec<(String, String)> {
+        if !cursor.at("[") {
+            return Vec::new();
+        }
+        let text = cursor.skip_balanced();
+        split_top_level(&text[1..text.len().saturating_sub(1)])
+            .iter()
+            .filter_map(|option| {
+                let (name, value) = option.split_once('=')?;
+                Some((option_name(name), unquote(value.trim())))
+            })
+            .collect()
+    }
+
+    fn qualify(&self, scope: Option<(&str, usize)>, name: &str) -> String {
+        match scope {
+            Some((outer, _)) => format!("{}.{}", outer, name),
+            None => name.to_string(),
+        }
+    }
+
+    fn parse_message(
+        &self,
+        cursor: &mut TokenCursor,
+        doc: Option<String>,
+        scope: Option<(&str, usize)>,
+        spec: &mut FileSpec,
+    ) {
+        cursor.next();
+        let name = self.qualify(scope, cursor.ident().unwrap_or(""));
+        let index = spec.types.len();
+        spec.types.push(TypeSpec {
+            name: format!("{} message", name),
+            doc,
+            kind: "message".to_string(),
+            ..Default::default()
+        });
+
+        if cursor.eat("{") {
+            self.parse_body(cursor, Some((&name, index)), spec);
+            cursor.eat("}");
+        }
+    }
+
+    /// `repeated string tags = 2 [packed = true];` and `map<string, Label> labels = 3;`
+    fn parse_field(
+        &self,
+        cursor: &mut TokenCursor,
+        scope: Option<(&str, usize)>,
+        oneof: Option<&str>,
+        spec: &mut FileSpec,
+    ) {
+        let start = cursor.position();
+        while !cursor.is_eof() && !matches!(cursor.peek_text(), "=" | ";" | "}" | "{") {
+            if cursor.at("<") {
+                cursor.skip_balanced();
+            } else {
+                cursor.next();
+            }
+        }
+        let declaration = squash(cursor.text(start, cursor.position()));
+        if !cursor.eat("=") {
+            // Not a field (proto2 groups and anything else we do not model)
+            if cursor.at("{") {
+                cursor.skip_balanced();
+            } else if cursor.position() == start {
+                cursor.next();
+            } else {
+                cursor.eat(";");
+            }
+            return;
+        }
+        let number = cursor.next().map(|t| t.text).unwrap_or("");
+        let options = self.parse_options(cursor);
+        cursor.eat(";");
+
+        let Some((_, index)) = scope else { return };
+        let name = declaration.rsplit(' ').next().unwrap_or("").to_string();
+        let mut field = format!("{} = {}", declaration, number);
+        if let Some(oneof) = oneof {
+            field = format!("oneof {} {}", oneof, field);
+        }
+
+        let owner = &mut spec.types[index];
+        owner.fields.push(field);
+        owner.attributes.extend(options.into_iter().map(|(option, value)| AttributeSpec {
+            name: option,
+            args: vec![value],
+            target: "field".to_string(),
+            member: Some(name.clone()),
+        }));
+    }
+
+    fn parse_enum(
+        &self,
+        cursor: &mut TokenCursor,
+        doc: Option<String>,
+        scope: Option<(&str, usize)>,
+        spec: &mut FileSpec,
+    ) {
+        cursor.next();
+        let name = self.qualify(scope, cursor.ident().unwrap_or(""));
+        let mut variants = Vec::new();
+        if cursor.eat("{") {
+            while !cursor.is_eof() && !cursor.at("}") {
+                match cursor.peek_text() {
+                    "option" | "reserved" => self.skip_statement(cursor),
+                    _ => {
+                        let value = cursor.ident().unwrap_or("").to_string();
+                        if cursor.eat("=") {
+                            // Values may be negative
+                            let start = cursor.position();
+                            while !cursor.is_eof() && !matches!(cursor.peek_text(), ";" | "[" | "}")
+                            {
+                                cursor.next();
+                            }
+                            let number = squash(cursor.text(start, cursor.position()));
+                            variants.push(format!("{} = {}", value, number.replace("- ", "-")));
+                        }
+                        self.parse_options(cursor);
+                        if !cursor.eat(";") && value.is_empty() {
+                            cursor.next();
+                        }
+                    }
+                }
+            }
+            cursor.eat("}");
+        }
+
+        spec.types.push(TypeSpec {
+            name: format!("{} enum", name),
+            doc,
+            kind: "enum".to_string(),
+            variants,
+            ..Default::default()
+        });
+    }
+
+    /// A `service` with its `rpc` methods, e.g.
+    /// `rpc Watch(WatchRequest) returns (stream Event)`.
+    fn parse_service(&self, cursor: &mut TokenCursor, doc: Option<String>, spec: &mut FileSpec) {
+        cursor.next();
+        let name = cursor.ident().unwrap_or("").to_string();
+        let mut methods = Vec::new();
+        let mut attributes = Vec::new();
+
+        if cursor.eat("{") {
+            while !cursor.is_eof() && !cursor.at("}") {
+                if !cursor.eat("rpc") {
+                    self.skip_statement(cursor);
+                    continue;
+                }
+                let method = cursor.ident().unwrap_or("").to_string();
+                let request = squash(cursor.skip_balanced());
+                cursor.eat("returns");
+                let response = squash(cursor.skip_balanced());
+                methods.push(format!("rpc {}{} returns {}", method, request, response));
+
+                // `{ option (google.api.http) = { get: "/v1/users/{id}" }; }`
+                if cursor.eat("{") {
+                    while !cursor.is_eof() && !cursor.at("}") {
+                        if !cursor.eat("option") {
+                            self.skip_statement(cursor);
+                            continue;
+                        }
+                        let start = cursor.position();
+                        while !cursor.is_eof() && !matches!(cursor.peek_text(), "=" | ";") {
+                            cursor.next();
+                        }
+                        let option = option_name(cursor.text(start, cursor.position()));
+                        cursor.eat("=");
+                        let start = cursor.position();
+                        self.skip_statement(cursor);
+                        let value = cursor.text(start, cursor.position());
+                        let value = squash(value.trim_end_matches(';'));
+                        attributes.push(AttributeSpec {
+                            name: option,
+                            args: vec![unquote(&value)],
+                            target: "method".to_string(),
+                            member: Some(method.clone()),
+                        });
+                    }
+                    cursor.eat("}");
+                }
+                cursor.eat(";");
+            }
+            cursor.eat("}");
+        }
+
+        spec.types.push(TypeSpec {
+            name: format!("{} service", name),
+            doc,
+            kind: "service".to_string(),
+            methods,
+            attributes,
+            ..Default::default()
+        });
+    }
+}
+
+/// Option name without the parentheses of custom options (`(google.api.http)`).
+fn option_name(name: &str) -> String {
+    squash(name).replace(['(', ')', ' '], "")
+}
+
+impl Default for ProtoPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for ProtoPlugin {
+    fn name(&self) -> &'static str {
+        "proto"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["proto"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let mut cursor = TokenCursor::new(source, &Self::lex_options());
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        self.parse_body(&mut cursor, None, &mut spec);
+
+        if spec.package.is_empty() {
+            spec.package =
+                path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_messages_and_enums() {
+        let plugin = ProtoPlugin::new();
+        let source = r#"
+syntax = "proto3";
+
+package acme.users.v1;
+
+import "google/protobuf/timestamp.proto";
+import public "acme/common.proto";
+
+option go_package = "github.com/acme/users/v1;usersv1";
+
+// A registered user.
+message User {
+  string id = 1;
+  repeated string tags = 2 [packed = true];
+  map<string, string> labels = 3;
+  optional int32 age = 4 [deprecated = true];
+  oneof contact {
+    string email = 5;
+    string phone = 6;
+  }
+  reserved 7, 8;
+
+  message Address {
+    string city = 1;
+  }
+
+  // Account state.
+  enum Status {
+    STATUS_UNSPECIFIED = 0;
+    STATUS_ACTIVE = 1;
+    STATUS_LEGACY = -1 [deprecated = true];
+  }
+}
+"#;
+        let spec = plugin.extract(source, Path::new("users.proto")).unwrap();
+        assert_eq!(spec.package, "acme.users.v1");
+        assert_eq!(
+            spec.imports,
+            vec!["google/protobuf/timestamp.proto", "public acme/common.proto"]
+        );
+
+        let user = &spec.types[0];
+        assert_eq!(user.name, "User message");
+        assert_eq!(user.doc.as_deref(), Some("A registered user."));
+        assert_eq!(
+            user.fields,
+            vec![
+                "string id = 1",
+                "repeated string tags = 2",
+                "map<string, string> labels = 3",
+                "optional int32 age = 4",
+                "oneof contact string email = 5",
+                "oneof contact string phone = 6",
+            ]
+        );
+        assert_eq!(user.attributes[1].name, "deprecated");
+        assert_eq!(user.attributes[1].member.as_deref(), Some("age"));
+
+        assert_eq!(spec.types[1].name, "User.Address message");
+        assert_eq!(spec.types[1].fields, vec!["string city = 1"]);
+
+        let status = &spec.types[2];
+        assert_eq!(status.name, "User.Status enum");
+        assert_eq!(status.doc.as_deref(), Some("Account state."));
+        assert_eq!(
+            status.variants,
+            vec!["STATUS_UNSPECIFIED = 0", "STATUS_ACTIVE = 1", "STATUS_LEGACY = -1"]
+        );
+    }
+
+    #[test]
+    fn test_extract_services() {
+        let plugin = ProtoPlugin::new();
+        let source = r#"
+syntax = "proto3";
+package acme.users.v1;
+
+// Manages users.
+service UserService {
+  rpc GetUser(GetUserRequest) returns (User) {
+    option (google.api.http) = { get: "/v1/users/{id}" };
+  }
+  rpc WatchUsers(WatchRequest) returns (stream UserEvent);
+  rpc Upload(stream Chunk) returns (stream Ack) {}
+}
+"#;
+        let spec = plugin.extract(source, Path::new("service.proto")).unwrap();
+        let service = &spec.types[0];
+        assert_eq!(service.name, "UserService service");
+        assert_eq!(service.kind, "service");
+        assert_eq!(service.doc.as_deref(), Some("Manages users."));
+        assert_eq!(
+            service.methods,
+            vec![
+                "rpc GetUser(GetUserRequest) returns (User)",
+                "rpc WatchUsers(WatchRequest) returns (stream UserEvent)",
+                "rpc Upload(stream Chunk) returns (stream Ack)",
+            ]
+        );
+        assert_eq!(service.attributes[0].name, "google.api.http");
+        assert_eq!(service.attributes[0].args, vec!["{ get: \"/v1/users/{id}\" }"]);
+        assert_eq!(service.attributes[0].member.as_deref(), Some("GetUser"));
+    }
+
+    #[test]
+    fn test_extract_extend_and_oneof() {
+        let plugin = ProtoPlugin::new();
+        let source = r#"
+syntax = "proto2";
+package acme;
+
+extend google.protobuf.FieldOptions {
+  optional string sensitive = 50000;
+}
+
+message Event {
+  extensions 100 to 199;
+  extend Base {
+    optional Event event = 100;
+  }
+  oneof payload {
+    option (validate.required) = true;
+    Created created = 1 [(acme.sensitive) = "yes"];
+    map<string, Deleted> deleted = 2;
+  }
+  required int64 at = 3;
+}
+
+message Created { optional string id = 1; }
+"#;
+        let spec = plugin.extract(source, Path::new("events.proto")).unwrap();
+        let names: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Event message", "Created message"]);
+
+        let event = &spec.types[0];
+        assert_eq!(
+            event.fields,
+            vec![
+                "oneof payload Created created = 1",
+                "oneof payload map<string, Deleted> deleted = 2",
+                "required int64 at = 3",
+            ]
+        );
+        assert_eq!(event.attributes.len(), 1);
+        assert_eq!(event.attributes[0].name, "acme.sensitive");
+        assert_eq!(event.attributes[0].member.as_deref(), Some("created"));
+        assert_eq!(spec.types[1].fields, vec!["optional string id = 1"]);
+    }
+}
@@ -110,8 +110,7 @@ impl PythonPlugin {
                             if let Some(left) = assign.child(0) {
                                 if left.kind() == "attribute" {
                                     let attr_text = NodeHelper::text(left, source);
-                                    if attr_text.starts_with("self.") {
-                                        let field_name = &attr_text[5..];
+                                    if let Some(field_name) = attr_text.strip_prefix("self.") {
                                         // Only add if not already in fields
                                         if !fields.iter().any(|f| f.starts_with(field_name)) {
                                             fields.push(field_name.to_string());
@@ -136,13 +135,98 @@ impl PythonPlugin {
         })
     }
 
+    fn extract_type_aliases(&self, root: Node, source: &str) -> Vec<TypeSpec> {
+        let mut types = Vec::new();
+
+        // PEP 695: type Point[T] = tuple[T, T]
+        for alias in NodeHelper::find_all(root, "type_alias_statement") {
+            if self.is_inside_class(alias) || self.is_inside_function(alias) {
+                continue;
+            }
+
+            let left = match NodeHelper::field(alias, "left") {
+                Some(n) => n,
+                None => continue,
+            };
+
+            let mut type_params = Vec::new();
+            let name_node = match NodeHelper::child_by_kind(left, "generic_type") {
+                Some(generic) => {
+                    if let Some(params) = NodeHelper::child_by_kind(generic, "type_parameter") {
+                        for param in NodeHelper::children_by_kind(params, "type") {
+                            type_params.push(NodeHelper::text(param, source).to_string());
+                        }
+                    }
+                    NodeHelper::child_by_kind(generic, "identifier")
+                }
+                None => Some(left),
+            };
+
+            if let Some(name) = name_node.map(|n| NodeHelper::text(n, source)) {
+                types.push(TypeSpec {
+                    name: format!("{} type", name),
+                    doc: NodeHelper::preceding_comment(alias, source),
+                    kind: "type_alias".to_string(),
+                    type_params,
+                    ..Default::default()
+                });
+            }
+        }
+
+        // X: TypeAlias = ... and X = NewType("X", ...)
+        for node in NodeHelper::find_all(root, "expression_statement") {
+            if self.is_inside_class(node) || self.is_inside_function(node) {
+                continue;
+            }
+
+            if let Some(assign) = NodeHelper::child_by_kind(node, "assignment") {
+                if !self.is_type_alias(assign, source) {
+                    continue;
+                }
+
+                if let Some(left) = NodeHelper::field(assign, "left") {
+                    types.push(TypeSpec {
+                        name: format!("{} type", NodeHelper::text(left, source)),
+                        doc: NodeHelper::preceding_comment(node, source),
+                        kind: "type_alias".to_string(),
+                        ..Default::default()
+                    });
+                }
+            }
+        }
+
+        types
+    }
+
+    /// Check if an assignment declares a type alias (`TypeAlias` annotation or `NewType` call).
+    fn is_type_alias(&self, assign: Node, source: &str) -> bool {
+        let annotated = NodeHelper::field(assign, "type")
+            .map(|t| {
+                let text = NodeHelper::text(t, source);
+                text == "TypeAlias" || text.ends_with(".TypeAlias")
+            })
+            .unwrap_or(false);
+
+        let new_type = NodeHelper::field(assign, "right")
+            .filter(|r| r.kind() == "call")
+            .and_then(|call| NodeHelper::field(call, "function"))
+            .map(|f| {
+                let text = NodeHelper::text(f, source);
+                text == "NewType" || text.ends_with(".NewType")
+            })
+            .unwrap_or(false);
+
+        annotated || new_type
+    }
+
     fn extract_docstring(&self, node: Node, source: &str) -> Option<String> {
         // Look for the body/block of the class/function
         let body = NodeHelper::child_by_kind(node, "block")?;
 
         // The first expression statement with a string might be a docstring
         let mut cursor = body.walk();
-        for child in body.children(&mut cursor) {
+        let first = body.children(&mut cursor).next();
+        if let Some(child) = first {
             if child.kind() == "expression_statement" {
                 if let Some(string_node) = child.child(0) {
                     if string_node.kind() == "string" {
@@ -159,8 +243,6 @@ impl PythonPlugin {
                     }
                 }
             }
-            // Stop looking after we hit a non-expression statement
-            break;
         }
 
         None
@@ -245,6 +327,11 @@ impl PythonPlugin {
             }
 
             if let Some(assign) = NodeHelper::child_by_kind(node, "assignment") {
+                // Type aliases are reported as types, not constants
+                if self.is_type_alias(assign, source) {
+                    continue;
+                }
+
                 let left = assign.child(0);
                 let right = assign.child(2);
 
@@ -259,6 +346,8 @@ impl PythonPlugin {
                                 type_name: None,
                                 value,
                                 doc: None,
+                                export: None,
+                                visibility: None,
                             });
                         }
                     }
@@ -293,7 +382,7 @@ impl LanguagePlugin for PythonPlugin {
     }
 
     fn extensions(&self) -> &[&'static str] {
-        &["py"]
+        &["py", "pyi"]
     }
 
     fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
@@ -302,18 +391,115 @@ impl LanguagePlugin for PythonPlugin {
         let tree = parser.parse(source)?;
         let root = tree.root_node();
 
+        let mut types = self.extract_classes(root, source);
+        types.extend(self.extract_type_aliases(root, source));
+
         Ok(FileSpec {
             file: path.to_string_lossy().to_string(),
             package: self.extract_module(path),
             imports: self.extract_imports(root, source),
-            types: self.extract_classes(root, source),
+            types,
             functions: self.extract_functions(root, source),
-            methods: Vec::new(), // Python methods are included in class types
             constants: self.extract_constants(root, source),
-            variables: Vec::new(),
-            errors: Vec::new(),
+            ..Default::default()
         })
     }
+
+    fn link(&self, specs: &mut Vec<FileSpec>) -> Result<()> {
+        // Pair each .pyi stub with the .py module next to it
+        let mut pairs = Vec::new();
+        for (stub_idx, stub) in specs.iter().enumerate() {
+            let stub_path = Path::new(&stub.file);
+            if stub_path.extension().and_then(|e| e.to_str()) != Some("pyi") {
+                continue;
+            }
+            let module_path = stub_path.with_extension("py");
+            if let Some(module_idx) = specs.iter().position(|s| Path::new(&s.file) == module_path) {
+                pairs.push((stub_idx, module_idx));
+            }
+        }
+
+        for &(stub_idx, module_idx) in &pairs {
+            let stub = specs[stub_idx].clone();
+            merge_stub(&mut specs[module_idx], stub);
+        }
+
+        // Merged stubs no longer need their own entry
+        let mut idx = 0;
+        specs.retain(|_| {
+            let keep = !pairs.iter().any(|&(stub_idx, _)| stub_idx == idx);
+            idx += 1;
+            keep
+        });
+
+        Ok(())
+    }
+}
+
+/// Merge a `.pyi` stub into the spec of its `.py` module.
+///
+/// Signatures declared in the stub take precedence; docstrings come from the
+/// module, or from the stub when the module has none. Items only declared in
+/// the stub are added.
+fn merge_stub(module: &mut FileSpec, stub: FileSpec) {
+    for import in stub.imports {
+        if !module.imports.contains(&import) {
+            module.imports.push(import);
+        }
+    }
+
+    merge_by_key(&mut module.types, stub.types, |t| t.name.clone(), |base, stub_type| {
+        let doc = base.doc.take().or(stub_type.doc.clone());
+        let mut fields = std::mem::take(&mut base.fields);
+        let mut methods = std::mem::take(&mut base.methods);
+        merge_by_key(&mut fields, stub_type.fields.clone(), |f| member_name(f), |f, s| *f = s);
+        merge_by_key(&mut methods, stub_type.methods.clone(), |m| member_name(m), |m, s| *m = s);
+        *base = TypeSpec {
+            doc,
+            fields,
+            methods,
+            ..stub_type
+        };
+    });
+
+    merge_by_key(&mut module.functions, stub.functions, |f| member_name(&f.signature), |base, stub_func| {
+        let doc = base.doc.take().or(stub_func.doc.clone());
+        *base = FuncSpec { doc, ..stub_func };
+    });
+
+    merge_by_key(&mut module.constants, stub.constants, |c| c.name.clone(), |base, stub_const| {
+        base.type_name = stub_const.type_name.or(base.type_name.take());
+        if base.value.is_none() {
+            base.value = stub_const.value.filter(|v| v != "...");
+        }
+    });
+}
+
+/// Merge `overlay` items into `base`, matching on `key`; unmatched items are appended.
+fn merge_by_key<T, K, F, M>(base: &mut Vec<T>, overlay: Vec<T>, key: F, merge: M)
+where
+    K: PartialEq,
+    F: Fn(&T) -> K,
+    M: Fn(&mut T, T),
+{
+    for item in overlay {
+        let item_key = key(&item);
+        match base.iter_mut().find(|b| key(b) == item_key) {
+            Some(existing) => merge(existing, item),
+            None => base.push(item),
+        }
+    }
+}
+
+/// Name of a field (`name: type`) or function (`def name(...)`) entry.
+fn member_name(entry: &str) -> String {
+    let entry = entry.strip_prefix("def ").unwrap_or(entry);
+    entry
+        .split(['(', ':', '['])
+        .next()
+        .unwrap_or(entry)
+        .trim()
+        .to_string()
 }
 
 #[cfg(test)]
@@ -375,4 +561,64 @@ DEFAULT_TIMEOUT = 30
         let spec = plugin.extract(source, Path::new("config.py")).unwrap();
         assert_eq!(spec.constants.len(), 2);
     }
+
+    #[test]
+    fn test_extract_type_aliases() {
+        let plugin = PythonPlugin::new();
+        let source = r#"
+from typing import NewType, TypeAlias
+
+UserId = NewType("UserId", int)
+VECTOR: TypeAlias = list[float]
+type Pair[T] = tuple[T, T]
+MAX_RETRIES = 3
+"#;
+        let spec = plugin.extract(source, Path::new("types.py")).unwrap();
+        let names: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert!(names.contains(&"UserId type"));
+        assert!(names.contains(&"VECTOR type"));
+        assert!(names.contains(&"Pair type"));
+        assert!(spec.types.iter().all(|t| t.kind == "type_alias"));
+
+        let pair = spec.types.iter().find(|t| t.name == "Pair type").unwrap();
+        assert_eq!(pair.type_params, vec!["T".to_string()]);
+
+        assert_eq!(spec.constants.len(), 1);
+        assert_eq!(spec.constants[0].name, "MAX_RETRIES");
+    }
+
+    #[test]
+    fn test_link_merges_stub() {
+        let plugin = PythonPlugin::new();
+        let module = r#"
+def load(path):
+    """Load a config file."""
+    return open(path).read()
+
+def helper():
+    pass
+"#;
+        let stub = r#"
+def load(path: str) -> str:
+    """Read a file."""
+def helper() -> None:
+    """Do nothing."""
+def extra(flag: bool) -> None: ...
+"#;
+        let mut specs = vec![
+            plugin.extract(module, Path::new("pkg/config.py")).unwrap(),
+            plugin.extract(stub, Path::new("pkg/config.pyi")).unwrap(),
+        ];
+        plugin.link(&mut specs).unwrap();
+
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].file, "pkg/config.py");
+        assert_eq!(specs[0].functions.len(), 3);
+
+        let load = &specs[0].functions[0];
+        assert_eq!(load.signature, "def load(path: str) -> str");
+        assert!(load.doc.as_ref().unwrap().contains("Load a config"));
+        // The stub's docstring is used when the module has none
+        assert_eq!(specs[0].functions[1].doc.as_deref(), Some("Do nothing."));
+    }
 }
@@ -0,0 +1,439 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tree_sitter::{Language, Node, Query, QueryCursor, StreamingIterator};
+
+use super::lexer::squash;
+use crate::cache::content_hash;
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser, BUILD_VERSION};
+use crate::spec::{ConstSpec, ErrorSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+
+/// Item categories a query can capture, each as `@<category>` for the item's
+/// node and `@<category>.<part>` for its pieces.
+const CATEGORIES: &[&str] = &["type", "function", "method", "constant", "variable", "error"];
+
+/// Manifest of a query plugin, e.g. `.opensdd/plugins/lua.toml`.
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    name: String,
+    /// One of the grammars built into spec-extract (see `grammar`)
+    grammar: String,
+    extensions: Vec<String>,
+    /// Query file, relative to the manifest (defaults to `<manifest stem>.scm`)
+    queries: Option<PathBuf>,
+}
+
+/// Plugin driven by tree-sitter queries instead of Rust code.
+///
+/// Captures use standard names: `@package` and `@import`, and for each item
+/// category (`type`, `function`, `method`, `constant`, `variable`, `error`) the
+/// item node itself plus parts such as `@type.name`, `@type.field`,
+/// `@function.signature`, `@function.body` or `@constant.value`. `@doc` marks
+/// the item's comments; without it the comments preceding the item are used.
+/// A type's kind comes from `@type.kind` or a `(#set! kind "struct")` property.
+///
+/// Grammars are compiled in, so a manifest can only pick one of the built-in
+/// grammars; queries and extensions are free to change without a rebuild.
+pub struct QueryPlugin {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    language: Language,
+    query: Query,
+    version: String,
+}
+
+/// Captures sharing one item node, gathered across all matches.
+struct Item<'a> {
+    category: &'static str,
+    node: Node<'a>,
+    parts: Vec<(String, Node<'a>)>,
+    kind: Option<String>,
+}
+
+impl<'a> Item<'a> {
+    fn text(&self, part: &str, source: &'a str) -> Option<String> {
+        self.texts(part, source).into_iter().next()
+    }
+
+    fn texts(&self, part: &str, source: &'a str) -> Vec<String> {
+        self.parts
+            .iter()
+            .filter(|(name, _)| name == part)
+            .map(|(_, node)| squash(NodeHelper::text(*node, source)))
+            .collect()
+    }
+
+    fn doc(&self, source: &str) -> Option<String> {
+        let docs: Vec<String> = self
+            .parts
+            .iter()
+            .filter(|(name, _)| name == "doc")
+            .map(|(_, node)| NodeHelper::clean_comment(NodeHelper::text(*node, source)))
+            .collect();
+        if !docs.is_empty() {
+            return Some(docs.join("\n"));
+        }
+        // Comments usually precede the whole declaration (`const x = 1` for a
+        // captured `const_spec`), so look at the parent when the item comes first
+        NodeHelper::preceding_comment(self.node, source).or_else(|| {
+            let parent = self.node.parent().filter(|p| p.parent().is_some())?;
+            if self.node.prev_named_sibling().is_some() {
+                return None;
+            }
+            NodeHelper::preceding_comment(parent, source)
+        })
+    }
+
+    /// The `signature` part, or the item's text up to its `body`.
+    fn signature(&self, source: &str) -> String {
+        if let Some(signature) = self.text("signature", source) {
+            return signature;
+        }
+        let end = self
+            .parts
+            .iter()
+            .find(|(name, _)| name == "body")
+            .map(|(_, body)| body.start_byte())
+            .unwrap_or(self.node.end_byte());
+        squash(&source[self.node.start_byte()..end])
+    }
+}
+
+/// Give a plugin loaded at runtime the `&'static` names the trait expects.
+///
+/// Plugins are registered once per run and live until it ends, so leaking
+/// their names is bounded by the number of manifests.
+pub(super) fn leak_names(
+    name: &str,
+    extensions: &[&str],
+) -> (&'static str, &'static [&'static str]) {
+    let extensions: Vec<&'static str> = extensions
+        .iter()
+        .map(|ext| &*Box::leak(ext.trim_start_matches('.').to_string().into_boxed_str()))
+        .collect();
+    (Box::leak(name.to_string().into_boxed_str()), Box::leak(extensions.into_boxed_slice()))
+}
+
+/// Look up a grammar compiled into spec-extract by name.
+pub fn grammar(name: &str) -> Option<Language> {
+    let language = match name {
+        "go" => tree_sitter_go::LANGUAGE,
+        "rust" => tree_sitter_rust::LANGUAGE,
+        "python" => tree_sitter_python::LANGUAGE,
+        "typescript" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX,
+        "javascript" => tree_sitter_javascript::LANGUAGE,
+        "java" => tree_sitter_java::LANGUAGE,
+        "csharp" => tree_sitter_c_sharp::LANGUAGE,
+        "c" => tree_sitter_c::LANGUAGE,
+        "cpp" => tree_sitter_cpp::LANGUAGE,
+        "swift" => tree_sitter_swift::LANGUAGE,
+        "ruby" => tree_sitter_ruby::LANGUAGE,
+        "php" => tree_sitter_php::LANGUAGE_PHP,
+        "elixir" => tree_sitter_elixir::LANGUAGE,
+        _ => return None,
+    };
+    Some(language.into())
+}
+
+impl QueryPlugin {
+    /// Create a plugin from a grammar name and the source of its queries.
+    pub fn new(name: &str, grammar_name: &str, extensions: &[&str], queries: &str) -> Result<Self> {
+        let language =
+            grammar(grammar_name).ok_or_else(|| anyhow!("Unknown grammar: {}", grammar_name))?;
+        let query = Query::new(&language, queries)
+            .map_err(|e| anyhow!("Invalid queries for {}: {}", name, e))?;
+        for capture in query.capture_names() {
+            let category = capture.split('.').next().unwrap_or_default();
+            let known = CATEGORIES.contains(&category)
+                || matches!(*capture, "package" | "import" | "doc")
+                || capture.starts_with('_');
+            if !known {
+                bail!("Unknown capture @{} in queries for {}", capture, name);
+            }
+        }
+
+        let (name, extensions) = leak_names(name, extensions);
+        // Editing the queries changes the output as much as a new release does
+        let hash = content_hash(&[grammar_name.as_bytes(), queries.as_bytes()]);
+        let version = format!("{}.{}", BUILD_VERSION, &hash[..16]);
+        Ok(Self { name, extensions, language, query, version })
+    }
+
+    /// Load a plugin from its TOML manifest.
+    pub fn load(manifest_path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(manifest_path).with_context(|| {
+            format!("Failed to read plugin manifest: {}", manifest_path.display())
+        })?;
+        let manifest: Manifest = toml::from_str(&content)
+            .with_context(|| format!("Invalid plugin manifest: {}", manifest_path.display()))?;
+        let queries_path = manifest_path.parent().unwrap_or(Path::new("")).join(
+            manifest.queries.unwrap_or_else(|| {
+                let stem = manifest_path.file_stem().unwrap_or_default();
+                PathBuf::from(stem).with_extension("scm")
+            }),
+        );
+        let queries = fs::read_to_string(&queries_path)
+            .with_context(|| format!("Failed to read queries: {}", queries_path.display()))?;
+        let extensions: Vec<&str> = manifest.extensions.iter().map(|e| e.as_str()).collect();
+        Self::new(&manifest.name, &manifest.grammar, &extensions, &queries)
+    }
+
+    fn collect<'a>(&self, root: Node<'a>, source: &'a str) -> (Vec<String>, Vec<Item<'a>>) {
+        let names = self.query.capture_names();
+        let mut values: Vec<String> = Vec::new();
+        let mut items: Vec<Item<'a>> = Vec::new();
+
+        let mut cursor = QueryCursor::new();
+        let mut matches = cursor.matches(&self.query, root, source.as_bytes());
+        while let Some(m) = matches.next() {
+            let mut parts = Vec::new();
+            let mut item: Option<(&'static str, Node<'a>)> = None;
+            for capture in m.captures {
+                let name = names[capture.index as usize];
+                match name {
+                    "package" | "import" => {
+                        let text = NodeHelper::text(capture.node, source);
+                        values.push(format!("{} {}", name, text.trim_matches(['"', '\'', '`'])));
+                    }
+                    "doc" => parts.push(("doc".to_string(), capture.node)),
+                    _ => {
+                        let (category, part) = name.split_once('.').unwrap_or((name, ""));
+                        let Some(&category) = CATEGORIES.iter().find(|c| **c == category) else {
+                            continue;
+                        };
+                        if part.is_empty() {
+                            item = Some((category, capture.node));
+                        } else {
+                            item = item.or(Some((category, capture.node.parent().unwrap_or(root))));
+                            parts.push((part.to_string(), capture.node));
+                        }
+                    }
+                }
+            }
+
+            let Some((category, node)) = item else {
+                continue;
+            };
+            let kind = self
+                .query
+                .property_settings(m.pattern_index)
+                .iter()
+                .find(|p| &*p.key == "kind")
+                .and_then(|p| p.value.as_ref().map(|v| v.to_string()));
+            let index = match items.iter().position(|i| i.category == category && i.node == node)
+            {
+                Some(index) => index,
+                None => {
+                    items.push(Item { category, node, parts: Vec::new(), kind: None });
+                    items.len() - 1
+                }
+            };
+            let existing = &mut items[index];
+            for part in parts {
+                if !existing.parts.iter().any(|p| p.0 == part.0 && p.1 == part.1) {
+                    existing.parts.push(part);
+                }
+            }
+            existing.kind = existing.kind.take().or(kind);
+        }
+
+        (values, items)
+    }
+}
+
+impl LanguagePlugin for QueryPlugin {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        self.extensions
+    }
+
+    fn version(&self) -> String {
+        self.version.clone()
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let mut parser = TreeSitterParser::new(self.language.clone())?;
+        let tree = parser.parse(source)?;
+        let (values, items) = self.collect(tree.root_node(), source);
+
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        for value in values {
+            match value.split_once(' ') {
+                Some(("package", name)) if spec.package.is_empty() => spec.package = name.into(),
+                Some(("import", import)) => spec.imports.push(import.to_string()),
+                _ => {}
+            }
+        }
+        if spec.package.is_empty() {
+            spec.package =
+                path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+        }
+
+        for item in &items {
+            let doc = item.doc(source);
+            let name = item.text("name", source).unwrap_or_default();
+            match item.category {
+                "type" => {
+                    let kind = item.text("kind", source).or(item.kind.clone());
+                    let kind = kind.unwrap_or_else(|| "type".to_string());
+                    let implements = item.texts("implements", source);
+                    spec.types.push(TypeSpec {
+                        name: format!("{} {}", name, kind),
+                        doc,
+                        kind,
+                        fields: item.texts("field", source),
+                        methods: item.texts("method", source),
+                        embeds: item.texts("embed", source),
+                        implements: (!implements.is_empty()).then(|| implements.join(", ")),
+                        type_params: item.texts("param", source),
+                        variants: item.texts("variant", source),
+                        ..Default::default()
+                    });
+                }
+                "function" | "method" => {
+                    let func = FuncSpec {
+                        signature: item.signature(source),
+                        doc,
+                        receiver: item.text("receiver", source),
+                        ..Default::default()
+                    };
+                    if item.category == "method" {
+                        spec.methods.push(func);
+                    } else {
+                        spec.functions.push(func);
+                    }
+                }
+                "constant" => spec.constants.push(ConstSpec {
+                    name,
+                    type_name: item.text("type", source),
+                    value: item.text("value", source),
+                    doc,
+                    export: None,
+                    visibility: None,
+                }),
+                "variable" => spec.variables.push(VarSpec {
+                    name,
+                    type_name: item.text("type", source),
+                    doc,
+                    export: None,
+                    visibility: None,
+                }),
+                _ => spec.errors.push(ErrorSpec {
+                    name,
+                    message: item.text("message", source).map(|m| m.trim_matches('"').to_string()),
+                    doc,
+                }),
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::PluginRegistry;
+
+    const GO_QUERIES: &str = include_str!("../../queries/go.scm");
+
+    #[test]
+    fn test_extract_with_go_queries() {
+        let plugin = QueryPlugin::new("go", "go", &["go"], GO_QUERIES).unwrap();
+        let source = r#"
+package users
+
+import (
+	"context"
+	"errors"
+)
+
+// MaxUsers caps the directory size.
+const MaxUsers = 100
+
+// ErrNotFound is returned for unknown ids.
+var ErrNotFound = errors.New("user not found")
+
+// User is a registered account.
+type User struct {
+	ID   int
+	Name string
+}
+
+// Store persists users.
+type Store interface {
+	Get(ctx context.Context, id int) (*User, error)
+}
+
+// NewUser creates a user.
+func NewUser(name string) *User {
+	return &User{Name: name}
+}
+
+// Rename changes the name.
+func (u *User) Rename(name string) {
+	u.Name = name
+}
+"#;
+        let spec = plugin.extract(source, Path::new("users.go")).unwrap();
+        assert_eq!(spec.package, "users");
+        assert_eq!(spec.imports, vec!["context", "errors"]);
+
+        let names: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["User struct", "Store interface"]);
+        assert_eq!(spec.types[0].doc.as_deref(), Some("User is a registered account."));
+        assert_eq!(spec.types[0].fields, vec!["ID int", "Name string"]);
+        assert_eq!(spec.types[1].methods, vec!["Get(ctx context.Context, id int) (*User, error)"]);
+
+        assert_eq!(spec.functions[0].signature, "func NewUser(name string) *User");
+        assert_eq!(spec.functions[0].doc.as_deref(), Some("NewUser creates a user."));
+        assert_eq!(spec.methods[0].signature, "func (u *User) Rename(name string)");
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("*User"));
+
+        assert_eq!(spec.constants[0].name, "MaxUsers");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("100"));
+        assert_eq!(spec.constants[0].doc.as_deref(), Some("MaxUsers caps the directory size."));
+        assert_eq!(spec.errors[0].name, "ErrNotFound");
+        assert_eq!(spec.errors[0].message.as_deref(), Some("user not found"));
+    }
+
+    #[test]
+    fn test_load_manifest_overrides_builtin() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("ruby.toml"),
+            "name = \"ruby\"\ngrammar = \"ruby\"\nextensions = [\"rb\", \"rake\"]\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("ruby.scm"),
+            "((class name: (constant) @type.name) @type (#set! kind \"class\"))\n",
+        )
+        .unwrap();
+
+        let mut registry = PluginRegistry::new();
+        let loaded = registry.load_plugins(dir.path()).unwrap();
+        assert_eq!(loaded, vec!["ruby"]);
+
+        let plugin = registry.get_for_file(Path::new("tasks/db.rake")).unwrap();
+        let spec = plugin.extract("class Migrate\nend\n", Path::new("tasks/db.rake")).unwrap();
+        assert_eq!(spec.types[0].name, "Migrate class");
+    }
+
+    #[test]
+    fn test_rejects_unknown_grammar_and_captures() {
+        let err = QueryPlugin::new("lua", "lua", &["lua"], "").err().unwrap();
+        assert_eq!(err.to_string(), "Unknown grammar: lua");
+
+        let err = QueryPlugin::new("go", "go", &["go"], "(identifier) @name").err().unwrap();
+        assert_eq!(err.to_string(), "Unknown capture @name in queries for go");
+    }
+}
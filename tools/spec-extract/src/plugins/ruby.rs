@@ -0,0 +1,684 @@
+use anyhow::Result;
+use std::path::Path;
+use tree_sitter::Node;
+
+use super::doctags::DocTags;
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
+use crate::spec::{ConstSpec, ErrorSpec, FileSpec, FuncSpec, TypeSpec};
+
+/// Calls in a class body that mix a module into it.
+const MIXINS: &[&str] = &["include", "extend", "prepend"];
+
+/// Calls in a class body that declare attribute accessors.
+const ATTRIBUTES: &[&str] = &["attr_reader", "attr_writer", "attr_accessor"];
+
+/// Calls in a class body that set the visibility of methods.
+const VISIBILITY_CALLS: &[&str] =
+    &["private", "protected", "public", "private_class_method", "public_class_method"];
+
+/// Superclass suffixes that make a class an exception type.
+const EXCEPTION_SUFFIXES: &[&str] = &["Error", "Exception"];
+
+/// Plugin for extracting specifications from Ruby source files.
+pub struct RubyPlugin;
+
+/// A class or module whose body is being walked.
+struct Owner {
+    /// Index of the type in `FileSpec.types`
+    index: usize,
+    /// Name relative to the file's package (`Outer::Inner`)
+    name: String,
+}
+
+/// A class seen while walking, for the exception pass.
+struct ClassInfo {
+    name: String,
+    base: String,
+    doc: Option<String>,
+}
+
+impl RubyPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The outermost namespace modules (`Acme::Users` for directly nested ones),
+    /// or the file name.
+    ///
+    /// A module counts as a namespace only while it defines no methods itself;
+    /// a module with methods is a mixin and is extracted as a type.
+    fn extract_package(&self, root: Node, source: &str, path: &Path) -> Vec<String> {
+        let mut segments = Vec::new();
+        let mut module = self.only_module(root, source);
+
+        while let Some(node) = module {
+            let body = NodeHelper::field(node, "body");
+            let defines_methods = body.is_some_and(|b| {
+                let mut cursor = b.walk();
+                let found = b
+                    .named_children(&mut cursor)
+                    .any(|c| matches!(c.kind(), "method" | "singleton_method"));
+                found
+            });
+            if defines_methods {
+                break;
+            }
+            segments.extend(self.name_segments(node, source));
+            module = body.and_then(|b| self.only_module(b, source));
+        }
+
+        if segments.is_empty() {
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+            vec![stem.to_string()]
+        } else {
+            segments
+        }
+    }
+
+    /// The single module declared in a body that holds nothing else but requires.
+    fn only_module<'a>(&self, body: Node<'a>, source: &str) -> Option<Node<'a>> {
+        let mut cursor = body.walk();
+        let members: Vec<Node> = body
+            .named_children(&mut cursor)
+            .filter(|c| c.kind() != "comment" && !self.is_require(*c, source))
+            .collect();
+        match members.as_slice() {
+            [only] if only.kind() == "module" => Some(*only),
+            _ => None,
+        }
+    }
+
+    fn name_segments(&self, node: Node, source: &str) -> Vec<String> {
+        NodeHelper::field_text(node, "name", source)
+            .map(|name| name.split("::").map(|s| s.trim().to_string()).collect())
+            .unwrap_or_default()
+    }
+
+    /// `require "json"` as `json`, `require_relative "base"` as `./base`.
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<String> {
+        NodeHelper::find_all(root, "call")
+            .into_iter()
+            .filter(|call| self.is_require(*call, source))
+            .filter_map(|call| {
+                let method = NodeHelper::field_text(call, "method", source)?;
+                let args = NodeHelper::field(call, "arguments")?;
+                let target = NodeHelper::child_by_kind(args, "string")?;
+                let path = NodeHelper::child_by_kind(target, "string_content")
+                    .map(|c| NodeHelper::text(c, source))?;
+                if method == "require_relative" && !path.starts_with('.') {
+                    Some(format!("./{}", path))
+                } else {
+                    Some(path.to_string())
+                }
+            })
+            .collect()
+    }
+
+    fn is_require(&self, node: Node, source: &str) -> bool {
+        node.kind() == "call"
+            && NodeHelper::field(node, "receiver").is_none()
+            && matches!(
+                NodeHelper::field_text(node, "method", source),
+                Some("require" | "require_relative")
+            )
+    }
+
+    /// Walk the declarations of a namespace body (the program or a namespace module).
+    fn walk(
+        &self,
+        body: Node,
+        source: &str,
+        namespace: &[String],
+        package: &[String],
+        spec: &mut FileSpec,
+        classes: &mut Vec<ClassInfo>,
+    ) {
+        let mut cursor = body.walk();
+        for child in body.named_children(&mut cursor) {
+            match child.kind() {
+                "module" | "class" => {
+                    self.visit_type(child, source, namespace, package, spec, classes)
+                }
+                "method" | "singleton_method" => spec.functions.push(FuncSpec {
+                    signature: self.signature(child, source, "public", false),
+                    doc: self.doc(child, source),
+                    ..Default::default()
+                }),
+                "assignment" => {
+                    if let Some(constant) = self.constant(child, source, namespace, package) {
+                        match self.struct_type(child, source, namespace, package) {
+                            Some(spec_type) => spec.types.push(spec_type),
+                            None => spec.constants.push(constant),
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_type(
+        &self,
+        node: Node,
+        source: &str,
+        namespace: &[String],
+        package: &[String],
+        spec: &mut FileSpec,
+        classes: &mut Vec<ClassInfo>,
+    ) {
+        let mut path = namespace.to_vec();
+        path.extend(self.name_segments(node, source));
+        let body = NodeHelper::field(node, "body");
+
+        // Modules making up the package only contribute their contents
+        if node.kind() == "module" && package.starts_with(&path) {
+            if let Some(body) = body {
+                self.walk(body, source, &path, package, spec, classes);
+            }
+            return;
+        }
+
+        let name = qualify(&path, package);
+        let doc = self.doc(node, source);
+        let base = NodeHelper::field(node, "superclass")
+            .and_then(|superclass| superclass.named_child(0))
+            .map(|base| NodeHelper::text(base, source).to_string());
+        if node.kind() == "class" {
+            let base = base.clone().unwrap_or_default();
+            classes.push(ClassInfo { name: name.clone(), base, doc: doc.clone() });
+        }
+
+        spec.types.push(TypeSpec {
+            name: format!("{} {}", name, node.kind()),
+            doc,
+            kind: node.kind().to_string(),
+            embeds: base.into_iter().collect(),
+            ..Default::default()
+        });
+
+        if let Some(body) = body {
+            let owner = Owner { index: spec.types.len() - 1, name };
+            self.type_body(body, source, &path, package, &owner, false, spec, classes);
+        }
+    }
+
+    /// Members of a class or module body; `singleton` is set inside `class << self`.
+    #[allow(clippy::too_many_arguments)]
+    fn type_body(
+        &self,
+        body: Node,
+        source: &str,
+        path: &[String],
+        package: &[String],
+        owner: &Owner,
+        singleton: bool,
+        spec: &mut FileSpec,
+        classes: &mut Vec<ClassInfo>,
+    ) {
+        let mut visibility = "public";
+        let mut cursor = body.walk();
+        for child in body.named_children(&mut cursor) {
+            match child.kind() {
+                "identifier" => {
+                    let word = NodeHelper::text(child, source);
+                    if matches!(word, "public" | "private" | "protected") {
+                        visibility = word;
+                    }
+                }
+                "method" | "singleton_method" => {
+                    self.push_method(child, child, source, owner, visibility, singleton, spec)
+                }
+                "singleton_class" => {
+                    if let Some(inner) = NodeHelper::field(child, "body") {
+                        self.type_body(inner, source, path, package, owner, true, spec, classes);
+                    }
+                }
+                "module" | "class" => {
+                    self.visit_type(child, source, path, package, spec, classes)
+                }
+                "assignment" => {
+                    if let Some(constant) = self.constant(child, source, path, package) {
+                        match self.struct_type(child, source, path, package) {
+                            Some(spec_type) => spec.types.push(spec_type),
+                            None => spec.constants.push(constant),
+                        }
+                    }
+                }
+                "call" if NodeHelper::field(child, "receiver").is_none() => {
+                    let method = NodeHelper::field_text(child, "method", source).unwrap_or("");
+                    let args: Vec<Node> = NodeHelper::field(child, "arguments")
+                        .map(|a| a.named_children(&mut a.walk()).collect())
+                        .unwrap_or_default();
+
+                    if MIXINS.contains(&method) {
+                        let mixins = args.iter().map(|a| NodeHelper::text(*a, source).to_string());
+                        spec.types[owner.index].embeds.extend(mixins);
+                    } else if ATTRIBUTES.contains(&method) {
+                        let doc = self.doc(child, source).unwrap_or_default();
+                        let tags = DocTags::yard(&doc);
+                        let prefix = visibility_prefix(visibility);
+                        for arg in args.iter().filter(|a| a.kind() == "simple_symbol") {
+                            let attr = NodeHelper::text(*arg, source).trim_start_matches(':');
+                            let field = match tags.var() {
+                                Some(ty) => format!("{}{} {}: {}", prefix, method, attr, ty),
+                                None => format!("{}{} {}", prefix, method, attr),
+                            };
+                            spec.types[owner.index].fields.push(field);
+                        }
+                    } else if VISIBILITY_CALLS.contains(&method) {
+                        let (visibility, singleton) = match method.strip_suffix("_class_method") {
+                            Some(visibility) => (visibility, true),
+                            None => (method, singleton),
+                        };
+                        // `private def helper; end`
+                        for def in args.iter().filter(|a| a.kind().ends_with("method")) {
+                            self.push_method(
+                                *def, child, source, owner, visibility, singleton, spec,
+                            );
+                        }
+                        // `private :helper, :other` after the definitions
+                        let names: Vec<&str> = args
+                            .iter()
+                            .filter(|a| a.kind() == "simple_symbol")
+                            .map(|a| NodeHelper::text(*a, source).trim_start_matches(':'))
+                            .collect();
+                        restrict_methods(spec, owner, &names, visibility, singleton);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Add a method of `owner`; `anchor` is the node its doc comment precedes.
+    #[allow(clippy::too_many_arguments)]
+    fn push_method(
+        &self,
+        node: Node,
+        anchor: Node,
+        source: &str,
+        owner: &Owner,
+        visibility: &str,
+        singleton: bool,
+        spec: &mut FileSpec,
+    ) {
+        spec.methods.push(FuncSpec {
+            signature: self.signature(node, source, visibility, singleton),
+            doc: self.doc(anchor, source),
+            receiver: Some(owner.name.clone()),
+            ..Default::default()
+        });
+    }
+
+    /// `def [self.]name(params) -> Return`, with parameter and return types from YARD.
+    fn signature(&self, node: Node, source: &str, visibility: &str, singleton: bool) -> String {
+        let doc = self.doc(node, source).unwrap_or_default();
+        let tags = DocTags::yard(&doc);
+
+        let name = NodeHelper::field_text(node, "name", source).unwrap_or("");
+        let object = match NodeHelper::field_text(node, "object", source) {
+            Some(object) => format!("{}.", object),
+            None if singleton => "self.".to_string(),
+            None => String::new(),
+        };
+        let params = NodeHelper::field(node, "parameters")
+            .map(|params| {
+                let mut cursor = params.walk();
+                let params: Vec<String> = params
+                    .named_children(&mut cursor)
+                    .filter(|p| p.kind() != "comment")
+                    .map(|p| self.parameter(p, source, &tags))
+                    .collect();
+                format!("({})", params.join(", "))
+            })
+            .unwrap_or_default();
+        let returns = tags.returns().map(|ty| format!(" -> {}", ty)).unwrap_or_default();
+
+        format!("{}def {}{}{}{}", visibility_prefix(visibility), object, name, params, returns)
+    }
+
+    /// A parameter as written, with its YARD type added after the name.
+    fn parameter(&self, param: Node, source: &str, tags: &DocTags) -> String {
+        let text = NodeHelper::text(param, source);
+        let name = match param.kind() {
+            "identifier" => text,
+            _ => match NodeHelper::field_text(param, "name", source) {
+                Some(name) => name,
+                None => return text.to_string(),
+            },
+        };
+        let ty = match tags.param(name) {
+            Some(ty) => ty,
+            None => return text.to_string(),
+        };
+        let value = NodeHelper::field_text(param, "value", source);
+
+        match (param.kind(), value) {
+            ("splat_parameter", _) => format!("*{}: {}", name, ty),
+            ("hash_splat_parameter", _) => format!("**{}: {}", name, ty),
+            ("block_parameter", _) => format!("&{}: {}", name, ty),
+            (_, Some(value)) => format!("{}: {} = {}", name, ty, value),
+            _ => format!("{}: {}", name, ty),
+        }
+    }
+
+    /// `NAME = value` in a namespace or type body.
+    fn constant(
+        &self,
+        node: Node,
+        source: &str,
+        path: &[String],
+        package: &[String],
+    ) -> Option<ConstSpec> {
+        let left = NodeHelper::field(node, "left").filter(|l| l.kind() == "constant")?;
+        let mut full = path.to_vec();
+        full.push(NodeHelper::text(left, source).to_string());
+        Some(ConstSpec {
+            name: qualify(&full, package),
+            type_name: None,
+            value: NodeHelper::field_text(node, "right", source).map(|v| v.to_string()),
+            doc: self.doc(node, source),
+            export: None,
+            visibility: None,
+        })
+    }
+
+    /// `Point = Struct.new(:x, :y)` and `Point = Data.define(:x, :y)` declare a type.
+    fn struct_type(
+        &self,
+        node: Node,
+        source: &str,
+        path: &[String],
+        package: &[String],
+    ) -> Option<TypeSpec> {
+        let right = NodeHelper::field(node, "right").filter(|r| r.kind() == "call")?;
+        let receiver = NodeHelper::field_text(right, "receiver", source)?;
+        let method = NodeHelper::field_text(right, "method", source)?;
+        if !matches!((receiver, method), ("Struct", "new") | ("Data", "define")) {
+            return None;
+        }
+
+        let mut full = path.to_vec();
+        full.push(NodeHelper::field_text(node, "left", source)?.to_string());
+        let fields = NodeHelper::field(right, "arguments")
+            .map(|args| {
+                NodeHelper::children_by_kind(args, "simple_symbol")
+                    .into_iter()
+                    .map(|s| NodeHelper::text(s, source).trim_start_matches(':').to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Some(TypeSpec {
+            name: format!("{} struct", qualify(&full, package)),
+            doc: self.doc(node, source),
+            kind: "struct".to_string(),
+            fields,
+            ..Default::default()
+        })
+    }
+
+    /// Classes deriving from an exception class, then exceptions named by `@raise`.
+    fn extract_errors(&self, classes: &[ClassInfo], methods: &[FuncSpec]) -> Vec<ErrorSpec> {
+        // A class is an exception if it derives from one, directly or through
+        // another exception class in this file
+        let mut exceptions: Vec<&str> = Vec::new();
+        loop {
+            let before = exceptions.len();
+            for class in classes {
+                let base = class.base.rsplit("::").next().unwrap_or("");
+                let is_exception = EXCEPTION_SUFFIXES.iter().any(|s| base.ends_with(s))
+                    || exceptions.iter().any(|e| e.rsplit("::").next() == Some(base));
+                if is_exception && !base.is_empty() && !exceptions.contains(&class.name.as_str())
+                {
+                    exceptions.push(&class.name);
+                }
+            }
+            if exceptions.len() == before {
+                break;
+            }
+        }
+
+        let mut errors: Vec<ErrorSpec> = classes
+            .iter()
+            .filter(|c| exceptions.contains(&c.name.as_str()))
+            .map(|c| ErrorSpec { name: c.name.clone(), message: None, doc: c.doc.clone() })
+            .collect();
+
+        for method in methods {
+            let tags = DocTags::yard(method.doc.as_deref().unwrap_or(""));
+            for (raised, description) in tags.raises() {
+                let declared = errors.iter().any(|e| {
+                    e.name == *raised || e.name.ends_with(&format!("::{}", raised))
+                });
+                if !declared {
+                    errors.push(ErrorSpec {
+                        name: raised.clone(),
+                        message: None,
+                        doc: description.clone(),
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Comments before a node. The comments before the first declaration of a body
+    /// are attached outside the `body_statement`, so look there too.
+    fn doc(&self, node: Node, source: &str) -> Option<String> {
+        NodeHelper::preceding_comment(node, source).or_else(|| {
+            let parent = node.parent()?;
+            if parent.kind() == "body_statement" && node.prev_named_sibling().is_none() {
+                NodeHelper::preceding_comment(parent, source)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+/// Name relative to the file's package, joined with `::`.
+fn qualify(path: &[String], package: &[String]) -> String {
+    let relative = path.strip_prefix(package).filter(|rest| !rest.is_empty()).unwrap_or(path);
+    relative.join("::")
+}
+
+/// Give the methods of `owner` named in `private :helper` their visibility.
+fn restrict_methods(
+    spec: &mut FileSpec,
+    owner: &Owner,
+    names: &[&str],
+    visibility: &str,
+    singleton: bool,
+) {
+    let owned = spec.methods.iter_mut().filter(|m| m.receiver.as_ref() == Some(&owner.name));
+    for method in owned {
+        let Some((_, definition)) = method.signature.split_once("def ") else {
+            continue;
+        };
+        let name = match definition.strip_prefix("self.") {
+            Some(name) if singleton => name,
+            None if !singleton => definition,
+            _ => continue,
+        };
+        if names.contains(&name.split(['(', ' ']).next().unwrap_or_default()) {
+            method.signature = format!("{}def {}", visibility_prefix(visibility), definition);
+        }
+    }
+}
+
+fn visibility_prefix(visibility: &str) -> String {
+    if visibility == "public" {
+        String::new()
+    } else {
+        format!("{} ", visibility)
+    }
+}
+
+impl Default for RubyPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for RubyPlugin {
+    fn name(&self) -> &'static str {
+        "ruby"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["rb"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let language = tree_sitter_ruby::LANGUAGE;
+        let mut parser = TreeSitterParser::new(language.into())?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+
+        let package = self.extract_package(root, source, path);
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: package.join("::"),
+            imports: self.extract_imports(root, source),
+            ..Default::default()
+        };
+
+        let mut classes = Vec::new();
+        self.walk(root, source, &[], &package, &mut spec, &mut classes);
+        spec.errors = self.extract_errors(&classes, &spec.methods);
+
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_class_with_yard_types() {
+        let plugin = RubyPlugin::new();
+        let source = r#"
+require "json"
+require_relative "base"
+
+module Acme
+  # Serves users.
+  class UserService < BaseService
+    include Enumerable
+    extend Forwardable
+    MAX = 10
+
+    # @return [Repo] the backing store
+    attr_reader :repo
+    attr_accessor :name, :email
+
+    # Finds a user.
+    # @param id [String] the id
+    # @param limit [Integer]
+    # @return [User, nil] the user
+    def find(id, limit: 10, *rest)
+    end
+
+    def self.build(**opts) = new(nil)
+
+    class << self
+      def shared; end
+    end
+
+    private
+
+    def helper; end
+  end
+end
+"#;
+        let spec = plugin.extract(source, Path::new("user_service.rb")).unwrap();
+        assert_eq!(spec.package, "Acme");
+        assert_eq!(spec.imports, vec!["json", "./base"]);
+
+        let class = &spec.types[0];
+        assert_eq!(class.name, "UserService class");
+        assert_eq!(class.doc.as_deref(), Some("Serves users."));
+        assert_eq!(class.embeds, vec!["BaseService", "Enumerable", "Forwardable"]);
+        assert_eq!(
+            class.fields,
+            vec!["attr_reader repo: Repo", "attr_accessor name", "attr_accessor email"]
+        );
+
+        let signatures: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(
+            signatures,
+            vec![
+                "def find(id: String, limit: Integer = 10, *rest) -> User | nil",
+                "def self.build(**opts)",
+                "def self.shared",
+                "private def helper",
+            ]
+        );
+        assert!(spec.methods.iter().all(|m| m.receiver.as_deref() == Some("UserService")));
+
+        assert_eq!(spec.constants[0].name, "UserService::MAX");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("10"));
+    }
+
+    #[test]
+    fn test_extract_mixin_module_and_struct() {
+        let plugin = RubyPlugin::new();
+        let source = r##"
+# Greets people.
+module Greeting
+  DEFAULT = "hi"
+
+  # @param name [String]
+  # @return [String]
+  def greet(name)
+    "#{DEFAULT} #{name}"
+  end
+end
+
+Point = Struct.new(:x, :y)
+
+def helper; end
+"##;
+        let spec = plugin.extract(source, Path::new("greeting.rb")).unwrap();
+        // A module with methods is a mixin, not a namespace
+        assert_eq!(spec.package, "greeting");
+
+        assert_eq!(spec.types[0].name, "Greeting module");
+        assert_eq!(spec.types[0].kind, "module");
+        assert_eq!(spec.types[0].doc.as_deref(), Some("Greets people."));
+        assert_eq!(spec.methods[0].signature, "def greet(name: String) -> String");
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("Greeting"));
+        assert_eq!(spec.constants[0].name, "Greeting::DEFAULT");
+
+        assert_eq!(spec.types[1].name, "Point struct");
+        assert_eq!(spec.types[1].fields, vec!["x", "y"]);
+        assert_eq!(spec.functions[0].signature, "def helper");
+    }
+
+    #[test]
+    fn test_extract_errors() {
+        let plugin = RubyPlugin::new();
+        let source = r#"
+module Store
+  # Base failure of the store.
+  class StoreError < StandardError; end
+
+  class NotFoundError < StoreError; end
+
+  class Repo
+    # @raise [NotFoundError] when missing
+    # @raise [Timeout::Error] when slow
+    def fetch(id); end
+  end
+end
+"#;
+        let spec = plugin.extract(source, Path::new("store.rb")).unwrap();
+        let names: Vec<&str> = spec.errors.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["StoreError", "NotFoundError", "Timeout::Error"]);
+        assert_eq!(spec.errors[0].doc.as_deref(), Some("Base failure of the store."));
+        assert_eq!(spec.errors[2].doc.as_deref(), Some("when slow"));
+    }
+}
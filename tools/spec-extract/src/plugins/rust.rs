@@ -98,8 +98,12 @@ impl RustPlugin {
                     let field_type = NodeHelper::field(field, "type")
                         .map(|n| NodeHelper::text(n, source));
 
+                    let modifier = NodeHelper::child_by_kind(field, "visibility_modifier")
+                        .map(|m| format!("{} ", NodeHelper::text(m, source)))
+                        .unwrap_or_default();
+
                     if let (Some(n), Some(t)) = (field_name, field_type) {
-                        fields.push(format!("{}: {}", n, t));
+                        fields.push(format!("{}{}: {}", modifier, n, t));
                     }
                 }
             }
@@ -111,6 +115,7 @@ impl RustPlugin {
             kind: "struct".to_string(),
             fields,
             type_params,
+            visibility: Some(self.visibility(node, source)),
             ..Default::default()
         })
     }
@@ -148,6 +153,7 @@ impl RustPlugin {
             kind: "enum".to_string(),
             variants,
             type_params,
+            visibility: Some(self.visibility(node, source)),
             ..Default::default()
         })
     }
@@ -186,6 +192,7 @@ impl RustPlugin {
             kind: "trait".to_string(),
             methods,
             type_params,
+            visibility: Some(self.visibility(node, source)),
             ..Default::default()
         })
     }
@@ -200,6 +207,7 @@ impl RustPlugin {
             name: format!("{} type", name),
             doc,
             kind: "type_alias".to_string(),
+            visibility: Some(self.visibility(node, source)),
             ..Default::default()
         })
     }
@@ -219,6 +227,21 @@ impl RustPlugin {
         Some(format!("fn {}{}{}", name, params, return_type))
     }
 
+    /// The item's `pub`, `pub(crate)` or similar modifier, or `private` without one.
+    /// Trait items and the members of trait impls are as visible as the trait.
+    fn visibility(&self, node: Node, source: &str) -> String {
+        if let Some(modifier) = NodeHelper::child_by_kind(node, "visibility_modifier") {
+            return NodeHelper::text(modifier, source).to_string();
+        }
+        let owner =
+            node.parent().filter(|p| p.kind() == "declaration_list").and_then(|p| p.parent());
+        let in_trait = owner.is_some_and(|owner| {
+            owner.kind() == "trait_item"
+                || (owner.kind() == "impl_item" && NodeHelper::field(owner, "trait").is_some())
+        });
+        if in_trait { "pub" } else { "private" }.to_string()
+    }
+
     fn extract_functions(&self, root: Node, source: &str) -> Vec<FuncSpec> {
         let mut functions = Vec::new();
 
@@ -293,6 +316,7 @@ impl RustPlugin {
             signature,
             doc,
             receiver,
+            visibility: Some(self.visibility(node, source)),
             ..Default::default()
         })
     }
@@ -318,6 +342,8 @@ impl RustPlugin {
                     type_name,
                     value,
                     doc,
+                    export: None,
+                    visibility: Some(self.visibility(const_item, source)),
                 });
             }
         }
@@ -342,6 +368,8 @@ impl RustPlugin {
                     name,
                     type_name,
                     doc,
+                    export: None,
+                    visibility: Some(self.visibility(static_item, source)),
                 });
             }
         }
@@ -380,7 +408,7 @@ impl LanguagePlugin for RustPlugin {
             methods: self.extract_methods(root, source),
             constants: self.extract_constants(root, source),
             variables: self.extract_statics(root, source),
-            errors: Vec::new(),
+            ..Default::default()
         })
     }
 }
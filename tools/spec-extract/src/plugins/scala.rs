@@ -0,0 +1,891 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::lexer::{
+    is_modifier, parse_header, parse_param, split_top_level, squash, strip_default, Header,
+    LexOptions, Param, TokenCursor, TokenKind,
+};
+use super::sealed::link_sealed_variants;
+use crate::parser::LanguagePlugin;
+use crate::spec::{ConstSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+
+/// Keywords that may precede a declaration.
+const MODIFIERS: &[&str] = &[
+    "private", "protected", "final", "sealed", "abstract", "implicit", "lazy", "override",
+    "inline", "opaque", "transparent", "open", "infix", "erased", "case",
+];
+
+/// The template (or extension) whose body is being parsed.
+struct Scope {
+    /// Index into `FileSpec::types`; extensions have no type of their own
+    index: Option<usize>,
+    /// Qualified type name, or the extended type for extensions
+    name: String,
+    kind: &'static str,
+    /// Signature prefix for the members (`extension (s: String) `)
+    prefix: String,
+}
+
+/// Plugin for extracting specifications from Scala 2 and 3 source files.
+///
+/// Like the Kotlin plugin this walks declarations over the [`super::lexer`] token
+/// stream. Scala 3 bodies may be delimited by indentation instead of braces, so
+/// bodies and initializers end at the first line indented no deeper than the
+/// declaration that owns them.
+pub struct ScalaPlugin;
+
+impl ScalaPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn lex_options() -> LexOptions {
+        LexOptions {
+            doc_prefixes: &["/**"],
+            operators: &["?=>", "=>", "<-", "<:", ">:", "::", "->"],
+            ..LexOptions::default()
+        }
+    }
+
+    /// Parse declarations until the end of `scope`: its closing brace, or with `indent`
+    /// set, the first line that is not indented past that column.
+    fn parse_members(
+        &self,
+        cursor: &mut TokenCursor,
+        scope: Option<&Scope>,
+        indent: Option<usize>,
+        spec: &mut FileSpec,
+        constructors: &mut HashMap<usize, String>,
+    ) {
+        while let Some(token) = cursor.peek() {
+            let newline_before = token.newline_before;
+            if indent.is_some_and(|col| newline_before && cursor.column() <= col) {
+                return;
+            }
+            match cursor.peek_text() {
+                "}" if scope.is_some() => return,
+                "(" | "[" | "{" => {
+                    cursor.skip_balanced();
+                    continue;
+                }
+                _ => {}
+            }
+
+            let header = self.parse_header(cursor);
+            match cursor.peek_text() {
+                "package" if scope.is_none() => {
+                    cursor.next();
+                    // Chained package clauses (`package a` / `package b`) nest
+                    if let Some(name) = cursor.dotted_name() {
+                        if spec.package.is_empty() {
+                            spec.package = name;
+                        } else {
+                            spec.package = format!("{}.{}", spec.package, name);
+                        }
+                    }
+                    cursor.eat(":");
+                    cursor.eat("{");
+                }
+                "import" | "export" => {
+                    let keyword = cursor.next().map(|t| t.text).unwrap_or("");
+                    let start = cursor.position();
+                    self.skip_rest(cursor, header.column);
+                    if keyword == "import" && scope.is_none() {
+                        let clause = cursor.text(start, cursor.position());
+                        let clause = clause.trim_end_matches(';');
+                        spec.imports.extend(split_top_level(clause).iter().map(|i| squash(i)));
+                    }
+                }
+                "class" | "trait" | "object" | "enum" => {
+                    self.parse_class(cursor, header, scope, spec, constructors);
+                }
+                "case" if scope.is_some_and(|s| s.kind == "enum") => {
+                    self.parse_enum_cases(cursor, scope, spec);
+                }
+                "def" => self.parse_def(cursor, header, scope, spec),
+                "val" | "var" => self.parse_val(cursor, header, scope, spec),
+                "type" => self.parse_type_member(cursor, header, scope, spec),
+                "given" => self.parse_given(cursor, header, scope, spec, constructors),
+                "extension" => self.parse_extension(cursor, header, spec, constructors),
+                "end" => {
+                    // Scala 3 end marker: `end UserService`
+                    cursor.next();
+                    while cursor.peek().is_some_and(|t| !t.newline_before && t.text != "}") {
+                        cursor.next();
+                    }
+                }
+                "}" if scope.is_none() => {
+                    // Closes a `package a { ... }` block
+                    cursor.next();
+                }
+                _ if header.annotations.is_empty() && header.modifiers.is_empty() => {
+                    // Statements and anything else we do not model
+                    cursor.next();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Parse annotations and modifiers, keeping the doc comment in front of them.
+    fn parse_header(&self, cursor: &mut TokenCursor) -> Header {
+        parse_header(cursor, |cursor, offset| self.is_modifier(cursor, offset))
+    }
+
+    /// Whether the token at `offset` is used as a modifier rather than a name.
+    fn is_modifier(&self, cursor: &TokenCursor, offset: usize) -> bool {
+        let text = match cursor.peek_at(offset) {
+            Some(token) if MODIFIERS.contains(&token.text) => token.text,
+            _ => return false,
+        };
+        let next = cursor.peek_at(offset + 1);
+        match text {
+            // `case` also starts enum cases and match arms
+            "case" => next.is_some_and(|t| matches!(t.text, "class" | "object")),
+            "private" | "protected" => {
+                next.is_some_and(|t| t.kind == TokenKind::Ident || matches!(t.text, "@" | "["))
+            }
+            _ => is_modifier(cursor, offset, MODIFIERS),
+        }
+    }
+
+    /// Skip an initializer or body: up to `;`, an unbalanced closing bracket, or the
+    /// next line indented no deeper than `column`.
+    fn skip_rest(&self, cursor: &mut TokenCursor, column: usize) {
+        while let Some(token) = cursor.peek() {
+            let newline_before = token.newline_before;
+            match token.text {
+                ";" => {
+                    cursor.next();
+                    return;
+                }
+                "}" | ")" | "]" => return,
+                _ if newline_before && cursor.column() <= column => return,
+                "(" | "[" | "{" => {
+                    cursor.skip_balanced();
+                }
+                _ => {
+                    cursor.next();
+                }
+            }
+        }
+    }
+
+    /// Read a type up to the end of its line or the next delimiter.
+    fn parse_type(&self, cursor: &mut TokenCursor) -> String {
+        let start = cursor.position();
+        while let Some(token) = cursor.peek() {
+            let text = token.text;
+            let ends_line = cursor.peek_at(1).is_none_or(|t| t.newline_before);
+            let stop = matches!(text, "=" | "{" | "}" | ";" | "," | ")" | "]" | "with" | "derives")
+                || (token.newline_before && cursor.position() > start)
+                // Scala 3 `class A extends B:`
+                || (text == ":" && ends_line);
+            if stop {
+                break;
+            }
+            if matches!(text, "(" | "[") {
+                cursor.skip_balanced();
+            } else {
+                cursor.next();
+            }
+        }
+        squash(cursor.text(start, cursor.position()))
+    }
+
+    /// `[T <: Animal, U]` as a list of parameters.
+    fn type_params(&self, cursor: &mut TokenCursor) -> Vec<String> {
+        if !cursor.at("[") {
+            return Vec::new();
+        }
+        let text = cursor.skip_balanced();
+        split_top_level(&text[1..text.len().saturating_sub(1)])
+            .iter()
+            .map(|p| squash(p))
+            .collect()
+    }
+
+    /// Parse a parenthesized parameter list, returning it as written and its parameters.
+    fn parse_params(&self, cursor: &mut TokenCursor) -> (String, Vec<Param>) {
+        let text = cursor.skip_balanced();
+        let inner = text[1..text.len().saturating_sub(1)].trim();
+        let options = Self::lex_options();
+
+        // `(using ctx: Context)` and `(implicit ec: ExecutionContext)`
+        let (clause, inner) = match inner.split_once(char::is_whitespace) {
+            Some((word @ ("using" | "implicit"), rest)) => (format!("{} ", word), rest),
+            _ => (String::new(), inner),
+        };
+
+        let params: Vec<Param> = split_top_level(inner)
+            .iter()
+            .map(|param| parse_param(param, &options, |c, offset| self.is_modifier(c, offset)))
+            .collect();
+
+        let texts: Vec<&str> = params.iter().map(|p| p.text.as_str()).collect();
+        (format!("({}{})", clause, texts.join(", ")), params)
+    }
+
+    fn qualify(&self, scope: Option<&Scope>, name: &str) -> String {
+        match scope {
+            Some(scope) if scope.index.is_some() => format!("{}.{}", scope.name, name),
+            _ => name.to_string(),
+        }
+    }
+
+    /// Parse a body after `{`, or after `:`/`with` at the end of a line (Scala 3).
+    fn parse_body(
+        &self,
+        cursor: &mut TokenCursor,
+        scope: &Scope,
+        column: usize,
+        spec: &mut FileSpec,
+        constructors: &mut HashMap<usize, String>,
+    ) {
+        if cursor.eat("{") {
+            self.parse_members(cursor, Some(scope), None, spec, constructors);
+            cursor.eat("}");
+        } else if cursor.peek().is_some_and(|t| t.newline_before && cursor.column() > column) {
+            self.parse_members(cursor, Some(scope), Some(column), spec, constructors);
+        }
+    }
+
+    fn parse_class(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+        constructors: &mut HashMap<usize, String>,
+    ) {
+        let keyword = cursor.next().map(|t| t.text).unwrap_or("");
+        let name = cursor.ident().unwrap_or("").to_string();
+        let qualified = self.qualify(scope, &name);
+
+        let kind = match keyword {
+            "trait" if header.has("sealed") => "sealed_trait",
+            "trait" => "trait",
+            "object" if header.has("case") => "case_object",
+            "object" => "object",
+            "enum" => "enum",
+            _ if header.has("case") => "case_class",
+            _ if header.has("sealed") => "sealed_class",
+            _ if header.has("abstract") => "abstract_class",
+            _ => "class",
+        };
+        let suffix = match kind {
+            "trait" | "sealed_trait" => "trait",
+            "object" | "case_object" => "object",
+            "enum" => "enum",
+            _ => "class",
+        };
+
+        let type_params = self.type_params(cursor);
+        let mut fields = Vec::new();
+        let mut attributes = header.attributes("class", None);
+
+        // Constructor parameter lists, possibly after `private` or annotations
+        let saved = cursor.position();
+        self.parse_header(cursor);
+        if !cursor.peek().is_some_and(|t| t.text == "(" && !t.newline_before) {
+            cursor.reset(saved);
+        }
+        let mut lists = Vec::new();
+        while cursor.peek().is_some_and(|t| t.text == "(" && !t.newline_before) {
+            let (text, params) = self.parse_params(cursor);
+            // Case class parameters of the first list are fields too
+            let case_fields = kind == "case_class" && lists.is_empty();
+            for param in &params {
+                if param.property || case_fields {
+                    fields.push(strip_default(&param.text, false));
+                }
+                let member = format!("constructor.{}", param.name);
+                attributes.extend(param.annotations.iter().cloned().map(|mut attr| {
+                    attr.member = Some(member.clone());
+                    attr
+                }));
+            }
+            lists.push(text);
+        }
+        if !lists.is_empty() {
+            constructors.insert(spec.types.len(), lists.concat());
+        }
+
+        // The first parent is the superclass (or a trait); the rest are mixins
+        let mut embeds = Vec::new();
+        let mut mixins = Vec::new();
+        if cursor.eat("extends") {
+            loop {
+                let parent = self.parse_type(cursor);
+                if cursor.at("(") {
+                    cursor.skip_balanced();
+                }
+                if !parent.is_empty() {
+                    if embeds.is_empty() || suffix == "trait" {
+                        embeds.push(parent);
+                    } else {
+                        mixins.push(parent);
+                    }
+                }
+                // A `with` ending the line opens a Scala 3 body rather than naming a mixin
+                let mixin = cursor.at("with")
+                    && cursor.peek_at(1).is_some_and(|t| !t.newline_before && t.text != "{");
+                if !(mixin || cursor.at(",")) {
+                    break;
+                }
+                cursor.next();
+            }
+        }
+        if cursor.eat("derives") {
+            self.parse_type(cursor);
+            while cursor.eat(",") {
+                self.parse_type(cursor);
+            }
+        }
+
+        let index = spec.types.len();
+        spec.types.push(TypeSpec {
+            name: format!("{} {}", qualified, suffix),
+            doc: header.doc,
+            kind: kind.to_string(),
+            fields,
+            embeds,
+            implements: Some(mixins.join(", ")).filter(|s| !s.is_empty()),
+            type_params,
+            attributes,
+            ..Default::default()
+        });
+
+        let inner = Scope { index: Some(index), name: qualified, kind, prefix: String::new() };
+        if cursor.at(":") && cursor.peek_at(1).is_some_and(|t| t.newline_before) {
+            cursor.next();
+        }
+        self.parse_body(cursor, &inner, header.column, spec, constructors);
+    }
+
+    /// `case Red, Green` and `case Circle(radius: Double)` in a Scala 3 enum.
+    fn parse_enum_cases(
+        &self,
+        cursor: &mut TokenCursor,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        let column = cursor.column();
+        cursor.next();
+        let mut variants = Vec::new();
+        while let Some(name) = cursor.ident() {
+            let params =
+                if cursor.at("(") { squash(cursor.skip_balanced()) } else { String::new() };
+            variants.push(format!("{}{}", name, params));
+            if cursor.eat("extends") {
+                self.skip_rest(cursor, column);
+            }
+            if !cursor.eat(",") {
+                break;
+            }
+        }
+        if let Some(index) = scope.and_then(|s| s.index) {
+            spec.types[index].variants.extend(variants);
+        }
+    }
+
+    fn parse_def(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        cursor.next();
+        // Operator names (`++`) span several tokens
+        let start = cursor.position();
+        while let Some(token) = cursor.peek() {
+            let stop = matches!(token.text, "(" | "[" | ":" | "=" | "{" | "}")
+                || (token.newline_before && cursor.position() > start);
+            if stop {
+                break;
+            }
+            cursor.next();
+        }
+        let name = cursor.text(start, cursor.position()).trim().to_string();
+        let type_params =
+            if cursor.at("[") { squash(cursor.skip_balanced()) } else { String::new() };
+
+        let mut lists = String::new();
+        let mut params = Vec::new();
+        while cursor.peek().is_some_and(|t| t.text == "(" && !t.newline_before) {
+            let (text, list) = self.parse_params(cursor);
+            lists.push_str(&text);
+            params.extend(list);
+        }
+        let returns = if cursor.eat(":") { Some(self.parse_type(cursor)) } else { None };
+        if cursor.eat("=") {
+            self.skip_rest(cursor, header.column);
+        } else if cursor.at("{") {
+            // Scala 2 procedure syntax
+            cursor.skip_balanced();
+        }
+
+        let signature = format!(
+            "{}{}def {}{}{}{}",
+            scope.map(|s| s.prefix.as_str()).unwrap_or(""),
+            header.prefix(),
+            name,
+            type_params,
+            lists,
+            returns.map(|r| format!(": {}", r)).unwrap_or_default()
+        );
+
+        let (scope, index) = match scope {
+            Some(scope) => (scope, scope.index),
+            None => {
+                spec.functions.push(FuncSpec { signature, doc: header.doc, ..Default::default() });
+                return;
+            }
+        };
+
+        if let Some(index) = index {
+            let owner = &mut spec.types[index];
+            owner.attributes.extend(header.attributes("method", Some(name.clone())));
+            for param in params {
+                let member = format!("{}.{}", name, param.name);
+                owner.attributes.extend(param.annotations.into_iter().map(|mut attr| {
+                    attr.member = Some(member.clone());
+                    attr
+                }));
+            }
+        }
+
+        // Class and object methods become FileSpec methods; traits list signatures
+        if let (Some(index), "trait" | "sealed_trait") = (index, scope.kind) {
+            spec.types[index].methods.push(signature);
+        } else {
+            spec.methods.push(FuncSpec {
+                signature,
+                doc: header.doc,
+                receiver: Some(scope.name.clone()),
+                ..Default::default()
+            });
+        }
+    }
+
+    fn parse_val(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        let keyword = cursor.next().map(|t| t.text).unwrap_or("");
+        // Pattern definitions (`val (a, b) = ...`) declare no single name
+        let name = match cursor.ident() {
+            Some(name) => name.to_string(),
+            None => {
+                self.skip_rest(cursor, header.column);
+                return;
+            }
+        };
+        let type_name = if cursor.eat(":") { Some(self.parse_type(cursor)) } else { None };
+        let value = if cursor.eat("=") {
+            let start = cursor.position();
+            self.skip_rest(cursor, header.column);
+            let text = squash(cursor.text(start, cursor.position()));
+            Some(text.trim_end_matches(';').trim_end().to_string())
+        } else {
+            None
+        };
+
+        // Constants are `final`/`inline` vals, or capitalized vals of objects
+        let in_object = scope.is_none_or(|s| matches!(s.kind, "object" | "case_object"));
+        let constant = keyword == "val"
+            && (header.has("final")
+                || header.has("inline")
+                || (in_object && name.starts_with(|c: char| c.is_ascii_uppercase())));
+        if constant && value.is_some() {
+            spec.constants.push(ConstSpec {
+                name: self.qualify(scope, &name),
+                type_name,
+                value,
+                doc: header.doc,
+                ..Default::default()
+            });
+            return;
+        }
+
+        match scope.and_then(|s| s.index) {
+            Some(index) => {
+                let field = format!(
+                    "{}{} {}{}",
+                    header.prefix(),
+                    keyword,
+                    name,
+                    type_name.map(|t| format!(": {}", t)).unwrap_or_default()
+                );
+                let owner = &mut spec.types[index];
+                owner.fields.push(field);
+                owner.attributes.extend(header.attributes("field", Some(name)));
+            }
+            None if scope.is_none() => spec.variables.push(VarSpec {
+                name,
+                type_name,
+                doc: header.doc,
+                ..Default::default()
+            }),
+            None => {}
+        }
+    }
+
+    /// `type Id = String` declares an alias; an abstract `type T` is a type member.
+    fn parse_type_member(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+    ) {
+        cursor.next();
+        let start = cursor.position();
+        let name = cursor.ident().unwrap_or("").to_string();
+        let type_params = self.type_params(cursor);
+        if cursor.at("<:") || cursor.at(">:") {
+            cursor.next();
+            self.parse_type(cursor);
+        }
+        let declaration = squash(cursor.text(start, cursor.position()));
+
+        if cursor.eat("=") {
+            self.skip_rest(cursor, header.column);
+            spec.types.push(TypeSpec {
+                name: format!("{} type", self.qualify(scope, &name)),
+                doc: header.doc,
+                kind: "type_alias".to_string(),
+                type_params,
+                ..Default::default()
+            });
+        } else if let Some(index) = scope.and_then(|s| s.index) {
+            spec.types[index].type_params.push(declaration);
+        }
+    }
+
+    /// `given intOrd: Ord[Int] with { ... }`, `given Ord[Int] = ...` and friends.
+    ///
+    /// Anonymous givens are named the way the compiler names them (`given_Ord_Int`).
+    fn parse_given(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        scope: Option<&Scope>,
+        spec: &mut FileSpec,
+        constructors: &mut HashMap<usize, String>,
+    ) {
+        cursor.next();
+        let start = cursor.position();
+        let mut name = cursor.ident().map(str::to_string);
+        let mut type_params = self.type_params(cursor);
+        while cursor.peek().is_some_and(|t| t.text == "(" && !t.newline_before) {
+            cursor.skip_balanced();
+        }
+        let type_name = if cursor.eat(":") {
+            self.parse_type(cursor)
+        } else {
+            // No name after all: the type follows directly
+            cursor.reset(start);
+            name = None;
+            type_params.clear();
+            self.parse_type(cursor)
+        };
+        let name = name.unwrap_or_else(|| given_name(&type_name));
+        let qualified = self.qualify(scope, &name);
+
+        let index = spec.types.len();
+        spec.types.push(TypeSpec {
+            name: format!("{} given", qualified),
+            doc: header.doc,
+            kind: "given".to_string(),
+            implements: Some(type_name),
+            type_params,
+            ..Default::default()
+        });
+
+        if cursor.eat("with") {
+            let inner =
+                Scope { index: Some(index), name: qualified, kind: "given", prefix: String::new() };
+            self.parse_body(cursor, &inner, header.column, spec, constructors);
+        } else if cursor.eat("=") {
+            self.skip_rest(cursor, header.column);
+        }
+    }
+
+    /// `extension (s: String) def slug: String`, or a block of extension methods.
+    fn parse_extension(
+        &self,
+        cursor: &mut TokenCursor,
+        header: Header,
+        spec: &mut FileSpec,
+        constructors: &mut HashMap<usize, String>,
+    ) {
+        cursor.next();
+        let type_params =
+            if cursor.at("[") { squash(cursor.skip_balanced()) } else { String::new() };
+        let mut lists = Vec::new();
+        let mut receiver = String::new();
+        while cursor.at("(") {
+            let (text, params) = self.parse_params(cursor);
+            if lists.is_empty() {
+                receiver = params
+                    .first()
+                    .and_then(|p| p.text.split_once(':'))
+                    .map(|(_, ty)| ty.trim().to_string())
+                    .unwrap_or_default();
+            }
+            lists.push(text);
+        }
+
+        let prefix = match type_params.as_str() {
+            "" => format!("extension {} ", lists.join("")),
+            _ => format!("extension {} {} ", type_params, lists.join("")),
+        };
+        let scope = Scope { index: None, name: receiver, kind: "extension", prefix };
+        if cursor.eat("{") {
+            self.parse_members(cursor, Some(&scope), None, spec, constructors);
+            cursor.eat("}");
+        } else {
+            self.parse_members(cursor, Some(&scope), Some(header.column), spec, constructors);
+        }
+    }
+}
+
+/// The compiler's name for an anonymous given (`Ord[List[Int]]` -> `given_Ord_List_Int`).
+fn given_name(type_name: &str) -> String {
+    let mut name = String::from("given");
+    for part in type_name.split(|c: char| !c.is_alphanumeric() && c != '_') {
+        if !part.is_empty() {
+            name.push('_');
+            name.push_str(part);
+        }
+    }
+    name
+}
+
+impl Default for ScalaPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for ScalaPlugin {
+    fn name(&self) -> &'static str {
+        "scala"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["scala", "sc"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let mut cursor = TokenCursor::new(source, &Self::lex_options());
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+
+        let mut constructors = HashMap::new();
+        self.parse_members(&mut cursor, None, None, &mut spec, &mut constructors);
+        link_sealed_variants(&mut spec.types, &constructors);
+
+        if spec.package.is_empty() {
+            // Default package: fall back to the file name
+            spec.package =
+                path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown").to_string();
+        }
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_classes_and_objects() {
+        let plugin = ScalaPlugin::new();
+        let source = r#"
+package com.acme
+package users
+
+import scala.concurrent.{ExecutionContext, Future}
+
+/** Serves users. */
+class UserService(private val repo: UserRepository, @Named("ttl") ttl: Int)
+    extends BaseService with Logging {
+  val cache: Map[String, User] = Map.empty
+
+  /** Finds a user. */
+  def find(id: String)(using ec: ExecutionContext): Future[Option[User]] =
+    repo.find(id)
+
+  private def helper(x: Int) = x + 1
+}
+
+object UserService:
+  final val MaxUsers = 100
+  def apply(repo: UserRepository): UserService =
+    new UserService(repo, 10)
+
+trait UserRepository:
+  type Key
+  def find(id: String): Future[Option[User]]
+
+extension (s: String)
+  def slug: String = s.toLowerCase
+
+def main(args: Array[String]): Unit = println("hi")
+"#;
+        let spec = plugin.extract(source, Path::new("UserService.scala")).unwrap();
+        assert_eq!(spec.package, "com.acme.users");
+        assert_eq!(spec.imports, vec!["scala.concurrent.{ExecutionContext, Future}"]);
+
+        let class = &spec.types[0];
+        assert_eq!(class.name, "UserService class");
+        assert_eq!(class.doc.as_deref(), Some("Serves users."));
+        assert_eq!(class.embeds, vec!["BaseService"]);
+        assert_eq!(class.implements.as_deref(), Some("Logging"));
+        assert_eq!(
+            class.fields,
+            vec!["private val repo: UserRepository", "val cache: Map[String, User]"]
+        );
+        assert_eq!(class.attributes[0].name, "Named");
+        assert_eq!(class.attributes[0].member.as_deref(), Some("constructor.ttl"));
+
+        assert_eq!(spec.types[1].name, "UserService object");
+        assert_eq!(spec.constants[0].name, "UserService.MaxUsers");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("100"));
+
+        let repo = &spec.types[2];
+        assert_eq!(repo.kind, "trait");
+        assert_eq!(repo.type_params, vec!["Key"]);
+        assert_eq!(repo.methods, vec!["def find(id: String): Future[Option[User]]"]);
+
+        let methods: Vec<(&str, Option<&str>)> = spec
+            .methods
+            .iter()
+            .map(|m| (m.signature.as_str(), m.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                (
+                    "def find(id: String)(using ec: ExecutionContext): Future[Option[User]]",
+                    Some("UserService")
+                ),
+                ("private def helper(x: Int)", Some("UserService")),
+                ("def apply(repo: UserRepository): UserService", Some("UserService")),
+                ("extension (s: String) def slug: String", Some("String")),
+            ]
+        );
+        assert_eq!(spec.methods[0].doc.as_deref(), Some("Finds a user."));
+        assert_eq!(spec.functions[0].signature, "def main(args: Array[String]): Unit");
+    }
+
+    #[test]
+    fn test_extract_templates_annotations_and_generics() {
+        let plugin = ScalaPlugin::new();
+        let source = r#"
+object Messages {
+  final val Greeting = s"Hello, ${name.getOrElse("guest")} {not a block}"
+  final val Json = s"""{ "tags": [${tags.map(t => s"\"$t\"").mkString(",")}] }"""
+
+  @deprecated(
+    message = "use group",
+    since = "2.0"
+  )
+  @throws[IOException]
+  def index(rows: List[(String, Map[Int, List[Either[Error, Row]]])]): Map[String, List[Row]] =
+    rows.groupMap(_._1)(_._2).view.mapValues(_.flatten).toMap
+
+  def label(row: Row): String = s"${row.id}: ${row.tags.mkString("{", ", ", "}")}"
+}
+"#;
+        let spec = plugin.extract(source, Path::new("Messages.scala")).unwrap();
+        let constants: Vec<(&str, Option<&str>)> =
+            spec.constants.iter().map(|c| (c.name.as_str(), c.value.as_deref())).collect();
+        assert_eq!(
+            constants,
+            vec![
+                (
+                    "Messages.Greeting",
+                    Some(r#"s"Hello, ${name.getOrElse("guest")} {not a block}""#),
+                ),
+                (
+                    "Messages.Json",
+                    Some(r#"s"""{ "tags": [${tags.map(t => s"\"$t\"").mkString(",")}] }""""#),
+                ),
+            ]
+        );
+
+        let messages = &spec.types[0];
+        assert_eq!(messages.name, "Messages object");
+        let attrs: Vec<(&str, Option<&str>)> = messages
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(attrs, vec![("deprecated", Some("index")), ("throws", Some("index"))]);
+        assert_eq!(messages.attributes[0].args, vec!["message = \"use group\"", "since = \"2.0\""]);
+
+        let methods: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(
+            methods,
+            vec![
+                "def index(rows: List[(String, Map[Int, List[Either[Error, Row]]])]): \
+                 Map[String, List[Row]]",
+                "def label(row: Row): String",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_case_classes_enums_and_givens() {
+        let plugin = ScalaPlugin::new();
+        let source = r#"
+sealed trait Shape
+case class Circle(radius: Double, label: String = "") extends Shape
+case object Empty extends Shape
+
+enum Color:
+  case Red, Green
+  case Custom(rgb: Int)
+
+given intOrd: Ord[Int] with
+  def compare(a: Int, b: Int): Int = a - b
+
+given Ord[String] = Ord.fromCompare
+
+type UserId = String
+"#;
+        let spec = plugin.extract(source, Path::new("shapes.scala")).unwrap();
+        assert_eq!(spec.package, "shapes");
+
+        let shape = &spec.types[0];
+        assert_eq!(shape.kind, "sealed_trait");
+        assert_eq!(shape.variants, vec!["Circle(radius: Double, label: String = \"\")", "Empty"]);
+
+        let circle = &spec.types[1];
+        assert_eq!(circle.name, "Circle class");
+        assert_eq!(circle.kind, "case_class");
+        assert_eq!(circle.fields, vec!["radius: Double", "label: String"]);
+        assert_eq!(spec.types[2].name, "Empty object");
+        assert_eq!(spec.types[2].kind, "case_object");
+
+        assert_eq!(spec.types[3].variants, vec!["Red", "Green", "Custom(rgb: Int)"]);
+
+        assert_eq!(spec.types[4].name, "intOrd given");
+        assert_eq!(spec.types[4].implements.as_deref(), Some("Ord[Int]"));
+        assert_eq!(spec.methods[0].receiver.as_deref(), Some("intOrd"));
+        assert_eq!(spec.types[5].name, "given_Ord_String given");
+
+        assert_eq!(spec.types[6].name, "UserId type");
+        assert_eq!(spec.types[6].kind, "type_alias");
+    }
+}
@@ -0,0 +1,45 @@
+//! Variants of sealed hierarchies, shared by the Kotlin, Scala and Dart plugins.
+
+use std::collections::HashMap;
+
+use super::lexer::split_top_level;
+use crate::spec::TypeSpec;
+
+/// Fill `variants` of sealed types with their subclasses declared in this file.
+///
+/// `constructors` holds the primary constructor parameters of types by index, which
+/// are appended to the variant names.
+pub(crate) fn link_sealed_variants(
+    types: &mut [TypeSpec],
+    constructors: &HashMap<usize, String>,
+) {
+    for index in 0..types.len() {
+        if !types[index].kind.starts_with("sealed_") {
+            continue;
+        }
+        let sealed = type_name(&types[index].name).to_string();
+        let simple = sealed.rsplit('.').next().unwrap_or(&sealed).to_string();
+
+        let mut variants = Vec::new();
+        for (other, ty) in types.iter().enumerate() {
+            let implements = ty.implements.as_deref().map(split_top_level).unwrap_or_default();
+            let extends = ty.embeds.iter().chain(implements.iter()).any(|base| {
+                let base = base.split(['<', '[']).next().unwrap_or(base).trim();
+                base == sealed || base == simple
+            });
+            if other == index || !extends {
+                continue;
+            }
+            let name = type_name(&ty.name);
+            let name = name.strip_prefix(&format!("{}.", sealed)).unwrap_or(name);
+            let params = constructors.get(&other).map(String::as_str).unwrap_or("");
+            variants.push(format!("{}{}", name, params));
+        }
+        types[index].variants = variants;
+    }
+}
+
+/// The type name without its kind suffix ("Result.Success class" -> "Result.Success").
+fn type_name(spec_name: &str) -> &str {
+    spec_name.rsplit_once(' ').map(|(name, _)| name).unwrap_or(spec_name)
+}
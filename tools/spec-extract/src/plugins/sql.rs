@@ -0,0 +1,795 @@
+use anyhow::{anyhow, Result};
+use sqlparser::ast::{
+    AlterColumnOperation, AlterTableOperation, ColumnDef, ColumnOption, ColumnOptionDef,
+    CommentObject, CreateIndex, Ident, ObjectName, ObjectType, Query, SelectItem, SetExpr,
+    Statement, TableConstraint, TableFactor, UserDefinedTypeRepresentation, ViewColumnDef,
+};
+use sqlparser::dialect::GenericDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::{Token, Tokenizer, Whitespace};
+use std::path::Path;
+
+use crate::parser::LanguagePlugin;
+use crate::spec::{AttributeSpec, FileSpec, TypeSpec};
+
+/// Plugin for extracting the data layer from SQL migration files.
+///
+/// Tables, enum and composite types, views and indexes become types, with
+/// each statement replayed in order so the spec shows the schema a file leaves
+/// behind. Statements on objects the file does not define are kept on a
+/// `migration` type; `link` replays those against earlier migrations, taken in
+/// file name order, so the tree's specs show the final schema.
+pub struct SqlPlugin;
+
+/// A schema change read from a migration.
+enum Change {
+    Statement(Box<Statement>),
+    /// `CREATE TYPE name AS ENUM (...)`, which sqlparser does not support yet
+    CreateEnum { name: String, labels: Vec<String> },
+    /// `ALTER TYPE name ADD VALUE 'label' [BEFORE | AFTER 'other']`
+    AddEnumValue { name: String, label: String, before: Option<String>, after: Option<String> },
+}
+
+impl Change {
+    fn sql(&self) -> String {
+        match self {
+            Change::Statement(statement) => statement.to_string(),
+            Change::CreateEnum { name, labels } => {
+                let labels: Vec<String> = labels.iter().map(|l| quote(l)).collect();
+                format!("CREATE TYPE {} AS ENUM ({})", name, labels.join(", "))
+            }
+            Change::AddEnumValue { name, label, before, after } => {
+                let mut sql = format!("ALTER TYPE {} ADD VALUE {}", name, quote(label));
+                if let Some(before) = before {
+                    sql.push_str(&format!(" BEFORE {}", quote(before)));
+                }
+                if let Some(after) = after {
+                    sql.push_str(&format!(" AFTER {}", quote(after)));
+                }
+                sql
+            }
+        }
+    }
+}
+
+/// The columns and constraints of a table, as parsed back from its spec.
+struct Table {
+    columns: Vec<ColumnDef>,
+    constraints: Vec<TableConstraint>,
+}
+
+impl SqlPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Split `source` into statements, each with the comment lines right above it.
+///
+/// Statements sqlparser cannot read (function bodies, vendor extensions) are
+/// skipped rather than failing the whole migration.
+fn parse_changes(source: &str) -> Result<Vec<(Option<String>, Change)>> {
+    let dialect = GenericDialect {};
+    let tokens = Tokenizer::new(&dialect, source)
+        .tokenize()
+        .map_err(|e| anyhow!("Failed to tokenize SQL: {}", e))?;
+
+    let mut changes = Vec::new();
+    let mut statement: Vec<Token> = Vec::new();
+    let mut doc: Vec<String> = Vec::new();
+    let mut line_start = true;
+    for token in tokens.into_iter().chain(std::iter::once(Token::SemiColon)) {
+        if token == Token::SemiColon {
+            let doc = std::mem::take(&mut doc);
+            let doc = if doc.is_empty() { None } else { Some(doc.join("\n")) };
+            if let Some(change) = parse_change(std::mem::take(&mut statement)) {
+                changes.push((doc, change));
+            }
+            line_start = false;
+            continue;
+        }
+
+        let started = statement.iter().any(|t| !matches!(t, Token::Whitespace(_)));
+        match &token {
+            // A blank line separates a comment from the statement below it
+            Token::Whitespace(Whitespace::Newline) if !started => {
+                if line_start {
+                    doc.clear();
+                }
+                line_start = true;
+            }
+            Token::Whitespace(Whitespace::SingleLineComment { comment, .. }) if !started => {
+                doc.push(comment.trim().to_string());
+                line_start = true;
+            }
+            Token::Whitespace(Whitespace::MultiLineComment(comment)) if !started => {
+                let lines = comment.lines().map(|l| l.trim().trim_start_matches('*').trim());
+                doc.extend(lines.filter(|l| !l.is_empty()).map(String::from));
+                line_start = false;
+            }
+            _ => {}
+        }
+        statement.push(token);
+    }
+    Ok(changes)
+}
+
+fn parse_change(tokens: Vec<Token>) -> Option<Change> {
+    let words: Vec<&Token> = tokens.iter().filter(|t| !matches!(t, Token::Whitespace(_))).collect();
+    if words.is_empty() {
+        return None;
+    }
+    if let Some(change) = parse_enum_change(&words) {
+        return Some(change);
+    }
+    let dialect = GenericDialect {};
+    let statement = Parser::new(&dialect).with_tokens(tokens).parse_statement().ok()?;
+    Some(Change::Statement(Box::new(statement)))
+}
+
+/// Read the enum statements sqlparser lacks from the statement's tokens.
+fn parse_enum_change(tokens: &[&Token]) -> Option<Change> {
+    let is_word = |i: usize, word: &str| {
+        matches!(tokens.get(i), Some(Token::Word(w)) if w.value.eq_ignore_ascii_case(word))
+    };
+    let string_at = |i: usize| match tokens.get(i) {
+        Some(Token::SingleQuotedString(s)) => Some(s.clone()),
+        _ => None,
+    };
+    let create = is_word(0, "CREATE");
+    if !(create || is_word(0, "ALTER")) || !is_word(1, "TYPE") {
+        return None;
+    }
+
+    // Possibly schema-qualified type name
+    let mut parts = Vec::new();
+    let mut i = 2;
+    while let Some(Token::Word(w)) = tokens.get(i) {
+        parts.push(w.value.clone());
+        i += 1;
+        if tokens.get(i) != Some(&&Token::Period) {
+            break;
+        }
+        i += 1;
+    }
+    let name = parts.join(".");
+
+    if create {
+        let open = tokens.get(i + 2) == Some(&&Token::LParen);
+        if !is_word(i, "AS") || !is_word(i + 1, "ENUM") || !open {
+            return None;
+        }
+        let labels = tokens[i + 3..].iter().filter_map(|t| match t {
+            Token::SingleQuotedString(s) => Some(s.clone()),
+            _ => None,
+        });
+        return Some(Change::CreateEnum { name, labels: labels.collect() });
+    }
+
+    if !is_word(i, "ADD") || !is_word(i + 1, "VALUE") {
+        return None;
+    }
+    i += 2;
+    if is_word(i, "IF") {
+        i += 3;
+    }
+    let label = string_at(i)?;
+    let before = if is_word(i + 1, "BEFORE") { string_at(i + 2) } else { None };
+    let after = if is_word(i + 1, "AFTER") { string_at(i + 2) } else { None };
+    Some(Change::AddEnumValue { name, label, before, after })
+}
+
+/// Apply `change` to the objects in `types`, returning whatever part of it
+/// targets objects defined elsewhere.
+fn apply(types: &mut Vec<TypeSpec>, change: Change, doc: Option<String>) -> Option<Change> {
+    let mut statement = match change {
+        Change::Statement(statement) => statement,
+        Change::CreateEnum { name, labels } => {
+            let spec = TypeSpec {
+                name: format!("{} enum", name),
+                doc,
+                kind: "enum".to_string(),
+                variants: labels,
+                ..Default::default()
+            };
+            define(types, spec, false);
+            return None;
+        }
+        Change::AddEnumValue { name, label, before, after } => {
+            let Some(index) = find(types, &name, &["enum"]) else {
+                return Some(Change::AddEnumValue { name, label, before, after });
+            };
+            let variants = &mut types[index].variants;
+            if variants.contains(&label) {
+                return None;
+            }
+            let position = match (&before, &after) {
+                (Some(before), _) => variants.iter().position(|v| v == before),
+                (_, Some(after)) => variants.iter().position(|v| v == after).map(|p| p + 1),
+                _ => None,
+            };
+            variants.insert(position.unwrap_or(variants.len()), label);
+            return None;
+        }
+    };
+
+    match statement.as_mut() {
+        Statement::CreateTable(create) => {
+            let mut spec = TypeSpec {
+                name: format!("{} table", object_name(&create.name)),
+                doc: doc.or_else(|| create.comment.as_ref().map(|c| c.to_string())),
+                kind: "table".to_string(),
+                ..Default::default()
+            };
+            let table = Table {
+                columns: create.columns.clone(),
+                constraints: create.constraints.clone(),
+            };
+            write_table(&mut spec, table);
+            define(types, spec, create.if_not_exists);
+        }
+        Statement::CreateView { name, columns, query, materialized, if_not_exists, .. } => {
+            let spec = view_spec(&object_name(name), columns, query, *materialized, doc);
+            define(types, spec, *if_not_exists);
+        }
+        Statement::CreateType { name, representation } => {
+            let spec = match representation {
+                UserDefinedTypeRepresentation::Enum { labels } => TypeSpec {
+                    name: format!("{} enum", object_name(name)),
+                    doc,
+                    kind: "enum".to_string(),
+                    variants: labels.iter().map(|l| l.value.clone()).collect(),
+                    ..Default::default()
+                },
+                UserDefinedTypeRepresentation::Composite { attributes } => TypeSpec {
+                    name: format!("{} composite", object_name(name)),
+                    doc,
+                    kind: "composite".to_string(),
+                    fields: attributes.iter().map(|a| a.to_string()).collect(),
+                    ..Default::default()
+                },
+            };
+            define(types, spec, false);
+        }
+        Statement::CreateIndex(index) => {
+            let Some(table) = find(types, &object_name(&index.table_name), &["table"]) else {
+                return Some(Change::Statement(statement));
+            };
+            types[table].attributes.push(index_attribute(index));
+        }
+        Statement::AlterTable { name, operations, .. } => {
+            let Some(index) = find(types, &object_name(name), &["table"]) else {
+                return Some(Change::Statement(statement));
+            };
+            let mut table = read_table(&types[index])?;
+            for operation in operations.iter() {
+                if let AlterTableOperation::RenameTable { table_name } = operation {
+                    types[index].name = format!("{} table", object_name(table_name));
+                } else {
+                    alter(&mut table, operation);
+                }
+            }
+            write_table(&mut types[index], table);
+        }
+        Statement::Drop { object_type, names, .. } => {
+            let kinds: &[&str] = match object_type {
+                ObjectType::Table => &["table"],
+                ObjectType::View => &["view", "materialized_view"],
+                ObjectType::Type => &["enum", "composite"],
+                ObjectType::Index => &[],
+                _ => return None,
+            };
+            names.retain(|name| {
+                let name = object_name(name);
+                if *object_type == ObjectType::Index {
+                    return !drop_index(types, &name);
+                }
+                match find(types, &name, kinds) {
+                    Some(index) => {
+                        types.remove(index);
+                        false
+                    }
+                    None => true,
+                }
+            });
+            if !names.is_empty() {
+                return Some(Change::Statement(statement));
+            }
+        }
+        Statement::Comment {
+            object_type: CommentObject::Table, object_name: name, comment, ..
+        } => {
+            let kinds = ["table", "view", "materialized_view"];
+            let Some(index) = find(types, &object_name(name), &kinds) else {
+                return Some(Change::Statement(statement));
+            };
+            types[index].doc = comment.clone();
+        }
+        // Data changes and everything else leave the schema alone
+        _ => {}
+    }
+    None
+}
+
+/// Add `spec`, replacing an earlier definition of the same object.
+fn define(types: &mut Vec<TypeSpec>, spec: TypeSpec, if_not_exists: bool) {
+    match types.iter().position(|t| t.name == spec.name) {
+        Some(_) if if_not_exists => {}
+        Some(index) => types[index] = spec,
+        None => types.push(spec),
+    }
+}
+
+fn find(types: &[TypeSpec], name: &str, kinds: &[&str]) -> Option<usize> {
+    types.iter().position(|t| {
+        kinds.contains(&t.kind.as_str()) && t.name == format!("{} {}", name, t.kind)
+    })
+}
+
+fn drop_index(types: &mut [TypeSpec], name: &str) -> bool {
+    for spec in types.iter_mut().filter(|t| t.kind == "table") {
+        let before = spec.attributes.len();
+        spec.attributes.retain(|a| a.target != "index" || a.member.as_deref() != Some(name));
+        if spec.attributes.len() != before {
+            return true;
+        }
+    }
+    false
+}
+
+/// Parse a table spec back into columns and constraints, so it can be altered.
+fn read_table(spec: &TypeSpec) -> Option<Table> {
+    let constraints = spec.attributes.iter().filter(|a| a.target == "constraint");
+    let definitions: Vec<&String> =
+        spec.fields.iter().chain(constraints.flat_map(|a| a.args.first())).collect();
+    if definitions.is_empty() {
+        return Some(Table { columns: Vec::new(), constraints: Vec::new() });
+    }
+    let definitions: Vec<&str> = definitions.iter().map(|d| d.as_str()).collect();
+    let sql = format!("CREATE TABLE t ({})", definitions.join(", "));
+    match Parser::parse_sql(&GenericDialect {}, &sql).ok()?.pop()? {
+        Statement::CreateTable(create) => {
+            Some(Table { columns: create.columns, constraints: create.constraints })
+        }
+        _ => None,
+    }
+}
+
+/// Write columns and constraints to a table spec, keeping its indexes.
+fn write_table(spec: &mut TypeSpec, table: Table) {
+    let indexes: Vec<AttributeSpec> =
+        spec.attributes.drain(..).filter(|a| a.target == "index").collect();
+    spec.fields = table.columns.iter().map(|c| c.to_string()).collect();
+
+    for column in &table.columns {
+        for option in &column.options {
+            if let ColumnOption::ForeignKey { .. } = option.option {
+                spec.attributes.push(AttributeSpec {
+                    name: "foreign_key".to_string(),
+                    args: vec![option.option.to_string()],
+                    target: "column".to_string(),
+                    member: Some(column.name.value.clone()),
+                });
+            }
+        }
+    }
+    for constraint in &table.constraints {
+        let name = match constraint {
+            TableConstraint::PrimaryKey { .. } => "primary_key",
+            TableConstraint::Unique { .. } => "unique",
+            TableConstraint::ForeignKey { .. } => "foreign_key",
+            TableConstraint::Check { .. } => "check",
+            TableConstraint::Index { .. } | TableConstraint::FulltextOrSpatial { .. } => "index",
+        };
+        spec.attributes.push(AttributeSpec {
+            name: name.to_string(),
+            args: vec![constraint.to_string()],
+            target: "constraint".to_string(),
+            member: constraint_name(constraint).map(|n| n.value.clone()),
+        });
+    }
+    spec.attributes.extend(indexes);
+}
+
+fn alter(table: &mut Table, operation: &AlterTableOperation) {
+    match operation {
+        AlterTableOperation::AddColumn { column_def, .. }
+            if column(table, &column_def.name).is_none() =>
+        {
+            table.columns.push(column_def.clone());
+        }
+        AlterTableOperation::DropColumn { column_name, .. } => {
+            table.columns.retain(|c| !same(&c.name, column_name));
+        }
+        AlterTableOperation::RenameColumn { old_column_name, new_column_name } => {
+            if let Some(column) = column(table, old_column_name) {
+                column.name = new_column_name.clone();
+            }
+        }
+        AlterTableOperation::ChangeColumn { old_name, new_name, data_type, options, .. } => {
+            if let Some(column) = column(table, old_name) {
+                column.name = new_name.clone();
+                column.data_type = data_type.clone();
+                column.options = column_options(options);
+            }
+        }
+        AlterTableOperation::ModifyColumn { col_name, data_type, options, .. } => {
+            if let Some(column) = column(table, col_name) {
+                column.data_type = data_type.clone();
+                column.options = column_options(options);
+            }
+        }
+        AlterTableOperation::AlterColumn { column_name, op } => {
+            let Some(column) = column(table, column_name) else {
+                return;
+            };
+            let options = &mut column.options;
+            match op {
+                AlterColumnOperation::SetNotNull => {
+                    options.retain(|o| !matches!(o.option, ColumnOption::Null));
+                    if !options.iter().any(|o| matches!(o.option, ColumnOption::NotNull)) {
+                        options.push(ColumnOptionDef { name: None, option: ColumnOption::NotNull });
+                    }
+                }
+                AlterColumnOperation::DropNotNull => {
+                    options.retain(|o| !matches!(o.option, ColumnOption::NotNull));
+                }
+                AlterColumnOperation::SetDefault { value } => {
+                    options.retain(|o| !matches!(o.option, ColumnOption::Default(_)));
+                    let option = ColumnOption::Default(value.clone());
+                    options.push(ColumnOptionDef { name: None, option });
+                }
+                AlterColumnOperation::DropDefault => {
+                    options.retain(|o| !matches!(o.option, ColumnOption::Default(_)));
+                }
+                AlterColumnOperation::SetDataType { data_type, .. } => {
+                    column.data_type = data_type.clone();
+                }
+                AlterColumnOperation::AddGenerated { .. } => {}
+            }
+        }
+        AlterTableOperation::AddConstraint(constraint) => {
+            table.constraints.push(constraint.clone());
+        }
+        AlterTableOperation::DropConstraint { name, .. } => {
+            table.constraints.retain(|c| !constraint_name(c).is_some_and(|n| same(n, name)));
+            for column in &mut table.columns {
+                column.options.retain(|o| !o.name.as_ref().is_some_and(|n| same(n, name)));
+            }
+        }
+        AlterTableOperation::RenameConstraint { old_name, new_name } => {
+            for constraint in &mut table.constraints {
+                if let Some(name) = constraint_name_mut(constraint) {
+                    if same(name, old_name) {
+                        *name = new_name.clone();
+                    }
+                }
+            }
+        }
+        AlterTableOperation::DropPrimaryKey => {
+            table.constraints.retain(|c| !matches!(c, TableConstraint::PrimaryKey { .. }));
+            for column in &mut table.columns {
+                column.options.retain(|o| {
+                    !matches!(o.option, ColumnOption::Unique { is_primary: true, .. })
+                });
+            }
+        }
+        _ => {}
+    }
+}
+
+fn column<'a>(table: &'a mut Table, name: &Ident) -> Option<&'a mut ColumnDef> {
+    table.columns.iter_mut().find(|c| same(&c.name, name))
+}
+
+fn column_options(options: &[ColumnOption]) -> Vec<ColumnOptionDef> {
+    options.iter().map(|o| ColumnOptionDef { name: None, option: o.clone() }).collect()
+}
+
+fn constraint_name(constraint: &TableConstraint) -> Option<&Ident> {
+    match constraint {
+        TableConstraint::Unique { name, .. }
+        | TableConstraint::PrimaryKey { name, .. }
+        | TableConstraint::ForeignKey { name, .. }
+        | TableConstraint::Check { name, .. }
+        | TableConstraint::Index { name, .. } => name.as_ref(),
+        TableConstraint::FulltextOrSpatial { opt_index_name, .. } => opt_index_name.as_ref(),
+    }
+}
+
+fn constraint_name_mut(constraint: &mut TableConstraint) -> Option<&mut Ident> {
+    match constraint {
+        TableConstraint::Unique { name, .. }
+        | TableConstraint::PrimaryKey { name, .. }
+        | TableConstraint::ForeignKey { name, .. }
+        | TableConstraint::Check { name, .. }
+        | TableConstraint::Index { name, .. } => name.as_mut(),
+        TableConstraint::FulltextOrSpatial { opt_index_name, .. } => opt_index_name.as_mut(),
+    }
+}
+
+/// `unique_index(email)` for `CREATE UNIQUE INDEX users_email ON users (email)`
+fn index_attribute(index: &CreateIndex) -> AttributeSpec {
+    let mut args: Vec<String> = index.columns.iter().map(|c| c.to_string()).collect();
+    if let Some(predicate) = &index.predicate {
+        args.push(format!("WHERE {}", predicate));
+    }
+    AttributeSpec {
+        name: if index.unique { "unique_index" } else { "index" }.to_string(),
+        args,
+        target: "index".to_string(),
+        member: index.name.as_ref().map(object_name),
+    }
+}
+
+/// A view's columns, with the tables it reads from as embeds.
+fn view_spec(
+    name: &str,
+    columns: &[ViewColumnDef],
+    query: &Query,
+    materialized: bool,
+    doc: Option<String>,
+) -> TypeSpec {
+    let kind = if materialized { "materialized_view" } else { "view" };
+    let mut spec = TypeSpec {
+        name: format!("{} {}", name, kind),
+        doc,
+        kind: kind.to_string(),
+        fields: columns.iter().map(|c| c.name.value.clone()).collect(),
+        ..Default::default()
+    };
+
+    let mut body = query.body.as_ref();
+    let select = loop {
+        match body {
+            SetExpr::Select(select) => break Some(select),
+            SetExpr::SetOperation { left, .. } => body = left,
+            SetExpr::Query(query) => body = query.body.as_ref(),
+            _ => break None,
+        }
+    };
+    let Some(select) = select else {
+        return spec;
+    };
+
+    if spec.fields.is_empty() {
+        spec.fields = select
+            .projection
+            .iter()
+            .map(|item| match item {
+                SelectItem::ExprWithAlias { alias, .. } => alias.value.clone(),
+                SelectItem::UnnamedExpr(sqlparser::ast::Expr::Identifier(ident)) => {
+                    ident.value.clone()
+                }
+                SelectItem::UnnamedExpr(sqlparser::ast::Expr::CompoundIdentifier(parts)) => {
+                    parts.last().map(|p| p.value.clone()).unwrap_or_default()
+                }
+                other => other.to_string(),
+            })
+            .collect();
+    }
+    for from in &select.from {
+        let joined = from.joins.iter().map(|j| &j.relation);
+        for relation in std::iter::once(&from.relation).chain(joined) {
+            if let TableFactor::Table { name, .. } = relation {
+                let name = object_name(name);
+                if !spec.embeds.contains(&name) {
+                    spec.embeds.push(name);
+                }
+            }
+        }
+    }
+    spec
+}
+
+fn object_name(name: &ObjectName) -> String {
+    name.0.iter().map(|part| part.value.as_str()).collect::<Vec<_>>().join(".")
+}
+
+fn same(a: &Ident, b: &Ident) -> bool {
+    a.value.eq_ignore_ascii_case(&b.value)
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+impl Default for SqlPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for SqlPlugin {
+    fn name(&self) -> &'static str {
+        "sql"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["sql"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+        let mut types = Vec::new();
+        let mut pending = Vec::new();
+        for (doc, change) in parse_changes(source)? {
+            if let Some(rest) = apply(&mut types, change, doc) {
+                pending.push(rest.sql());
+            }
+        }
+        if !pending.is_empty() {
+            types.push(TypeSpec {
+                name: format!("{} migration", stem),
+                kind: "migration".to_string(),
+                fields: pending,
+                ..Default::default()
+            });
+        }
+
+        Ok(FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: stem.to_string(),
+            types,
+            ..Default::default()
+        })
+    }
+
+    // Replay migrations in file name order, so each object ends up in its final
+    // shape in the file that created it
+    fn link(&self, specs: &mut Vec<FileSpec>) -> Result<()> {
+        let mut indices: Vec<usize> =
+            (0..specs.len()).filter(|&i| self.can_handle(Path::new(&specs[i].file))).collect();
+        indices.sort_by(|&a, &b| specs[a].file.cmp(&specs[b].file));
+
+        for (n, &idx) in indices.iter().enumerate() {
+            let earlier = &indices[..n];
+            if let Some(pos) = specs[idx].types.iter().position(|t| t.kind == "migration") {
+                let statements = std::mem::take(&mut specs[idx].types[pos].fields);
+                let mut remaining = Vec::new();
+                for sql in statements {
+                    let Some((_, change)) = parse_changes(&sql)?.into_iter().next() else {
+                        remaining.push(sql);
+                        continue;
+                    };
+                    let mut rest = Some(change);
+                    for &j in earlier.iter().rev() {
+                        match rest {
+                            Some(change) => rest = apply(&mut specs[j].types, change, None),
+                            None => break,
+                        }
+                    }
+                    if let Some(change) = rest {
+                        remaining.push(change.sql());
+                    }
+                }
+                if remaining.is_empty() {
+                    specs[idx].types.remove(pos);
+                } else {
+                    specs[idx].types[pos].fields = remaining;
+                }
+            }
+
+            // Objects created again replace their earlier definitions
+            let names: Vec<String> = specs[idx]
+                .types
+                .iter()
+                .filter(|t| t.kind != "migration")
+                .map(|t| t.name.clone())
+                .collect();
+            for &j in earlier {
+                specs[j].types.retain(|t| !names.contains(&t.name));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_schema() {
+        let plugin = SqlPlugin::new();
+        let source = r#"
+-- Initial schema
+
+CREATE TYPE user_role AS ENUM ('admin', 'member');
+
+-- Registered users.
+CREATE TABLE users (
+    id BIGINT PRIMARY KEY,
+    org_id BIGINT NOT NULL REFERENCES orgs(id) ON DELETE CASCADE,
+    email TEXT NOT NULL,
+    legacy TEXT,
+    role user_role NOT NULL DEFAULT 'member',
+    CONSTRAINT users_email_key UNIQUE (email)
+);
+
+CREATE UNIQUE INDEX users_org_email ON users (org_id, email);
+
+ALTER TABLE users ADD COLUMN name TEXT;
+ALTER TABLE users DROP COLUMN legacy;
+ALTER TABLE users ALTER COLUMN name SET NOT NULL;
+ALTER TYPE user_role ADD VALUE 'owner' BEFORE 'admin';
+
+CREATE VIEW admins AS
+    SELECT u.id, u.email AS address FROM users u JOIN orgs o ON o.id = u.org_id
+    WHERE u.role = 'admin';
+
+INSERT INTO users (id, email) VALUES (1, 'root@example.com');
+"#;
+        let spec = plugin.extract(source, Path::new("db/0001_init.sql")).unwrap();
+        assert_eq!(spec.package, "0001_init");
+        let names: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["user_role enum", "users table", "admins view"]);
+
+        assert_eq!(spec.types[0].doc, None);
+        assert_eq!(spec.types[0].variants, vec!["owner", "admin", "member"]);
+
+        let users = &spec.types[1];
+        assert_eq!(users.doc.as_deref(), Some("Registered users."));
+        assert_eq!(
+            users.fields,
+            vec![
+                "id BIGINT PRIMARY KEY",
+                "org_id BIGINT NOT NULL REFERENCES orgs (id) ON DELETE CASCADE",
+                "email TEXT NOT NULL",
+                "role user_role NOT NULL DEFAULT 'member'",
+                "name TEXT NOT NULL",
+            ]
+        );
+        let attrs: Vec<(&str, &str, Option<&str>)> = users
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.target.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                ("foreign_key", "column", Some("org_id")),
+                ("unique", "constraint", Some("users_email_key")),
+                ("unique_index", "index", Some("users_org_email")),
+            ]
+        );
+        assert_eq!(users.attributes[0].args, vec!["REFERENCES orgs (id) ON DELETE CASCADE"]);
+        assert_eq!(users.attributes[2].args, vec!["org_id", "email"]);
+
+        let admins = &spec.types[2];
+        assert_eq!(admins.fields, vec!["id", "address"]);
+        assert_eq!(admins.embeds, vec!["users", "orgs"]);
+    }
+
+    #[test]
+    fn test_link_replays_migrations() {
+        let plugin = SqlPlugin::new();
+        let init = "CREATE TABLE users (id BIGINT PRIMARY KEY, email TEXT);\n\
+                    CREATE TABLE sessions (id BIGINT);\n\
+                    CREATE INDEX users_email ON users (email);";
+        let rename = "ALTER TABLE users RENAME COLUMN email TO address;\n\
+                      ALTER TABLE users ADD CONSTRAINT users_address_key UNIQUE (address);\n\
+                      DROP INDEX users_email;\n\
+                      DROP TABLE sessions, audit_log;";
+        let second = plugin.extract(rename, Path::new("migrations/0002_rename.sql")).unwrap();
+        assert_eq!(second.types.len(), 1);
+        assert_eq!(second.types[0].name, "0002_rename migration");
+        assert_eq!(second.types[0].fields.len(), 4);
+
+        let mut specs = vec![
+            second,
+            plugin.extract(init, Path::new("migrations/0001_init.sql")).unwrap(),
+        ];
+        plugin.link(&mut specs).unwrap();
+
+        let users = &specs[1].types[0];
+        assert_eq!(specs[1].types.len(), 1);
+        assert_eq!(users.fields, vec!["id BIGINT PRIMARY KEY", "address TEXT"]);
+        assert_eq!(users.attributes.len(), 1);
+        assert_eq!(users.attributes[0].args, vec!["CONSTRAINT users_address_key UNIQUE (address)"]);
+
+        // The table no migration defines stays pending
+        assert_eq!(specs[0].types[0].fields, vec!["DROP TABLE audit_log"]);
+    }
+}
@@ -0,0 +1,714 @@
+use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Component, Path};
+use tree_sitter::Node;
+
+use super::lexer::{split_top_level, squash, unquote};
+use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
+use crate::spec::{AttributeSpec, ConstSpec, ErrorSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+
+/// Standard library protocols, so a class conforming to one is not read as subclassing it.
+const KNOWN_PROTOCOLS: &[&str] = &[
+    "AnyObject", "Codable", "Comparable", "CustomDebugStringConvertible",
+    "CustomStringConvertible", "Decodable", "Encodable", "Equatable", "Error", "Hashable",
+    "Identifiable", "LocalizedError", "ObservableObject", "Sendable",
+];
+
+/// Conformances that make a type an error.
+const ERROR_PROTOCOLS: &[&str] = &["Error", "LocalizedError"];
+
+/// Plugin for extracting specifications from Swift source files.
+pub struct SwiftPlugin;
+
+impl SwiftPlugin {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The SwiftPM module (`Sources/<Module>/...`), or the file name.
+    fn extract_package(&self, path: &Path) -> String {
+        let components: Vec<&str> = path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(part) => part.to_str(),
+                _ => None,
+            })
+            .collect();
+        let module = components
+            .windows(3)
+            .find(|w| w[0] == "Sources")
+            .map(|w| w[1].to_string());
+        module.unwrap_or_else(|| {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        })
+    }
+
+    fn extract_imports(&self, root: Node, source: &str) -> Vec<String> {
+        NodeHelper::children_by_kind(root, "import_declaration")
+            .into_iter()
+            .filter_map(|import| {
+                // `@testable import X` and `import struct X.Y`
+                let text = self.without_attributes(import, source);
+                text.strip_prefix("import").map(|rest| rest.trim().to_string())
+            })
+            .collect()
+    }
+
+    /// Node text after its leading attributes, squashed onto one line.
+    fn without_attributes(&self, node: Node, source: &str) -> String {
+        self.text_until(node, source, node.end_byte())
+    }
+
+    /// Node text after its leading attributes up to `end`, squashed onto one line.
+    fn text_until(&self, node: Node, source: &str, end: usize) -> String {
+        let start = NodeHelper::child_by_kind(node, "modifiers")
+            .and_then(|modifiers| {
+                NodeHelper::children_by_kind(modifiers, "attribute").last().map(|a| a.end_byte())
+            })
+            .unwrap_or(node.start_byte());
+        squash(source[start..end.max(start)].trim())
+    }
+
+    fn attributes(
+        &self,
+        node: Node,
+        source: &str,
+        target: &str,
+        member: Option<&str>,
+    ) -> Vec<AttributeSpec> {
+        let modifiers = match NodeHelper::child_by_kind(node, "modifiers") {
+            Some(modifiers) => modifiers,
+            None => return Vec::new(),
+        };
+        NodeHelper::children_by_kind(modifiers, "attribute")
+            .into_iter()
+            .map(|attr| {
+                let text = NodeHelper::text(attr, source).trim_start_matches('@');
+                let (name, args) = match text.split_once('(') {
+                    Some((name, rest)) => {
+                        let inner = rest.trim_end().trim_end_matches(')');
+                        (name, split_top_level(inner).iter().map(|a| unquote(a)).collect())
+                    }
+                    None => (text, Vec::new()),
+                };
+                AttributeSpec {
+                    name: name.trim().to_string(),
+                    args,
+                    target: target.to_string(),
+                    member: member.map(str::to_string),
+                }
+            })
+            .collect()
+    }
+
+    fn has_modifier(&self, node: Node, source: &str, modifier: &str) -> bool {
+        NodeHelper::child_by_kind(node, "modifiers").is_some_and(|modifiers| {
+            let mut cursor = modifiers.walk();
+            let found = modifiers
+                .named_children(&mut cursor)
+                .any(|m| m.kind() != "attribute" && NodeHelper::text(m, source) == modifier);
+            found
+        })
+    }
+
+    /// The access level of a declaration: `public`, `open`, `internal`, `fileprivate` or
+    /// `private`. `private(set)` only restricts the setter and is skipped.
+    fn access_level(&self, node: Node, source: &str) -> Option<String> {
+        let modifiers = NodeHelper::child_by_kind(node, "modifiers")?;
+        NodeHelper::children_by_kind(modifiers, "visibility_modifier")
+            .into_iter()
+            .map(|m| NodeHelper::text(m, source))
+            .find(|m| !m.contains('('))
+            .map(str::to_string)
+    }
+
+    /// Declarations without an access modifier are `internal`.
+    fn visibility(&self, node: Node, source: &str) -> Option<String> {
+        Some(self.access_level(node, source).unwrap_or_else(|| "internal".to_string()))
+    }
+
+    /// Walk declarations in `node`, qualifying nested type names with `owner`.
+    fn extract_declarations(
+        &self,
+        node: Node,
+        source: &str,
+        owner: Option<&str>,
+        spec: &mut FileSpec,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let doc = NodeHelper::preceding_comment(child, source);
+            match child.kind() {
+                "class_declaration" | "protocol_declaration" => {
+                    self.extract_type(child, source, owner, doc, spec);
+                }
+                "typealias_declaration" => {
+                    let name = NodeHelper::field_text(child, "name", source).unwrap_or("");
+                    spec.types.push(TypeSpec {
+                        name: format!("{} type", qualify(owner, name)),
+                        doc,
+                        kind: "type_alias".to_string(),
+                        visibility: self.visibility(child, source),
+                        ..Default::default()
+                    });
+                }
+                "function_declaration" if owner.is_none() => {
+                    spec.functions.push(FuncSpec {
+                        signature: self.signature(child, source),
+                        doc,
+                        visibility: self.visibility(child, source),
+                        ..Default::default()
+                    });
+                }
+                "property_declaration" if owner.is_none() => {
+                    self.extract_global(child, source, doc, spec);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn extract_type(
+        &self,
+        node: Node,
+        source: &str,
+        owner: Option<&str>,
+        doc: Option<String>,
+        spec: &mut FileSpec,
+    ) {
+        let keyword = match node.kind() {
+            "protocol_declaration" => "protocol",
+            _ => NodeHelper::field_text(node, "declaration_kind", source).unwrap_or("class"),
+        };
+        let name = match NodeHelper::field(node, "name") {
+            // `extension Array where ...` names the generic type without arguments
+            Some(name) => NodeHelper::text(name, source).to_string(),
+            None => return,
+        };
+        let name = if keyword == "extension" { name } else { qualify(owner, &name) };
+
+        let type_params = NodeHelper::child_by_kind(node, "type_parameters")
+            .map(|params| {
+                NodeHelper::children_by_kind(params, "type_parameter")
+                    .into_iter()
+                    .map(|p| NodeHelper::text(p, source).to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // A class's first inherited type is its superclass unless it is a known protocol
+        let mut embeds = Vec::new();
+        let mut conformances = Vec::new();
+        for (i, inherited) in NodeHelper::children_by_kind(node, "inheritance_specifier")
+            .into_iter()
+            .enumerate()
+        {
+            let base = NodeHelper::text(inherited, source).to_string();
+            let is_superclass =
+                keyword == "class" && i == 0 && !KNOWN_PROTOCOLS.contains(&base.as_str());
+            if keyword == "protocol" || is_superclass {
+                embeds.push(base);
+            } else {
+                conformances.push(base);
+            }
+        }
+
+        let mut ty = TypeSpec {
+            name: format!("{} {}", name, keyword),
+            doc: doc.clone(),
+            kind: keyword.to_string(),
+            embeds,
+            implements: Some(conformances.join(", ")).filter(|s| !s.is_empty()),
+            type_params,
+            attributes: self.attributes(node, source, "class", None),
+            // Extensions have no access level of their own, only their members do
+            visibility: match keyword {
+                "extension" => self.access_level(node, source),
+                _ => self.visibility(node, source),
+            },
+            ..Default::default()
+        };
+
+        let is_error = NodeHelper::children_by_kind(node, "inheritance_specifier")
+            .iter()
+            .any(|i| ERROR_PROTOCOLS.contains(&NodeHelper::text(*i, source)));
+        if is_error && keyword != "enum" {
+            spec.errors.push(ErrorSpec { name: name.clone(), message: None, doc: doc.clone() });
+        }
+
+        let index = spec.types.len();
+        spec.types.push(TypeSpec::default());
+
+        if let Some(body) = NodeHelper::field(node, "body") {
+            self.extract_members(body, source, &name, keyword, &mut ty, spec);
+        }
+
+        if is_error && keyword == "enum" {
+            for variant in &ty.variants {
+                let case = variant.split(['(', ' ']).next().unwrap_or(variant);
+                spec.errors.push(ErrorSpec {
+                    name: format!("{}.{}", name, case),
+                    message: None,
+                    doc: doc.clone(),
+                });
+            }
+        }
+
+        spec.types[index] = ty;
+    }
+
+    fn extract_members(
+        &self,
+        body: Node,
+        source: &str,
+        owner: &str,
+        keyword: &str,
+        ty: &mut TypeSpec,
+        spec: &mut FileSpec,
+    ) {
+        let mut cursor = body.walk();
+        for member in body.named_children(&mut cursor) {
+            let doc = NodeHelper::preceding_comment(member, source);
+            match member.kind() {
+                "enum_entry" => ty.variants.extend(self.enum_cases(member, source)),
+                "property_declaration" | "protocol_property_declaration" => {
+                    let name = self.property_name(member, source);
+                    ty.attributes.extend(self.attributes(member, source, "property", Some(&name)));
+
+                    // Static constants belong to the type, as in `User.maxNameLength`
+                    let is_static = self.has_modifier(member, source, "static");
+                    let value = NodeHelper::field(member, "value");
+                    if is_static && self.is_let(member, source) && value.is_some() {
+                        spec.constants.push(ConstSpec {
+                            name: format!("{}.{}", owner, name),
+                            type_name: self.property_type(member, source),
+                            value: value.map(|v| NodeHelper::text(v, source).to_string()),
+                            doc,
+                            visibility: self.visibility(member, source),
+                            ..Default::default()
+                        });
+                        continue;
+                    }
+                    ty.fields.push(self.property_signature(member, source));
+                }
+                "associatedtype_declaration" => {
+                    ty.type_params.push(squash(
+                        NodeHelper::text(member, source).trim_start_matches("associatedtype"),
+                    ));
+                }
+                "function_declaration" | "init_declaration" | "subscript_declaration"
+                | "protocol_function_declaration" => {
+                    // The first `name` field is the function's; later ones are its types
+                    let name = match member.kind() {
+                        "init_declaration" => "init",
+                        "subscript_declaration" => "subscript",
+                        _ => NodeHelper::field_text(member, "name", source).unwrap_or(""),
+                    };
+                    ty.attributes.extend(self.attributes(member, source, "method", Some(name)));
+
+                    // Protocol requirements are listed on the protocol itself
+                    let signature = self.signature(member, source);
+                    if keyword == "protocol" {
+                        ty.methods.push(signature);
+                    } else {
+                        spec.methods.push(FuncSpec {
+                            signature,
+                            doc,
+                            receiver: Some(owner.to_string()),
+                            visibility: self.visibility(member, source),
+                            ..Default::default()
+                        });
+                    }
+                }
+                "class_declaration" | "protocol_declaration" => {
+                    self.extract_type(member, source, Some(owner), doc, spec);
+                }
+                "typealias_declaration" => {
+                    let name = NodeHelper::field_text(member, "name", source).unwrap_or("");
+                    spec.types.push(TypeSpec {
+                        name: format!("{}.{} type", owner, name),
+                        doc,
+                        kind: "type_alias".to_string(),
+                        visibility: self.visibility(member, source),
+                        ..Default::default()
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// `case a = "A", b(Int)` as one variant per name, with associated values or raw value.
+    fn enum_cases(&self, entry: Node, source: &str) -> Vec<String> {
+        let mut cases: Vec<String> = Vec::new();
+        for i in 0..entry.named_child_count() {
+            let child = match entry.named_child(i) {
+                Some(child) => child,
+                None => continue,
+            };
+            match entry.field_name_for_named_child(i as u32) {
+                Some("name") => cases.push(NodeHelper::text(child, source).to_string()),
+                Some("data_contents") => {
+                    if let Some(last) = cases.last_mut() {
+                        last.push_str(&squash(NodeHelper::text(child, source)));
+                    }
+                }
+                Some("raw_value") => {
+                    if let Some(last) = cases.last_mut() {
+                        last.push_str(&format!(" = {}", NodeHelper::text(child, source)));
+                    }
+                }
+                _ => {}
+            }
+        }
+        cases
+    }
+
+    /// Declaration without attributes or body: `public func find(id: ID) async throws -> User`.
+    fn signature(&self, node: Node, source: &str) -> String {
+        let body = NodeHelper::field(node, "body")
+            .or_else(|| NodeHelper::child_by_kind(node, "computed_property"));
+        let end = body.map(|b| b.start_byte()).unwrap_or(node.end_byte());
+        self.text_until(node, source, end)
+    }
+
+    /// `private(set) var name: String`, without initializer or accessors.
+    fn property_signature(&self, node: Node, source: &str) -> String {
+        let end = ["value", "computed_value"]
+            .iter()
+            .filter_map(|field| NodeHelper::field(node, field))
+            .map(|n| n.start_byte())
+            .min()
+            .unwrap_or(node.end_byte());
+        self.text_until(node, source, end).trim_end_matches('=').trim_end().to_string()
+    }
+
+    fn property_name(&self, node: Node, source: &str) -> String {
+        NodeHelper::field(node, "name")
+            .map(|pattern| {
+                NodeHelper::field_text(pattern, "bound_identifier", source)
+                    .unwrap_or_else(|| NodeHelper::text(pattern, source))
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    fn property_type(&self, node: Node, source: &str) -> Option<String> {
+        NodeHelper::child_by_kind(node, "type_annotation")
+            .map(|t| NodeHelper::text(t, source).trim_start_matches(':').trim().to_string())
+    }
+
+    fn is_let(&self, node: Node, source: &str) -> bool {
+        NodeHelper::child_by_kind(node, "value_binding_pattern")
+            .is_some_and(|binding| NodeHelper::text(binding, source) == "let")
+    }
+
+    /// Top-level `let` with a value is a constant; anything else is a variable.
+    fn extract_global(&self, node: Node, source: &str, doc: Option<String>, spec: &mut FileSpec) {
+        let name = self.property_name(node, source);
+        let type_name = self.property_type(node, source);
+        let visibility = self.visibility(node, source);
+        match NodeHelper::field(node, "value") {
+            Some(value) if self.is_let(node, source) => spec.constants.push(ConstSpec {
+                name,
+                type_name,
+                value: Some(NodeHelper::text(value, source).to_string()),
+                doc,
+                visibility,
+                ..Default::default()
+            }),
+            _ => spec.variables.push(VarSpec {
+                name,
+                type_name,
+                doc,
+                visibility,
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+fn qualify(owner: Option<&str>, name: &str) -> String {
+    match owner {
+        Some(owner) => format!("{}.{}", owner, name),
+        None => name.to_string(),
+    }
+}
+
+/// The type name without its kind suffix ("User struct" -> "User").
+fn type_name(spec_name: &str) -> &str {
+    spec_name.rsplit_once(' ').map(|(name, _)| name).unwrap_or(spec_name)
+}
+
+/// Fold conformances declared in extensions into the extended types, and move protocols
+/// that were read as a class's superclass into its conformances.
+fn link_conformances(specs: &mut [FileSpec]) {
+    let mut protocols = HashSet::new();
+    let mut extensions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for ty in specs.iter().flat_map(|s| s.types.iter()) {
+        match ty.kind.as_str() {
+            "protocol" => {
+                protocols.insert(type_name(&ty.name).to_string());
+            }
+            "extension" => {
+                let list = ty.implements.as_deref().map(split_top_level).unwrap_or_default();
+                extensions.entry(type_name(&ty.name).to_string()).or_default().extend(list);
+            }
+            _ => {}
+        }
+    }
+
+    for ty in specs.iter_mut().flat_map(|s| s.types.iter_mut()) {
+        if matches!(ty.kind.as_str(), "extension" | "protocol") {
+            continue;
+        }
+        let mut conformances = ty.implements.as_deref().map(split_top_level).unwrap_or_default();
+        if ty.kind == "class" && ty.embeds.first().is_some_and(|base| protocols.contains(base)) {
+            conformances.insert(0, ty.embeds.remove(0));
+        }
+        for conformance in extensions.get(type_name(&ty.name)).into_iter().flatten() {
+            if !conformances.contains(conformance) {
+                conformances.push(conformance.clone());
+            }
+        }
+        ty.implements = Some(conformances.join(", ")).filter(|s| !s.is_empty());
+    }
+}
+
+impl Default for SwiftPlugin {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LanguagePlugin for SwiftPlugin {
+    fn name(&self) -> &'static str {
+        "swift"
+    }
+
+    fn extensions(&self) -> &[&'static str] {
+        &["swift"]
+    }
+
+    fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
+        let language = tree_sitter_swift::LANGUAGE;
+        let mut parser = TreeSitterParser::new(language.into())?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: self.extract_package(path),
+            imports: self.extract_imports(root, source),
+            ..Default::default()
+        };
+        self.extract_declarations(root, source, None, &mut spec);
+        link_conformances(std::slice::from_mut(&mut spec));
+        Ok(spec)
+    }
+
+    fn link(&self, specs: &mut Vec<FileSpec>) -> Result<()> {
+        let mut swift: Vec<FileSpec> = Vec::new();
+        let mut indices = Vec::new();
+        for (i, spec) in specs.iter_mut().enumerate() {
+            if self.can_handle(Path::new(&spec.file)) {
+                indices.push(i);
+                swift.push(std::mem::take(spec));
+            }
+        }
+        link_conformances(&mut swift);
+        for (i, spec) in indices.into_iter().zip(swift) {
+            specs[i] = spec;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_types() {
+        let plugin = SwiftPlugin::new();
+        let source = r#"
+import Foundation
+@testable import UserKit
+
+/// Stores users.
+public protocol UserStore: AnyObject {
+    associatedtype ID: Hashable
+    var count: Int { get }
+    func find(id: ID) async throws -> User?
+}
+
+/// A user.
+@MainActor
+public struct User: Codable, Equatable {
+    public let id: String
+    private(set) var name: String = ""
+    static let maxNameLength = 64
+
+    public init(id: String) { self.id = id }
+
+    /// Greets someone.
+    public func greeting(for other: User) -> String { "" }
+    fileprivate mutating func rename(to name: String) throws {}
+}
+
+public enum Result<T> {
+    case success(T)
+    case failure(error: Error, code: Int)
+    case loading, idle
+}
+
+open class Service: BaseService, UserStore {
+    @Published var state: Int = 0
+    public override func start() async {}
+    class Cache {}
+}
+
+extension User: CustomStringConvertible {
+    public var description: String { name }
+}
+
+public typealias Handler = (User) -> Void
+let defaultTimeout: TimeInterval = 30
+public func makeUser(_ id: String) throws -> User { User(id: id) }
+"#;
+        let spec = plugin.extract(source, Path::new("Sources/UserKit/User.swift")).unwrap();
+        assert_eq!(spec.package, "UserKit");
+        assert_eq!(spec.imports, vec!["Foundation", "UserKit"]);
+
+        let names: Vec<(&str, &str)> =
+            spec.types.iter().map(|t| (t.name.as_str(), t.kind.as_str())).collect();
+        assert_eq!(
+            names,
+            vec![
+                ("UserStore protocol", "protocol"),
+                ("User struct", "struct"),
+                ("Result enum", "enum"),
+                ("Service class", "class"),
+                ("Service.Cache class", "class"),
+                ("User extension", "extension"),
+                ("Handler type", "type_alias"),
+            ]
+        );
+
+        let store = &spec.types[0];
+        assert_eq!(store.doc.as_deref(), Some("Stores users."));
+        assert_eq!(store.embeds, vec!["AnyObject"]);
+        assert_eq!(store.type_params, vec!["ID: Hashable"]);
+        assert_eq!(store.fields, vec!["var count: Int { get }"]);
+        assert_eq!(store.methods, vec!["func find(id: ID) async throws -> User?"]);
+
+        let user = &spec.types[1];
+        assert_eq!(user.implements.as_deref(), Some("Codable, Equatable, CustomStringConvertible"));
+        assert_eq!(user.fields, vec!["public let id: String", "private(set) var name: String"]);
+        assert_eq!(user.attributes[0].name, "MainActor");
+        assert_eq!(spec.constants[0].name, "User.maxNameLength");
+        assert_eq!(spec.constants[0].value.as_deref(), Some("64"));
+
+        assert_eq!(
+            spec.types[2].variants,
+            vec!["success(T)", "failure(error: Error, code: Int)", "loading", "idle"]
+        );
+
+        let service = &spec.types[3];
+        assert_eq!(service.embeds, vec!["BaseService"]);
+        assert_eq!(service.implements.as_deref(), Some("UserStore"));
+        assert_eq!(service.fields, vec!["var state: Int"]);
+        assert_eq!(service.attributes[0].name, "Published");
+        assert_eq!(service.attributes[0].member.as_deref(), Some("state"));
+
+        let methods: Vec<(&str, Option<&str>)> = spec
+            .methods
+            .iter()
+            .map(|m| (m.signature.as_str(), m.receiver.as_deref()))
+            .collect();
+        assert_eq!(
+            methods,
+            vec![
+                ("public init(id: String)", Some("User")),
+                ("public func greeting(for other: User) -> String", Some("User")),
+                ("fileprivate mutating func rename(to name: String) throws", Some("User")),
+                ("public override func start() async", Some("Service")),
+            ]
+        );
+        assert_eq!(spec.methods[1].doc.as_deref(), Some("Greets someone."));
+        assert_eq!(spec.types[5].fields, vec!["public var description: String"]);
+
+        assert_eq!(
+            spec.functions[0].signature,
+            "public func makeUser(_ id: String) throws -> User"
+        );
+        assert_eq!(spec.constants[1].name, "defaultTimeout");
+        assert_eq!(spec.constants[1].type_name.as_deref(), Some("TimeInterval"));
+
+        let visibilities: Vec<Option<&str>> =
+            spec.types.iter().map(|t| t.visibility.as_deref()).collect();
+        assert_eq!(
+            visibilities,
+            vec![
+                Some("public"),
+                Some("public"),
+                Some("public"),
+                Some("open"),
+                Some("internal"),
+                None,
+                Some("public"),
+            ]
+        );
+        let methods: Vec<&str> =
+            spec.methods.iter().filter_map(|m| m.visibility.as_deref()).collect();
+        assert_eq!(methods, vec!["public", "public", "fileprivate", "public"]);
+        assert_eq!(spec.constants[0].visibility.as_deref(), Some("internal"));
+        assert_eq!(spec.functions[0].visibility.as_deref(), Some("public"));
+    }
+
+    #[test]
+    fn test_extract_errors_and_superclass() {
+        let plugin = SwiftPlugin::new();
+        let source = r#"
+/// Things that go wrong.
+enum StoreError: Error {
+    case notFound
+    case invalid(reason: String)
+}
+
+struct AppError: LocalizedError { let message: String }
+
+final class ViewModel: NSObject, ObservableObject {}
+"#;
+        let spec = plugin.extract(source, Path::new("Errors.swift")).unwrap();
+        assert_eq!(spec.package, "Errors");
+        let errors: Vec<&str> = spec.errors.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(errors, vec!["StoreError.notFound", "StoreError.invalid", "AppError"]);
+        assert_eq!(spec.errors[0].doc.as_deref(), Some("Things that go wrong."));
+
+        let view_model = &spec.types[2];
+        assert_eq!(view_model.embeds, vec!["NSObject"]);
+        assert_eq!(view_model.implements.as_deref(), Some("ObservableObject"));
+    }
+
+    #[test]
+    fn test_link_extension_conformances() {
+        let plugin = SwiftPlugin::new();
+        let mut specs = vec![
+            plugin.extract("class Repo: Store {}", Path::new("Repo.swift")).unwrap(),
+            plugin.extract("protocol Store {}", Path::new("Store.swift")).unwrap(),
+            plugin
+                .extract("extension Repo: Sendable {}", Path::new("Repo+Sendable.swift"))
+                .unwrap(),
+        ];
+        assert_eq!(specs[0].types[0].embeds, vec!["Store"]);
+
+        plugin.link(&mut specs).unwrap();
+        let repo = &specs[0].types[0];
+        assert!(repo.embeds.is_empty());
+        assert_eq!(repo.implements.as_deref(), Some("Store, Sendable"));
+    }
+}
@@ -0,0 +1,531 @@
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use super::javascript::JS_EXTENSIONS;
+
+/// Extensions tried, in order, when a specifier names a file without its extension.
+///
+/// Sources win over declaration files so a package that ships both resolves to its code.
+const RESOLVE_EXTENSIONS: &[&str] = &[
+    "ts", "tsx", "mts", "cts", "d.ts", "d.mts", "d.cts", "js", "jsx", "mjs", "cjs",
+];
+
+/// `package.json` export conditions, in order of preference.
+const EXPORT_CONDITIONS: &[&str] = &["types", "typings", "import", "require", "node", "default"];
+
+/// Compiler options from a `tsconfig.json` that affect module resolution.
+#[derive(Debug, Default)]
+struct TsConfig {
+    /// `baseUrl`, resolved against the config that declared it
+    base_url: Option<PathBuf>,
+    /// `paths` patterns with their substitutions, resolved against `paths_base`
+    paths: Vec<(String, Vec<String>)>,
+    paths_base: PathBuf,
+    /// `outDir` and `rootDir`, used to map build output back to sources
+    out_dir: Option<PathBuf>,
+    root_dir: PathBuf,
+    /// Directories of projects listed in `references`
+    references: Vec<PathBuf>,
+}
+
+/// A workspace package found through a `package.json` with a `name`.
+#[derive(Debug)]
+struct Package {
+    name: String,
+    dir: PathBuf,
+    manifest: Value,
+}
+
+/// Resolves import specifiers to files in the extracted tree.
+///
+/// Relative specifiers resolve against the importing file. Bare specifiers go
+/// through the nearest `tsconfig.json` (`paths`, then `baseUrl`) and then through
+/// the `package.json` of workspace packages (`exports`, `types`, `module`, `main`).
+/// Targets that point into a project's `outDir` are mapped back to its `rootDir`,
+/// so imports of built packages land on their sources.
+pub(crate) struct ModuleResolver<'a> {
+    files: &'a HashMap<PathBuf, usize>,
+    configs: HashMap<PathBuf, TsConfig>,
+    packages: Vec<Package>,
+}
+
+impl<'a> ModuleResolver<'a> {
+    /// Load every `tsconfig.json` and `package.json` in the directories above `files`,
+    /// up to the project root, plus those of referenced projects.
+    pub fn new(files: &'a HashMap<PathBuf, usize>) -> Self {
+        let mut resolver = Self {
+            files,
+            configs: HashMap::new(),
+            packages: Vec::new(),
+        };
+
+        // Directories up to the project root, each listed once: an ancestor
+        // already seen has had the directories above it queued too
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let mut seen: HashSet<PathBuf> = HashSet::new();
+        let mut pending: Vec<PathBuf> = Vec::new();
+        for file in files.keys() {
+            for dir in file.ancestors().skip(1) {
+                if !seen.insert(dir.to_path_buf()) {
+                    break;
+                }
+                pending.push(dir.to_path_buf());
+                if is_project_root(dir, &cwd) {
+                    break;
+                }
+            }
+        }
+
+        while let Some(dir) = pending.pop() {
+            if let Some(config) = load_tsconfig(&dir.join("tsconfig.json"), 0) {
+                for reference in &config.references {
+                    if seen.insert(reference.clone()) {
+                        pending.push(reference.clone());
+                    }
+                }
+                resolver.configs.insert(dir.clone(), config);
+            }
+            if let Some(package) = load_package(&dir) {
+                resolver.packages.push(package);
+            }
+        }
+
+        // Longest names first so `@org/pkg-utils` wins over `@org/pkg`
+        resolver.packages.sort_by(|a, b| b.name.len().cmp(&a.name.len()).then(a.name.cmp(&b.name)));
+        resolver
+    }
+
+    /// Resolve `specifier` as imported from `from_file` to the index of its spec.
+    pub fn resolve(&self, from_file: &str, specifier: &str) -> Option<usize> {
+        let from = normalize_path(Path::new(from_file));
+        let base = from.parent().unwrap_or(Path::new(""));
+
+        if specifier.starts_with('.') || specifier.starts_with('/') {
+            return self.resolve_path(&base.join(specifier));
+        }
+
+        if let Some(config) = self.nearest_config(base) {
+            for target in config.path_targets(specifier) {
+                if let Some(idx) = self.resolve_path(&target) {
+                    return Some(idx);
+                }
+            }
+            let from_base = config
+                .base_url
+                .as_ref()
+                .and_then(|b| self.resolve_path(&b.join(specifier)));
+            if from_base.is_some() {
+                return from_base;
+            }
+        }
+
+        self.resolve_package(specifier)
+    }
+
+    fn resolve_package(&self, specifier: &str) -> Option<usize> {
+        let package = self.packages.iter().find(|p| {
+            specifier == p.name || specifier.starts_with(&format!("{}/", p.name))
+        })?;
+        let subpath = match &specifier[package.name.len()..] {
+            "" => ".".to_string(),
+            rest => format!(".{}", rest),
+        };
+
+        let mut targets = Vec::new();
+        match package.manifest.get("exports") {
+            Some(exports) => export_targets(exports, &subpath, &mut targets),
+            None if subpath == "." => {
+                for field in ["types", "typings", "module", "main"] {
+                    if let Some(entry) = package.manifest.get(field).and_then(Value::as_str) {
+                        targets.push(entry.to_string());
+                    }
+                }
+                targets.push("./index".to_string());
+            }
+            None => targets.push(subpath.clone()),
+        }
+
+        targets.iter().find_map(|t| self.resolve_path(&package.dir.join(t)))
+    }
+
+    /// Resolve a path that may omit its extension or name a directory.
+    fn resolve_path(&self, path: &Path) -> Option<usize> {
+        let path = normalize_path(path);
+        self.find_file(&path).or_else(|| {
+            // Build output (dist/index.js, dist/index.d.ts) maps back to the sources
+            let source = self.source_for_output(&path)?;
+            self.find_file(&source)
+        })
+    }
+
+    fn find_file(&self, path: &Path) -> Option<usize> {
+        let mut stems = vec![path.to_path_buf()];
+        // ESM-style specifiers name the emitted .js file
+        if let Some(stem) = strip_output_extension(path) {
+            stems.push(stem);
+        }
+
+        let mut candidates = stems.clone();
+        for stem in &stems {
+            for ext in RESOLVE_EXTENSIONS {
+                candidates.push(PathBuf::from(format!("{}.{}", stem.display(), ext)));
+            }
+            for ext in RESOLVE_EXTENSIONS {
+                candidates.push(stem.join(format!("index.{}", ext)));
+            }
+        }
+
+        candidates.iter().find_map(|c| self.files.get(c).copied())
+    }
+
+    fn source_for_output(&self, path: &Path) -> Option<PathBuf> {
+        self.configs.values().find_map(|config| {
+            let relative = path.strip_prefix(config.out_dir.as_ref()?).ok()?;
+            Some(config.root_dir.join(relative))
+        })
+    }
+
+    fn nearest_config(&self, dir: &Path) -> Option<&TsConfig> {
+        dir.ancestors().find_map(|d| self.configs.get(d))
+    }
+}
+
+impl TsConfig {
+    /// Substituted paths for every `paths` pattern matching `specifier`.
+    fn path_targets(&self, specifier: &str) -> Vec<PathBuf> {
+        let mut matches: Vec<(usize, &Vec<String>, &str)> = Vec::new();
+        for (pattern, targets) in &self.paths {
+            match pattern.split_once('*') {
+                Some((prefix, suffix)) => {
+                    let captured = specifier
+                        .strip_prefix(prefix)
+                        .and_then(|rest| rest.strip_suffix(suffix))
+                        .filter(|_| specifier.len() >= prefix.len() + suffix.len());
+                    if let Some(captured) = captured {
+                        matches.push((prefix.len(), targets, captured));
+                    }
+                }
+                // Exact patterns beat any wildcard
+                None if pattern == specifier => matches.push((usize::MAX, targets, "")),
+                None => {}
+            }
+        }
+
+        // TypeScript picks the pattern with the longest prefix
+        matches.sort_by_key(|m| std::cmp::Reverse(m.0));
+        matches
+            .into_iter()
+            .flat_map(|(_, targets, captured)| {
+                targets.iter().map(move |t| self.paths_base.join(t.replace('*', captured)))
+            })
+            .collect()
+    }
+}
+
+/// Where the search for `tsconfig.json` and `package.json` files stops: the
+/// working directory (which relative paths end at) or the top of a git checkout.
+fn is_project_root(dir: &Path, cwd: &Path) -> bool {
+    dir.as_os_str().is_empty() || dir == cwd || dir.join(".git").exists()
+}
+
+/// Read a `tsconfig.json`, following relative `extends` chains.
+fn load_tsconfig(path: &Path, depth: usize) -> Option<TsConfig> {
+    let content = fs::read_to_string(path).ok()?;
+    let json: Value = serde_json::from_str(&strip_json_comments(&content)).ok()?;
+    let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+    let mut config = json
+        .get("extends")
+        .and_then(Value::as_str)
+        .filter(|e| e.starts_with('.') && depth < 8)
+        .and_then(|e| {
+            let parent = dir.join(e);
+            let parent = match parent.extension() {
+                Some(_) => parent,
+                None => parent.with_extension("json"),
+            };
+            load_tsconfig(&parent, depth + 1)
+        })
+        .unwrap_or_else(|| TsConfig {
+            paths_base: dir.clone(),
+            root_dir: dir.clone(),
+            ..Default::default()
+        });
+
+    config.root_dir = dir.clone();
+    // `references` are not inherited through `extends`
+    config.references = json
+        .get("references")
+        .and_then(Value::as_array)
+        .map(|refs| {
+            refs.iter()
+                .filter_map(|r| r.get("path").and_then(Value::as_str))
+                .map(|p| {
+                    let target = normalize_path(&dir.join(p));
+                    // A reference may name the config file itself
+                    if target.extension().is_some_and(|e| e == "json") {
+                        target.parent().unwrap_or(Path::new("")).to_path_buf()
+                    } else {
+                        target
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let options = match json.get("compilerOptions") {
+        Some(options) => options,
+        None => return Some(config),
+    };
+
+    if let Some(base_url) = options.get("baseUrl").and_then(Value::as_str) {
+        let base_url = normalize_path(&dir.join(base_url));
+        config.paths_base = base_url.clone();
+        config.base_url = Some(base_url);
+    }
+    if let Some(paths) = options.get("paths").and_then(Value::as_object) {
+        // Without baseUrl, paths are relative to the config that declares them
+        if config.base_url.is_none() {
+            config.paths_base = dir.clone();
+        }
+        config.paths = paths
+            .iter()
+            .map(|(pattern, targets)| {
+                let targets = targets
+                    .as_array()
+                    .map(|t| t.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                    .unwrap_or_default();
+                (pattern.clone(), targets)
+            })
+            .collect();
+    }
+    if let Some(out_dir) = options.get("outDir").and_then(Value::as_str) {
+        config.out_dir = Some(normalize_path(&dir.join(out_dir)));
+    }
+    if let Some(root_dir) = options.get("rootDir").and_then(Value::as_str) {
+        config.root_dir = normalize_path(&dir.join(root_dir));
+    }
+
+    Some(config)
+}
+
+fn load_package(dir: &Path) -> Option<Package> {
+    let content = fs::read_to_string(dir.join("package.json")).ok()?;
+    let manifest: Value = serde_json::from_str(&content).ok()?;
+    let name = manifest.get("name")?.as_str()?.to_string();
+    Some(Package {
+        name,
+        dir: dir.to_path_buf(),
+        manifest,
+    })
+}
+
+/// Collect the targets of a `package.json` `exports` entry for `subpath`, best first.
+fn export_targets(exports: &Value, subpath: &str, targets: &mut Vec<String>) {
+    match exports {
+        Value::String(target) if subpath == "." => targets.push(target.clone()),
+        Value::Object(map) if map.keys().any(|k| k.starts_with('.')) => {
+            if let Some(entry) = map.get(subpath) {
+                export_targets(entry, ".", targets);
+                return;
+            }
+            // Subpath patterns: "./features/*": "./src/features/*.ts"
+            for (key, entry) in map {
+                let captured = key.split_once('*').and_then(|(prefix, suffix)| {
+                    subpath.strip_prefix(prefix)?.strip_suffix(suffix)
+                });
+                if let Some(captured) = captured {
+                    let mut inner = Vec::new();
+                    export_targets(entry, ".", &mut inner);
+                    targets.extend(inner.into_iter().map(|t| t.replace('*', captured)));
+                }
+            }
+        }
+        Value::Object(conditions) => {
+            for condition in EXPORT_CONDITIONS {
+                if let Some(entry) = conditions.get(*condition) {
+                    export_targets(entry, subpath, targets);
+                }
+            }
+        }
+        Value::Array(fallbacks) => {
+            for entry in fallbacks {
+                export_targets(entry, subpath, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Remove the extension of an emitted file (`.js`, `.d.ts`, ...) to get its stem.
+fn strip_output_extension(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let stem = ["d.ts", "d.mts", "d.cts"]
+        .iter()
+        .chain(JS_EXTENSIONS)
+        .find_map(|ext| name.strip_suffix(&format!(".{}", ext)))?;
+    Some(path.with_file_name(stem))
+}
+
+/// Strip `//` and `/* */` comments and trailing commas, which tsconfig files allow.
+fn strip_json_comments(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for c in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            ('}' | ']', _) => {
+                let trimmed = out.trim_end().len();
+                if out[..trimmed].ends_with(',') {
+                    out.truncate(trimmed - 1);
+                }
+                out.push(c);
+            }
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+pub(crate) fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, path: &str, content: &str) -> PathBuf {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_strip_json_comments() {
+        let text = "{\n  // comment\n  \"a\": \"//x\", /* b */\n  \"c\": [1, 2,],\n}";
+        let json: Value = serde_json::from_str(&strip_json_comments(text)).unwrap();
+        assert_eq!(json["a"], "//x");
+        assert_eq!(json["c"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_resolve_paths_and_packages() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "tsconfig.json",
+            r#"{
+                // Shared settings
+                "compilerOptions": {
+                    "baseUrl": ".",
+                    "paths": { "@app/*": ["apps/web/src/*"] },
+                },
+                "references": [{ "path": "./packages/core" }]
+            }"#,
+        );
+        write(
+            root,
+            "packages/core/package.json",
+            r#"{ "name": "@org/core", "exports": { ".": { "types": "./dist/index.d.ts" } } }"#,
+        );
+        write(
+            root,
+            "packages/core/tsconfig.json",
+            r#"{ "compilerOptions": { "outDir": "dist", "rootDir": "src" } }"#,
+        );
+
+        let paths = [
+            "apps/web/src/main.ts",
+            "apps/web/src/util/format.ts",
+            "packages/core/src/index.ts",
+            "lib/shared.d.ts",
+        ];
+        let files: HashMap<PathBuf, usize> = paths
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (normalize_path(&root.join(p)), i))
+            .collect();
+        let resolver = ModuleResolver::new(&files);
+        let main = root.join(paths[0]).to_string_lossy().to_string();
+
+        assert_eq!(resolver.resolve(&main, "./util/format.js"), Some(1));
+        assert_eq!(resolver.resolve(&main, "@app/util/format"), Some(1));
+        assert_eq!(resolver.resolve(&main, "@org/core"), Some(2));
+        assert_eq!(resolver.resolve(&main, "lib/shared"), Some(3));
+        assert_eq!(resolver.resolve(&main, "react"), None);
+    }
+
+    #[test]
+    fn test_config_search_stops_at_project_root() {
+        let dir = tempdir().unwrap();
+        let root = dir.path();
+        // A config outside of the checkout is not the project's
+        write(root, "tsconfig.json", r#"{ "compilerOptions": { "baseUrl": "repo" } }"#);
+        write(root, "repo/.git/HEAD", "ref: refs/heads/main\n");
+        write(root, "repo/web/tsconfig.json", r#"{ "compilerOptions": { "baseUrl": "." } }"#);
+
+        let paths = ["repo/web/main.ts", "repo/web/util.ts", "repo/lib.ts"];
+        let files: HashMap<PathBuf, usize> = paths
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (normalize_path(&root.join(p)), i))
+            .collect();
+        let resolver = ModuleResolver::new(&files);
+        let main = root.join(paths[0]).to_string_lossy().to_string();
+
+        assert_eq!(resolver.resolve(&main, "util"), Some(1));
+        assert_eq!(resolver.resolve(&main, "lib"), None);
+        assert_eq!(resolver.configs.len(), 1);
+    }
+}
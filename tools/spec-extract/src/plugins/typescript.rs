@@ -1,9 +1,32 @@
 use anyhow::Result;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tree_sitter::Node;
 
+use super::javascript::JS_EXTENSIONS;
+use super::jsdoc::{self, JsDoc};
+use super::tsconfig::{normalize_path, ModuleResolver};
 use crate::parser::{LanguagePlugin, NodeHelper, TreeSitterParser};
-use crate::spec::{ConstSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+use crate::spec::{AttributeSpec, ConstSpec, ExportSpec, FileSpec, FuncSpec, TypeSpec, VarSpec};
+
+/// File extensions handled by the TypeScript plugin.
+const TS_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts"];
+
+/// Statements that wrap a declaration without changing what it declares.
+const DECLARATION_WRAPPERS: &[&str] = &[
+    "export_statement",
+    "ambient_declaration",
+    "expression_statement",
+];
+
+/// Nodes whose bodies hold local (non-API) declarations.
+const FUNCTION_KINDS: &[&str] = &[
+    "function_declaration",
+    "generator_function_declaration",
+    "function_expression",
+    "arrow_function",
+    "method_definition",
+];
 
 /// Plugin for extracting specifications from TypeScript source files.
 pub struct TypeScriptPlugin;
@@ -22,11 +45,46 @@ impl TypeScriptPlugin {
         }
     }
 
+    /// Extract a FileSpec with an explicit grammar; shared with the JavaScript plugin.
+    pub(crate) fn extract_with_language(
+        &self,
+        source: &str,
+        path: &Path,
+        language: tree_sitter::Language,
+    ) -> Result<FileSpec> {
+        let mut parser = TreeSitterParser::new(language)?;
+        let tree = parser.parse(source)?;
+        let root = tree.root_node();
+
+        let mut spec = FileSpec {
+            file: path.to_string_lossy().to_string(),
+            package: self.extract_module(path),
+            imports: self.extract_imports(root, source),
+            exports: self.extract_exports(root, source),
+            types: self.extract_types(root, source),
+            functions: self.extract_functions(root, source),
+            constants: self.extract_constants(root, source),
+            variables: self.extract_variables(root, source),
+            ..Default::default()
+        };
+
+        self.apply_local_exports(&mut spec);
+
+        // Declaration files describe an API implemented elsewhere
+        if is_declaration_file(path) {
+            spec.declaration = true;
+            for func in &mut spec.functions {
+                func.declaration = true;
+            }
+        }
+
+        Ok(spec)
+    }
+
     fn extract_module(&self, path: &Path) -> String {
-        path.file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("unknown")
-            .to_string()
+        let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("unknown");
+        // `index.d.ts` declares the `index` module
+        stem.strip_suffix(".d").unwrap_or(stem).to_string()
     }
 
     fn extract_imports(&self, root: Node, source: &str) -> Vec<String> {
@@ -42,6 +100,18 @@ impl TypeScriptPlugin {
             }
         }
 
+        // CommonJS: require('x')
+        for call in NodeHelper::find_all(root, "call_expression") {
+            let callee = NodeHelper::field_text(call, "function", source);
+            if callee != Some("require") {
+                continue;
+            }
+            let arg = NodeHelper::field(call, "arguments").and_then(|args| args.named_child(0));
+            if let Some(arg) = arg.filter(|a| a.kind() == "string") {
+                imports.push(self.unquote(NodeHelper::text(arg, source)));
+            }
+        }
+
         imports
     }
 
@@ -62,6 +132,13 @@ impl TypeScriptPlugin {
             }
         }
 
+        // Extract abstract classes
+        for class_decl in NodeHelper::find_all(root, "abstract_class_declaration") {
+            if let Some(spec) = self.extract_class(class_decl, source) {
+                types.push(spec);
+            }
+        }
+
         // Extract type aliases
         for type_alias in NodeHelper::find_all(root, "type_alias_declaration") {
             if let Some(spec) = self.extract_type_alias(type_alias, source) {
@@ -76,14 +153,50 @@ impl TypeScriptPlugin {
             }
         }
 
+        // Extract JSDoc @typedef comments
+        for comment in NodeHelper::find_all(root, "comment") {
+            let text = NodeHelper::text(comment, source);
+            if text.contains("@typedef") {
+                if let Some(spec) = jsdoc::typedef(&NodeHelper::clean_comment(text)) {
+                    types.push(spec);
+                }
+            }
+        }
+
+        // Extract namespaces (namespace X {}) and modules (module X {} / declare module "x" {})
+        for kind in ["internal_module", "module"] {
+            for module_decl in NodeHelper::find_all(root, kind) {
+                if let Some(spec) = self.extract_namespace(module_decl, source) {
+                    types.push(spec);
+                }
+            }
+        }
+
         types
     }
 
+    fn extract_namespace(&self, node: Node, source: &str) -> Option<TypeSpec> {
+        let name = NodeHelper::field(node, "name")
+            .map(|n| self.unquote(NodeHelper::text(n, source)))?;
+        let name = self.qualified_name(node, &name, source);
+
+        let kind = if node.kind() == "internal_module" { "namespace" } else { "module" };
+
+        Some(TypeSpec {
+            name: format!("{} {}", name, kind),
+            doc: self.doc_comment(node, source),
+            kind: kind.to_string(),
+            export: self.export_status(node),
+            ..Default::default()
+        })
+    }
+
     fn extract_interface(&self, node: Node, source: &str) -> Option<TypeSpec> {
         let name = NodeHelper::field(node, "name")
             .map(|n| NodeHelper::text(n, source).to_string())?;
 
-        let doc = NodeHelper::preceding_comment(node, source);
+        let name = self.qualified_name(node, &name, source);
+        let doc = self.doc_comment(node, source);
 
         let mut methods = Vec::new();
         let mut fields = Vec::new();
@@ -125,6 +238,7 @@ impl TypeScriptPlugin {
             methods,
             type_params,
             embeds,
+            export: self.export_status(node),
             ..Default::default()
         })
     }
@@ -177,13 +291,23 @@ impl TypeScriptPlugin {
         let name = NodeHelper::field(node, "name")
             .map(|n| NodeHelper::text(n, source).to_string())?;
 
-        let doc = NodeHelper::preceding_comment(node, source);
+        let name = self.qualified_name(node, &name, source);
+        let doc = self.doc_comment(node, source);
 
         let mut methods = Vec::new();
         let mut fields = Vec::new();
         let mut type_params = Vec::new();
         let mut embeds = Vec::new();
         let mut implements = None;
+        let mut attributes = Vec::new();
+
+        // Class decorators sit on the class, or on the export statement wrapping it
+        let exported = node.parent().filter(|p| p.kind() == "export_statement");
+        for holder in exported.into_iter().chain(Some(node)) {
+            for decorator in NodeHelper::children_by_kind(holder, "decorator") {
+                attributes.push(self.extract_decorator(decorator, source, "class", None));
+            }
+        }
 
         // Extract type parameters
         if let Some(params) = NodeHelper::child_by_kind(node, "type_parameters") {
@@ -204,6 +328,10 @@ impl TypeScriptPlugin {
                         embeds.push(NodeHelper::text(type_node, source).to_string());
                     }
                 }
+                // JavaScript has no extends_clause: `extends Base` sits in the heritage directly
+                if child.is_named() && !matches!(child.kind(), "extends_clause" | "implements_clause") {
+                    embeds.push(NodeHelper::text(child, source).to_string());
+                }
                 if child.kind() == "implements_clause" {
                     let mut impl_cursor = child.walk();
                     let impl_types: Vec<String> = child
@@ -220,18 +348,26 @@ impl TypeScriptPlugin {
 
         // Extract members
         if let Some(body) = NodeHelper::child_by_kind(node, "class_body") {
-            self.extract_class_members(body, source, &mut fields, &mut methods);
+            self.extract_class_members(body, source, &mut fields, &mut methods, &mut attributes);
         }
 
+        let kind = if node.kind() == "abstract_class_declaration" {
+            "abstract_class"
+        } else {
+            "class"
+        };
+
         Some(TypeSpec {
             name: format!("{} class", name),
             doc,
-            kind: "class".to_string(),
+            kind: kind.to_string(),
             fields,
             methods,
             type_params,
             embeds,
             implements,
+            attributes,
+            export: self.export_status(node),
             ..Default::default()
         })
     }
@@ -242,17 +378,47 @@ impl TypeScriptPlugin {
         source: &str,
         fields: &mut Vec<String>,
         methods: &mut Vec<String>,
+        attributes: &mut Vec<AttributeSpec>,
     ) {
+        // Method decorators are siblings that precede the method in the class body
+        let mut pending: Vec<Node> = Vec::new();
+
         let mut cursor = body.walk();
-        for child in body.children(&mut cursor) {
+        let members: Vec<Node> = body.children(&mut cursor).collect();
+        let signatures: Vec<Node> =
+            members.iter().copied().filter(|m| m.kind() == "method_signature").collect();
+        for child in members {
+            if child.kind() == "decorator" {
+                pending.push(child);
+                continue;
+            }
+            // A comment between a decorator and its member leaves them together
+            if child.kind() == "comment" {
+                continue;
+            }
+            let mut decorators = std::mem::take(&mut pending);
+            decorators.extend(NodeHelper::children_by_kind(child, "decorator"));
+
             match child.kind() {
-                "public_field_definition" | "property_declaration" => {
+                "public_field_definition" | "property_declaration" | "field_definition" => {
+                    // JavaScript class fields name the property instead
                     let name = NodeHelper::field(child, "name")
+                        .or_else(|| NodeHelper::field(child, "property"))
                         .map(|n| NodeHelper::text(n, source));
                     let type_ann = NodeHelper::child_by_kind(child, "type_annotation")
-                        .map(|n| NodeHelper::text(n, source));
+                        .map(|n| NodeHelper::text(n, source).to_string())
+                        .or_else(|| {
+                            let doc = NodeHelper::preceding_comment(child, source)?;
+                            JsDoc::parse(&doc).type_name().map(|t| format!(": {}", t))
+                        });
 
                     if let Some(name) = name {
+                        for decorator in decorators {
+                            let member = Some(name.to_string());
+                            let attribute = self.extract_decorator(decorator, source, "property", member);
+                            attributes.push(attribute);
+                        }
+
                         let field = if let Some(t) = type_ann {
                             format!("{}{}", name, t)
                         } else {
@@ -261,18 +427,42 @@ impl TypeScriptPlugin {
                         fields.push(field);
                     }
                 }
-                "method_definition" => {
+                "method_definition" | "method_signature" | "abstract_method_signature" => {
                     let name = NodeHelper::field(child, "name")
                         .map(|n| NodeHelper::text(n, source));
+                    let prefix = if child.kind() == "abstract_method_signature" {
+                        "abstract "
+                    } else {
+                        ""
+                    };
                     let params = NodeHelper::field(child, "parameters")
-                        .map(|n| NodeHelper::text(n, source))
-                        .unwrap_or("()");
+                        .map(|n| self.parameters_text(n, source))
+                        .unwrap_or_else(|| "()".to_string());
                     let return_type = NodeHelper::child_by_kind(child, "type_annotation")
                         .map(|n| NodeHelper::text(n, source))
                         .unwrap_or("");
 
                     if let Some(name) = name {
-                        methods.push(format!("{}{}{}", name, params, return_type));
+                        for decorator in decorators {
+                            let member = Some(name.to_string());
+                            let attribute = self.extract_decorator(decorator, source, "method", member);
+                            attributes.push(attribute);
+                        }
+                        if let Some(params) = NodeHelper::field(child, "parameters") {
+                            attributes.extend(self.parameter_decorators(params, name, source));
+                        }
+
+                        // Like functions, an overloaded method's implementation is not
+                        // part of its API; its decorators are, as overloads take none
+                        let overloaded = child.kind() == "method_definition"
+                            && signatures
+                                .iter()
+                                .any(|sig| self.is_overload_of(*sig, child, source));
+                        if overloaded {
+                            continue;
+                        }
+                        let signature = format!("{}{}{}{}", prefix, name, params, return_type);
+                        methods.push(self.annotate_jsdoc(child, &signature, source));
                     }
                 }
                 _ => {}
@@ -280,11 +470,89 @@ impl TypeScriptPlugin {
         }
     }
 
+    /// Decorators on the parameters of a method, targeting `method.param`.
+    fn parameter_decorators(&self, params: Node, method: &str, source: &str) -> Vec<AttributeSpec> {
+        let mut attributes = Vec::new();
+        let mut cursor = params.walk();
+        for param in params.named_children(&mut cursor) {
+            let name = NodeHelper::field_text(param, "pattern", source).unwrap_or("");
+            for decorator in NodeHelper::children_by_kind(param, "decorator") {
+                let member = Some(format!("{}.{}", method, name));
+                attributes.push(self.extract_decorator(decorator, source, "parameter", member));
+            }
+        }
+        attributes
+    }
+
+    /// Parameter list as written, minus any parameter decorators.
+    fn parameters_text(&self, params: Node, source: &str) -> String {
+        let mut cursor = params.walk();
+        let decorated = params
+            .named_children(&mut cursor)
+            .any(|p| NodeHelper::has_child_kind(p, "decorator"));
+        if !decorated {
+            return NodeHelper::text(params, source).to_string();
+        }
+
+        let mut cursor = params.walk();
+        let parts: Vec<&str> = params
+            .named_children(&mut cursor)
+            .map(|param| {
+                let mut inner = param.walk();
+                let start = param
+                    .children(&mut inner)
+                    .find(|c| c.kind() != "decorator")
+                    .map(|c| c.start_byte())
+                    .unwrap_or(param.start_byte());
+                &source[start..param.end_byte()]
+            })
+            .collect();
+        format!("({})", parts.join(", "))
+    }
+
+    /// `@Name(args)` or `@Name` as an attribute; string arguments are unquoted.
+    fn extract_decorator(
+        &self,
+        decorator: Node,
+        source: &str,
+        target: &str,
+        member: Option<String>,
+    ) -> AttributeSpec {
+        let expr = decorator.named_child(0);
+        let call = expr.filter(|e| e.kind() == "call_expression");
+
+        let name = match call {
+            Some(call) => NodeHelper::field_text(call, "function", source).unwrap_or(""),
+            None => expr.map(|e| NodeHelper::text(e, source)).unwrap_or(""),
+        };
+
+        let mut args = Vec::new();
+        if let Some(arguments) = call.and_then(|c| NodeHelper::field(c, "arguments")) {
+            let mut cursor = arguments.walk();
+            for arg in arguments.named_children(&mut cursor) {
+                let text = NodeHelper::text(arg, source);
+                if arg.kind() == "string" {
+                    args.push(self.unquote(text));
+                } else if arg.kind() != "comment" {
+                    args.push(text.to_string());
+                }
+            }
+        }
+
+        AttributeSpec {
+            name: name.to_string(),
+            args,
+            target: target.to_string(),
+            member,
+        }
+    }
+
     fn extract_type_alias(&self, node: Node, source: &str) -> Option<TypeSpec> {
         let name = NodeHelper::field(node, "name")
             .map(|n| NodeHelper::text(n, source).to_string())?;
 
-        let doc = NodeHelper::preceding_comment(node, source);
+        let name = self.qualified_name(node, &name, source);
+        let doc = self.doc_comment(node, source);
 
         let mut type_params = Vec::new();
 
@@ -303,6 +571,7 @@ impl TypeScriptPlugin {
             doc,
             kind: "type_alias".to_string(),
             type_params,
+            export: self.export_status(node),
             ..Default::default()
         })
     }
@@ -311,7 +580,8 @@ impl TypeScriptPlugin {
         let name = NodeHelper::field(node, "name")
             .map(|n| NodeHelper::text(n, source).to_string())?;
 
-        let doc = NodeHelper::preceding_comment(node, source);
+        let name = self.qualified_name(node, &name, source);
+        let doc = self.doc_comment(node, source);
 
         let mut variants = Vec::new();
 
@@ -329,47 +599,67 @@ impl TypeScriptPlugin {
             doc,
             kind: "enum".to_string(),
             variants,
+            export: self.export_status(node),
             ..Default::default()
         })
     }
 
     fn extract_functions(&self, root: Node, source: &str) -> Vec<FuncSpec> {
-        let mut functions = Vec::new();
+        let mut functions: Vec<(usize, FuncSpec)> = Vec::new();
+
+        // Overload signatures and ambient `declare function`s
+        let signatures = NodeHelper::find_all(root, "function_signature");
+        for sig in &signatures {
+            if self.is_inside_class(*sig) || self.is_inside_function(*sig) {
+                continue;
+            }
+            if let Some(spec) = self.extract_func_spec(*sig, source) {
+                functions.push((sig.start_byte(), spec));
+            }
+        }
 
         // Regular function declarations
         for func_decl in NodeHelper::find_all(root, "function_declaration") {
-            if self.is_inside_class(func_decl) {
+            if self.is_inside_class(func_decl) || self.is_inside_function(func_decl) {
+                continue;
+            }
+            // The implementation of an overloaded function is not part of its API
+            if signatures.iter().any(|sig| self.is_overload_of(*sig, func_decl, source)) {
                 continue;
             }
             if let Some(spec) = self.extract_func_spec(func_decl, source) {
-                functions.push(spec);
+                functions.push((func_decl.start_byte(), spec));
             }
         }
 
-        // Arrow functions assigned to const/let
-        for var_decl in NodeHelper::find_all(root, "lexical_declaration") {
-            if self.is_inside_class(var_decl) {
-                continue;
-            }
-            for declarator in NodeHelper::children_by_kind(var_decl, "variable_declarator") {
-                let value = NodeHelper::field(declarator, "value");
-                if let Some(v) = value {
-                    if v.kind() == "arrow_function" {
+        // Arrow functions and function expressions assigned to const/let/var
+        for kind in ["lexical_declaration", "variable_declaration"] {
+            for var_decl in NodeHelper::find_all(root, kind) {
+                if self.is_inside_class(var_decl) || self.is_inside_function(var_decl) {
+                    continue;
+                }
+                for declarator in NodeHelper::children_by_kind(var_decl, "variable_declarator") {
+                    let value = NodeHelper::field(declarator, "value");
+                    if let Some(v) = value.filter(|v| self.is_function_value(*v)) {
                         if let Some(spec) = self.extract_arrow_func(declarator, v, source) {
-                            functions.push(spec);
+                            functions.push((var_decl.start_byte(), spec));
                         }
                     }
                 }
             }
         }
 
-        functions
+        functions.extend(self.extract_commonjs_funcs(root, source));
+
+        // Keep document order across the different declaration forms
+        functions.sort_by_key(|(pos, _)| *pos);
+        functions.into_iter().map(|(_, spec)| spec).collect()
     }
 
     fn is_inside_class(&self, node: Node) -> bool {
         let mut current = node.parent();
         while let Some(parent) = current {
-            if parent.kind() == "class_declaration" || parent.kind() == "class" {
+            if matches!(parent.kind(), "class_declaration" | "abstract_class_declaration" | "class") {
                 return true;
             }
             current = parent.parent();
@@ -377,9 +667,33 @@ impl TypeScriptPlugin {
         false
     }
 
+    fn is_inside_function(&self, node: Node) -> bool {
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if FUNCTION_KINDS.contains(&parent.kind()) {
+                return true;
+            }
+            current = parent.parent();
+        }
+        false
+    }
+
+    fn is_function_value(&self, node: Node) -> bool {
+        node.kind() == "arrow_function" || node.kind() == "function_expression"
+    }
+
+    /// Check if `sig` is an overload signature of the function or method implemented by
+    /// `func_decl`.
+    fn is_overload_of(&self, sig: Node, func_decl: Node, source: &str) -> bool {
+        let same_scope = self.outer_node(sig).parent() == self.outer_node(func_decl).parent();
+        let sig_name = NodeHelper::field_text(sig, "name", source);
+        same_scope && sig_name == NodeHelper::field_text(func_decl, "name", source)
+    }
+
     fn extract_func_spec(&self, node: Node, source: &str) -> Option<FuncSpec> {
         let name = NodeHelper::field(node, "name")
             .map(|n| NodeHelper::text(n, source))?;
+        let name = self.qualified_name(node, name, source);
 
         let params = NodeHelper::field(node, "parameters")
             .map(|n| NodeHelper::text(n, source))
@@ -394,11 +708,12 @@ impl TypeScriptPlugin {
             .unwrap_or_default();
 
         let signature = format!("function {}{}{}{}", name, type_params, params, return_type);
-        let doc = NodeHelper::preceding_comment(node, source);
+        let doc = self.doc_comment(node, source);
 
         Some(FuncSpec {
-            signature,
+            signature: self.annotate_jsdoc(node, &signature, source),
             doc,
+            export: self.export_status(node),
             ..Default::default()
         })
     }
@@ -406,9 +721,64 @@ impl TypeScriptPlugin {
     fn extract_arrow_func(&self, declarator: Node, arrow: Node, source: &str) -> Option<FuncSpec> {
         let name = NodeHelper::field(declarator, "name")
             .map(|n| NodeHelper::text(n, source))?;
+        let name = self.qualified_name(declarator, name, source);
+        let decl = declarator.parent().unwrap_or(declarator);
+        let keyword = decl.child(0).map(|n| NodeHelper::text(n, source)).unwrap_or("const");
+
+        let signature = format!("{} {} = {}", keyword, name, self.function_value_signature(arrow, source));
+        let doc = self.doc_comment(decl, source);
 
-        let params = NodeHelper::field(arrow, "parameters")
-            .or_else(|| NodeHelper::field(arrow, "parameter"))
+        Some(FuncSpec {
+            signature: self.annotate_jsdoc(decl, &signature, source),
+            doc,
+            export: self.export_status(decl),
+            ..Default::default()
+        })
+    }
+
+    /// Functions assigned to CommonJS exports (`exports.foo = function () {}`).
+    fn extract_commonjs_funcs(&self, root: Node, source: &str) -> Vec<(usize, FuncSpec)> {
+        let mut functions = Vec::new();
+
+        for stmt in NodeHelper::children_by_kind(root, "expression_statement") {
+            let assign = match NodeHelper::child_by_kind(stmt, "assignment_expression") {
+                Some(a) => a,
+                None => continue,
+            };
+            let target = NodeHelper::field_text(assign, "left", source).unwrap_or("");
+            let value = NodeHelper::field(assign, "right").filter(|v| self.is_function_value(*v));
+
+            let export = if target == "module.exports" {
+                "default"
+            } else if target.starts_with("exports.") || target.starts_with("module.exports.") {
+                "named"
+            } else {
+                continue;
+            };
+
+            if let Some(value) = value {
+                let signature = format!("{} = {}", target, self.function_value_signature(value, source));
+                functions.push((
+                    stmt.start_byte(),
+                    FuncSpec {
+                        signature: self.annotate_jsdoc(stmt, &signature, source),
+                        doc: self.doc_comment(stmt, source),
+                        export: Some(export.to_string()),
+                        ..Default::default()
+                    },
+                ));
+            }
+        }
+
+        functions
+    }
+
+    /// Parameters and return type of an arrow function or function expression.
+    fn function_value_signature(&self, value: Node, source: &str) -> String {
+        let function_keyword = if value.kind() == "function_expression" { "function" } else { "" };
+
+        let params = NodeHelper::field(value, "parameters")
+            .or_else(|| NodeHelper::field(value, "parameter"))
             .map(|n| {
                 let text = NodeHelper::text(n, source);
                 if text.starts_with('(') {
@@ -419,25 +789,32 @@ impl TypeScriptPlugin {
             })
             .unwrap_or_else(|| "()".to_string());
 
-        let return_type = NodeHelper::child_by_kind(arrow, "type_annotation")
-            .map(|n| NodeHelper::text(n, source).to_string())
+        let return_type = NodeHelper::child_by_kind(value, "type_annotation")
+            .map(|n| NodeHelper::text(n, source))
             .unwrap_or_default();
 
-        let signature = format!("const {} = {}{}", name, params, return_type);
-        let doc = NodeHelper::preceding_comment(declarator.parent().unwrap_or(declarator), source);
+        format!("{}{}{}", function_keyword, params, return_type)
+    }
 
-        Some(FuncSpec {
-            signature,
-            doc,
-            ..Default::default()
-        })
+    /// Fill in untyped parameters and return types from the JSDoc comment of a declaration.
+    fn annotate_jsdoc(&self, node: Node, signature: &str, source: &str) -> String {
+        match self.doc_comment(node, source) {
+            Some(doc) => JsDoc::parse(&doc).annotate(signature),
+            None => signature.to_string(),
+        }
+    }
+
+    /// Type of a declaration from its JSDoc `@type` tag.
+    fn jsdoc_type(&self, node: Node, source: &str) -> Option<String> {
+        let doc = self.doc_comment(node, source)?;
+        JsDoc::parse(&doc).type_name().map(|t| t.to_string())
     }
 
     fn extract_constants(&self, root: Node, source: &str) -> Vec<ConstSpec> {
         let mut constants = Vec::new();
 
         for var_decl in NodeHelper::find_all(root, "lexical_declaration") {
-            if self.is_inside_class(var_decl) {
+            if self.is_inside_class(var_decl) || self.is_inside_function(var_decl) {
                 continue;
             }
 
@@ -449,21 +826,23 @@ impl TypeScriptPlugin {
 
             for declarator in NodeHelper::children_by_kind(var_decl, "variable_declarator") {
                 let name = NodeHelper::field(declarator, "name")
-                    .map(|n| NodeHelper::text(n, source).to_string());
+                    .map(|n| NodeHelper::text(n, source))
+                    .map(|n| self.qualified_name(declarator, n, source));
 
                 let value = NodeHelper::field(declarator, "value");
 
-                // Skip if it's an arrow function
-                if value.map(|v| v.kind() == "arrow_function").unwrap_or(false) {
+                // Skip if it's an arrow function or function expression
+                if value.map(|v| self.is_function_value(v)).unwrap_or(false) {
                     continue;
                 }
 
                 let type_name = NodeHelper::child_by_kind(declarator, "type_annotation")
-                    .map(|n| NodeHelper::text(n, source).to_string());
+                    .map(|n| NodeHelper::text(n, source).to_string())
+                    .or_else(|| self.jsdoc_type(var_decl, source));
 
                 let value_str = value.map(|v| NodeHelper::text(v, source).to_string());
 
-                let doc = NodeHelper::preceding_comment(var_decl, source);
+                let doc = self.doc_comment(var_decl, source);
 
                 if let Some(name) = name {
                     constants.push(ConstSpec {
@@ -471,6 +850,8 @@ impl TypeScriptPlugin {
                         type_name,
                         value: value_str,
                         doc,
+                        export: self.export_status(var_decl),
+                        visibility: None,
                     });
                 }
             }
@@ -482,38 +863,422 @@ impl TypeScriptPlugin {
     fn extract_variables(&self, root: Node, source: &str) -> Vec<VarSpec> {
         let mut variables = Vec::new();
 
-        for var_decl in NodeHelper::find_all(root, "lexical_declaration") {
-            if self.is_inside_class(var_decl) {
+        for kind in ["lexical_declaration", "variable_declaration"] {
+            for var_decl in NodeHelper::find_all(root, kind) {
+                if self.is_inside_class(var_decl) || self.is_inside_function(var_decl) {
+                    continue;
+                }
+
+                // Check if it's a let or var declaration
+                let keyword = var_decl.child(0).map(|n| NodeHelper::text(n, source));
+                if keyword != Some("let") && keyword != Some("var") {
+                    continue;
+                }
+
+                for declarator in NodeHelper::children_by_kind(var_decl, "variable_declarator") {
+                    // Function-valued variables are reported as functions
+                    let value = NodeHelper::field(declarator, "value");
+                    if value.map(|v| self.is_function_value(v)).unwrap_or(false) {
+                        continue;
+                    }
+
+                    let name = NodeHelper::field(declarator, "name")
+                        .map(|n| NodeHelper::text(n, source))
+                        .map(|n| self.qualified_name(declarator, n, source));
+
+                    let type_name = NodeHelper::child_by_kind(declarator, "type_annotation")
+                        .map(|n| NodeHelper::text(n, source).to_string())
+                        .or_else(|| self.jsdoc_type(var_decl, source));
+
+                    let doc = self.doc_comment(var_decl, source);
+
+                    if let Some(name) = name {
+                        variables.push(VarSpec {
+                            name,
+                            type_name,
+                            doc,
+                            export: self.export_status(var_decl),
+                            visibility: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        variables
+    }
+
+    fn extract_exports(&self, root: Node, source: &str) -> Vec<ExportSpec> {
+        let mut exports = Vec::new();
+
+        // CommonJS: module.exports = ... / exports.foo = ...
+        for stmt in NodeHelper::children_by_kind(root, "expression_statement") {
+            if let Some(assign) = NodeHelper::child_by_kind(stmt, "assignment_expression") {
+                exports.extend(self.commonjs_exports(assign, source));
+            }
+        }
+
+        for export_stmt in NodeHelper::children_by_kind(root, "export_statement") {
+            let is_default = NodeHelper::has_child_kind(export_stmt, "default");
+            let from = NodeHelper::field(export_stmt, "source")
+                .map(|n| self.unquote(NodeHelper::text(n, source)));
+
+            // export class Foo {} / export default function foo() {}
+            if let Some(decl) = NodeHelper::field(export_stmt, "declaration") {
+                for name in self.declared_names(decl, source) {
+                    exports.push(if is_default {
+                        ExportSpec {
+                            name: "default".to_string(),
+                            local: Some(name),
+                            from: None,
+                        }
+                    } else {
+                        ExportSpec {
+                            name,
+                            ..Default::default()
+                        }
+                    });
+                }
                 continue;
             }
 
-            // Check if it's a let declaration
-            let kind = var_decl.child(0).map(|n| NodeHelper::text(n, source));
-            if kind != Some("let") {
+            // export default expr / export = expr
+            if is_default || NodeHelper::has_child_kind(export_stmt, "=") {
+                let value = NodeHelper::field(export_stmt, "value")
+                    .or_else(|| NodeHelper::child_by_kind(export_stmt, "identifier"));
+                let local = value
+                    .filter(|v| v.kind() == "identifier")
+                    .map(|v| NodeHelper::text(v, source).to_string());
+                exports.push(ExportSpec {
+                    name: "default".to_string(),
+                    local,
+                    from: None,
+                });
                 continue;
             }
 
-            for declarator in NodeHelper::children_by_kind(var_decl, "variable_declarator") {
-                let name = NodeHelper::field(declarator, "name")
-                    .map(|n| NodeHelper::text(n, source).to_string());
+            // export { a, b as c } [from './x']
+            if let Some(clause) = NodeHelper::child_by_kind(export_stmt, "export_clause") {
+                for specifier in NodeHelper::children_by_kind(clause, "export_specifier") {
+                    let name = NodeHelper::field_text(specifier, "name", source).unwrap_or("");
+                    let alias = NodeHelper::field_text(specifier, "alias", source);
+                    exports.push(ExportSpec {
+                        name: alias.unwrap_or(name).to_string(),
+                        local: alias.map(|_| name.to_string()),
+                        from: from.clone(),
+                    });
+                }
+                continue;
+            }
 
-                let type_name = NodeHelper::child_by_kind(declarator, "type_annotation")
-                    .map(|n| NodeHelper::text(n, source).to_string());
+            // export * as ns from './x'
+            if let Some(ns) = NodeHelper::child_by_kind(export_stmt, "namespace_export") {
+                if let Some(ident) = NodeHelper::child_by_kind(ns, "identifier") {
+                    exports.push(ExportSpec {
+                        name: NodeHelper::text(ident, source).to_string(),
+                        local: Some("*".to_string()),
+                        from,
+                    });
+                }
+                continue;
+            }
 
-                let doc = NodeHelper::preceding_comment(var_decl, source);
+            // export * from './x'
+            if from.is_some() {
+                exports.push(ExportSpec {
+                    name: "*".to_string(),
+                    local: None,
+                    from,
+                });
+            }
+        }
 
-                if let Some(name) = name {
-                    variables.push(VarSpec {
-                        name,
-                        type_name,
-                        doc,
-                    });
+        exports
+    }
+
+    fn commonjs_exports(&self, assign: Node, source: &str) -> Vec<ExportSpec> {
+        let target = NodeHelper::field_text(assign, "left", source).unwrap_or("");
+        let value = NodeHelper::field(assign, "right");
+        let local_of = |node: Option<Node>| {
+            node.filter(|n| n.kind() == "identifier")
+                .map(|n| NodeHelper::text(n, source).to_string())
+        };
+
+        if target == "module.exports" {
+            // module.exports = { a, b: c, d() {} }
+            if let Some(object) = value.filter(|v| v.kind() == "object") {
+                let mut exports = Vec::new();
+                let mut cursor = object.walk();
+                for entry in object.named_children(&mut cursor) {
+                    let (name, local) = match entry.kind() {
+                        "shorthand_property_identifier" => {
+                            (NodeHelper::text(entry, source).to_string(), None)
+                        }
+                        "pair" => (
+                            NodeHelper::field_text(entry, "key", source).unwrap_or("").to_string(),
+                            local_of(NodeHelper::field(entry, "value")),
+                        ),
+                        "method_definition" => (
+                            NodeHelper::field_text(entry, "name", source).unwrap_or("").to_string(),
+                            None,
+                        ),
+                        _ => continue,
+                    };
+                    let name = self.unquote(&name);
+                    let local = local.filter(|l| *l != name);
+                    exports.push(ExportSpec { name, local, from: None });
                 }
+                return exports;
             }
+
+            return vec![ExportSpec {
+                name: "default".to_string(),
+                local: local_of(value),
+                from: None,
+            }];
         }
 
-        variables
+        let name = target
+            .strip_prefix("module.exports.")
+            .or_else(|| target.strip_prefix("exports."));
+        match name {
+            Some(name) => {
+                let local = local_of(value).filter(|l| l != name);
+                vec![ExportSpec {
+                    name: name.to_string(),
+                    local,
+                    from: None,
+                }]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Names introduced by an exported declaration.
+    fn declared_names(&self, decl: Node, source: &str) -> Vec<String> {
+        // export declare ...
+        if decl.kind() == "ambient_declaration" {
+            return decl
+                .named_child(0)
+                .map(|inner| self.declared_names(inner, source))
+                .unwrap_or_default();
+        }
+
+        if decl.kind() == "lexical_declaration" || decl.kind() == "variable_declaration" {
+            NodeHelper::children_by_kind(decl, "variable_declarator")
+                .into_iter()
+                .filter_map(|d| NodeHelper::field_text(d, "name", source))
+                .map(|n| n.to_string())
+                .collect()
+        } else {
+            NodeHelper::field_text(decl, "name", source)
+                .map(|n| vec![self.unquote(n)])
+                .unwrap_or_default()
+        }
+    }
+
+    /// Export status of a declaration wrapped in an `export` statement.
+    fn export_status(&self, node: Node) -> Option<String> {
+        let mut export_stmt = None;
+        let mut current = node;
+        while let Some(parent) = current.parent().filter(|p| self.is_wrapper(*p)) {
+            if parent.kind() == "export_statement" {
+                export_stmt = Some(parent);
+            }
+            current = parent;
+        }
+
+        let export_stmt = export_stmt?;
+
+        // An export inside a namespace reaches the module only through exported namespaces
+        let mut ancestor = current.parent();
+        while let Some(parent) = ancestor {
+            if parent.kind() == "internal_module" {
+                self.export_status(parent)?;
+                break;
+            }
+            ancestor = parent.parent();
+        }
+
+        if NodeHelper::has_child_kind(export_stmt, "default") {
+            Some("default".to_string())
+        } else {
+            Some("named".to_string())
+        }
+    }
+
+    /// Mark items exported through a separate `export { ... }` or `export default name`.
+    fn apply_local_exports(&self, spec: &mut FileSpec) {
+        let mut statuses: HashMap<String, &str> = HashMap::new();
+        for export in spec.exports.iter().filter(|e| e.from.is_none()) {
+            let local = export.local.clone().unwrap_or_else(|| export.name.clone());
+            let status = if export.name == "default" { "default" } else { "named" };
+            statuses.entry(local).or_insert(status);
+        }
+
+        let status_of = |name: &str| statuses.get(name).map(|s| s.to_string());
+
+        for t in spec.types.iter_mut().filter(|t| t.export.is_none()) {
+            t.export = status_of(t.name.split_whitespace().next().unwrap_or(""));
+        }
+        for f in spec.functions.iter_mut().filter(|f| f.export.is_none()) {
+            f.export = status_of(function_name(&f.signature));
+        }
+        for c in spec.constants.iter_mut().filter(|c| c.export.is_none()) {
+            c.export = status_of(&c.name);
+        }
+        for v in spec.variables.iter_mut().filter(|v| v.export.is_none()) {
+            v.export = status_of(&v.name);
+        }
+    }
+
+    /// Doc comment for a declaration, looking past `export`/`declare` wrappers.
+    fn doc_comment(&self, node: Node, source: &str) -> Option<String> {
+        NodeHelper::preceding_comment(self.outer_node(node), source)
+    }
+
+    fn is_wrapper(&self, node: Node) -> bool {
+        DECLARATION_WRAPPERS.contains(&node.kind())
+    }
+
+    /// The outermost statement wrapping a declaration (`export`, `declare`, ...).
+    fn outer_node<'a>(&self, node: Node<'a>) -> Node<'a> {
+        let mut current = node;
+        while let Some(parent) = current.parent().filter(|p| self.is_wrapper(*p)) {
+            current = parent;
+        }
+        current
+    }
+
+    /// Prefix a name with the namespaces/modules enclosing its declaration.
+    fn qualified_name(&self, node: Node, name: &str, source: &str) -> String {
+        let mut parts = vec![name.to_string()];
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if parent.kind() == "internal_module" || parent.kind() == "module" {
+                if let Some(n) = NodeHelper::field(parent, "name") {
+                    parts.push(self.unquote(NodeHelper::text(n, source)));
+                }
+            }
+            current = parent.parent();
+        }
+        parts.reverse();
+        parts.join(".")
+    }
+
+    fn unquote(&self, text: &str) -> String {
+        text.trim_matches('"').trim_matches('\'').to_string()
     }
+
+    /// Public surface of a module, with `export *` re-exports expanded.
+    fn resolved_exports(
+        &self,
+        idx: usize,
+        specs: &[FileSpec],
+        resolver: &ModuleResolver,
+        visiting: &mut HashSet<usize>,
+    ) -> Vec<ExportSpec> {
+        visiting.insert(idx);
+
+        // Explicit exports shadow names brought in through `export *`
+        let explicit: HashSet<&str> = specs[idx]
+            .exports
+            .iter()
+            .filter(|e| e.name != "*")
+            .map(|e| e.name.as_str())
+            .collect();
+
+        let mut resolved: Vec<ExportSpec> = Vec::new();
+
+        for export in &specs[idx].exports {
+            let target = export
+                .from
+                .as_deref()
+                .filter(|_| export.name == "*")
+                .and_then(|from| resolver.resolve(&specs[idx].file, from))
+                .filter(|target| !visiting.contains(target));
+
+            match target {
+                Some(target) => {
+                    // `export *` never re-exports the default export
+                    for inner in self.resolved_exports(target, specs, resolver, visiting) {
+                        let shadowed = explicit.contains(inner.name.as_str());
+                        if inner.name == "default" || inner.name == "*" || shadowed {
+                            continue;
+                        }
+                        if !resolved.iter().any(|e| e.name == inner.name) {
+                            resolved.push(ExportSpec {
+                                name: inner.name,
+                                local: None,
+                                from: export.from.clone(),
+                            });
+                        }
+                    }
+                }
+                None => resolved.push(export.clone()),
+            }
+        }
+
+        visiting.remove(&idx);
+        resolved
+    }
+
+    /// Locate the interface or class `name` as seen from file `idx`: declared in the
+    /// file itself or exported by one of its resolved imports (following re-exports).
+    fn find_type(
+        &self,
+        idx: usize,
+        name: &str,
+        specs: &[FileSpec],
+        resolver: &ModuleResolver,
+        depth: usize,
+    ) -> Option<(usize, usize)> {
+        let declared = specs[idx].types.iter().position(|t| {
+            matches!(t.kind.as_str(), "interface" | "class" | "abstract_class")
+                && t.name.rsplit_once(' ').map(|(n, _)| n) == Some(name)
+        });
+        if declared.is_some() || depth > 8 {
+            return declared.map(|ty| (idx, ty));
+        }
+
+        // Re-exports of the name from another module
+        for export in &specs[idx].exports {
+            let from = match &export.from {
+                Some(from) if export.name == name => from,
+                _ => continue,
+            };
+            // Packages are outside the tree, but a later re-export may be local
+            let Some(target) = resolver.resolve(&specs[idx].file, from) else { continue };
+            let local = export.local.as_deref().unwrap_or(name);
+            if let Some(found) = self.find_type(target, local, specs, resolver, depth + 1) {
+                return Some(found);
+            }
+        }
+
+        if depth > 0 {
+            return None;
+        }
+
+        specs[idx].imports.iter().find_map(|import| {
+            let target = resolver.resolve(&specs[idx].file, import)?;
+            let exported = specs[target].exports.iter().any(|e| e.name == name);
+            if !exported {
+                return None;
+            }
+            self.find_type(target, name, specs, resolver, depth + 1)
+        })
+    }
+}
+
+/// Name of a function from its extracted signature (`function foo(...)` / `const foo = ...`).
+fn function_name(signature: &str) -> &str {
+    let rest = ["function ", "const ", "let ", "var "]
+        .iter()
+        .find_map(|prefix| signature.strip_prefix(prefix))
+        .unwrap_or(signature);
+    rest.split(['<', '(', ' ', '='])
+        .next()
+        .unwrap_or(rest)
 }
 
 impl Default for TypeScriptPlugin {
@@ -528,29 +1293,93 @@ impl LanguagePlugin for TypeScriptPlugin {
     }
 
     fn extensions(&self) -> &[&'static str] {
-        &["ts", "tsx"]
+        TS_EXTENSIONS
     }
 
     fn extract(&self, source: &str, path: &Path) -> Result<FileSpec> {
-        let language = self.get_language(path);
-        let mut parser = TreeSitterParser::new(language)?;
-        let tree = parser.parse(source)?;
-        let root = tree.root_node();
+        self.extract_with_language(source, path, self.get_language(path))
+    }
 
-        Ok(FileSpec {
-            file: path.to_string_lossy().to_string(),
-            package: self.extract_module(path),
-            imports: self.extract_imports(root, source),
-            types: self.extract_types(root, source),
-            functions: self.extract_functions(root, source),
-            methods: Vec::new(), // TS methods are included in class types
-            constants: self.extract_constants(root, source),
-            variables: self.extract_variables(root, source),
-            errors: Vec::new(),
-        })
+    // Also covers JavaScript files, since TS and JS modules can re-export each other
+    fn link(&self, specs: &mut Vec<FileSpec>) -> Result<()> {
+        let files: HashMap<PathBuf, usize> = specs
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| is_module_file(Path::new(&s.file)))
+            .map(|(i, s)| (normalize_path(Path::new(&s.file)), i))
+            .collect();
+        let resolver = ModuleResolver::new(&files);
+
+        let mut indices: Vec<usize> = files.values().copied().collect();
+        indices.sort();
+
+        // Map import specifiers to the files they land on
+        for &idx in &indices {
+            let resolved: BTreeMap<String, String> = specs[idx]
+                .imports
+                .iter()
+                .filter_map(|import| {
+                    let target = resolver.resolve(&specs[idx].file, import)?;
+                    Some((import.clone(), specs[target].file.clone()))
+                })
+                .collect();
+            specs[idx].resolved_imports = resolved;
+        }
+
+        // Expand barrel re-exports so each module lists its full public surface
+        let mut resolved = Vec::new();
+        for &idx in &indices {
+            if specs[idx].exports.iter().any(|e| e.name == "*") {
+                let exports = self.resolved_exports(idx, specs, &resolver, &mut HashSet::new());
+                resolved.push((idx, exports));
+            }
+        }
+
+        for (idx, exports) in resolved {
+            specs[idx].exports = exports;
+        }
+
+        // Record implementors on the interfaces they implement, across files
+        let mut links = Vec::new();
+        for &idx in &indices {
+            for ty in &specs[idx].types {
+                let implemented = ty.implements.as_deref().unwrap_or("");
+                for name in implemented.split(',').map(base_type_name).filter(|n| !n.is_empty()) {
+                    if let Some(target) = self.find_type(idx, name, specs, &resolver, 0) {
+                        links.push((target, ty.name.clone()));
+                    }
+                }
+            }
+        }
+
+        for ((file, ty), implementor) in links {
+            let implemented_by = &mut specs[file].types[ty].implemented_by;
+            if !implemented_by.contains(&implementor) {
+                implemented_by.push(implementor);
+            }
+        }
+
+        Ok(())
     }
 }
 
+/// Name of a referenced type without generic arguments (`Repo<User>` -> `Repo`).
+fn base_type_name(reference: &str) -> &str {
+    reference.split('<').next().unwrap_or("").trim()
+}
+
+/// Whether a path is a TypeScript or JavaScript module.
+fn is_module_file(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    TS_EXTENSIONS.contains(&ext) || JS_EXTENSIONS.contains(&ext)
+}
+
+/// Whether `path` is a declaration file (`.d.ts`, `.d.mts`, `.d.cts`).
+fn is_declaration_file(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    [".d.ts", ".d.mts", ".d.cts"].iter().any(|ext| name.ends_with(ext))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -647,4 +1476,313 @@ enum Status {
         assert_eq!(spec.types.len(), 1);
         assert_eq!(spec.types[0].name, "Status enum");
     }
+
+    #[test]
+    fn test_extract_abstract_class() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+export abstract class Shape {
+    abstract area(): number;
+    move(x: number): void;
+    move(x: number, y?: number): void {}
+}
+"#;
+        let spec = plugin.extract(source, Path::new("shape.ts")).unwrap();
+        assert_eq!(spec.types.len(), 1);
+        assert_eq!(spec.types[0].name, "Shape class");
+        assert_eq!(spec.types[0].kind, "abstract_class");
+        assert_eq!(
+            spec.types[0].methods,
+            vec!["abstract area(): number", "move(x: number): void"]
+        );
+    }
+
+    #[test]
+    fn test_extract_namespaces() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+namespace Geo {
+    export namespace Inner {
+        export function dist(a: number): number { return a; }
+    }
+    export class Point {}
+    export const ORIGIN = 0;
+}
+export namespace Api {
+    export namespace V1 { export class Client {} }
+    namespace Hidden { export class Secret {} }
+}
+"#;
+        let spec = plugin.extract(source, Path::new("geo.ts")).unwrap();
+        let names: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert!(names.contains(&"Geo.Point class"));
+        assert!(names.contains(&"Geo namespace"));
+        assert!(names.contains(&"Geo.Inner namespace"));
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(spec.functions[0].signature, "function Geo.Inner.dist(a: number): number");
+        assert_eq!(spec.constants[0].name, "Geo.ORIGIN");
+
+        // Exports count only when every enclosing namespace is exported too
+        let export = |name: &str| {
+            let ty = spec.types.iter().find(|t| t.name == name).unwrap();
+            ty.export.as_deref()
+        };
+        assert_eq!(export("Geo.Point class"), None);
+        assert_eq!(export("Geo.Inner namespace"), None);
+        assert_eq!(spec.functions[0].export, None);
+        assert_eq!(spec.constants[0].export, None);
+        assert_eq!(export("Api.V1.Client class"), Some("named"));
+        assert_eq!(export("Api.Hidden namespace"), None);
+        assert_eq!(export("Api.Hidden.Secret class"), None);
+    }
+
+    #[test]
+    fn test_extract_ambient_declarations() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+declare module "express" {
+    interface Request { user: string }
+}
+// Greets a user.
+declare function greet(name: string): void;
+declare const VERSION: string;
+"#;
+        let spec = plugin.extract(source, Path::new("globals.d.ts")).unwrap();
+        let names: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert!(names.contains(&"express.Request interface"));
+        assert!(names.contains(&"express module"));
+        assert_eq!(spec.functions.len(), 1);
+        assert!(spec.functions[0].doc.as_ref().unwrap().contains("Greets"));
+        assert_eq!(spec.constants[0].name, "VERSION");
+        assert_eq!(spec.package, "globals");
+        assert!(spec.declaration);
+        assert!(spec.functions[0].declaration);
+
+        let spec = plugin.extract("function greet(name: string): void {}\n", Path::new("greet.ts"));
+        let spec = spec.unwrap();
+        assert!(!spec.declaration);
+        assert!(!spec.functions[0].declaration);
+    }
+
+    #[test]
+    fn test_extract_overloads() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+export function parse(input: string): Node;
+export function parse(input: Buffer): Node;
+export function parse(input: any): Node {
+    const local = () => 1;
+    return build(input);
+}
+"#;
+        let spec = plugin.extract(source, Path::new("parse.ts")).unwrap();
+        assert_eq!(spec.functions.len(), 2);
+        assert_eq!(spec.functions[0].signature, "function parse(input: string): Node");
+        assert_eq!(spec.functions[1].signature, "function parse(input: Buffer): Node");
+        assert!(spec.constants.is_empty());
+
+        let source = r#"
+class Parser {
+    parse(input: string): Node;
+    parse(input: Buffer): Node;
+    @Trace()
+    parse(input: any): Node {
+        return build(input);
+    }
+    reset(): void {}
+}
+"#;
+        let spec = plugin.extract(source, Path::new("parser.ts")).unwrap();
+        assert_eq!(
+            spec.types[0].methods,
+            vec!["parse(input: string): Node", "parse(input: Buffer): Node", "reset(): void"]
+        );
+        assert_eq!(spec.types[0].attributes[0].name, "Trace");
+    }
+
+    #[test]
+    fn test_extract_function_expressions() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+const handler = function (req: Request): Response { return ok(); };
+var legacy = (x: string) => x;
+var counter = 0;
+"#;
+        let spec = plugin.extract(source, Path::new("handlers.ts")).unwrap();
+        assert_eq!(spec.functions.len(), 2);
+        assert_eq!(spec.functions[0].signature, "const handler = function(req: Request): Response");
+        assert_eq!(spec.functions[1].signature, "var legacy = (x: string)");
+        assert_eq!(spec.variables.len(), 1);
+        assert_eq!(spec.variables[0].name, "counter");
+    }
+
+    #[test]
+    fn test_export_status() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+/** A user in the system. */
+export interface User {
+    id: number;
+}
+
+export default class Service {}
+
+function helper(): void {}
+const internal = 1;
+const LIMIT = 10;
+
+export { LIMIT as MAX_LIMIT };
+"#;
+        let spec = plugin.extract(source, Path::new("user.ts")).unwrap();
+
+        assert_eq!(spec.types[0].export.as_deref(), Some("named"));
+        assert!(spec.types[0].doc.as_ref().unwrap().contains("user in the system"));
+        assert_eq!(spec.types[1].export.as_deref(), Some("default"));
+        assert!(spec.functions[0].export.is_none());
+        assert!(spec.constants[0].export.is_none());
+        assert_eq!(spec.constants[1].export.as_deref(), Some("named"));
+
+        let names: Vec<&str> = spec.exports.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "default", "MAX_LIMIT"]);
+        assert_eq!(spec.exports[2].local.as_deref(), Some("LIMIT"));
+    }
+
+    #[test]
+    fn test_extract_reexports() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+export * from './user';
+export * as models from './models';
+export { create as createUser, default as Client } from './client';
+"#;
+        let spec = plugin.extract(source, Path::new("index.ts")).unwrap();
+        assert_eq!(spec.exports.len(), 4);
+        assert_eq!(spec.exports[0].name, "*");
+        assert_eq!(spec.exports[0].from.as_deref(), Some("./user"));
+        assert_eq!(spec.exports[1].name, "models");
+        assert_eq!(spec.exports[2].name, "createUser");
+        assert_eq!(spec.exports[2].local.as_deref(), Some("create"));
+        assert_eq!(spec.exports[3].from.as_deref(), Some("./client"));
+    }
+
+    #[test]
+    fn test_extract_decorators() {
+        let plugin = TypeScriptPlugin::new();
+        let source = r#"
+@Controller('users')
+export class UsersController {
+    @Column({ nullable: true })
+    name: string;
+
+    constructor(@Inject(TOKEN) private readonly users: UsersService) {}
+
+    @Get(':id')
+    @HttpCode(200)
+    // Decorators keep applying across a comment
+    findOne(@Param('id') id: string): User {
+        return this.users.find(id);
+    }
+}
+"#;
+        let spec = plugin.extract(source, Path::new("users.controller.ts")).unwrap();
+        let class = &spec.types[0];
+        assert_eq!(class.fields, vec!["name: string"]);
+        assert_eq!(
+            class.methods,
+            vec!["constructor(private readonly users: UsersService)", "findOne(id: string): User"]
+        );
+
+        let attrs: Vec<(&str, &str, Option<&str>)> = class
+            .attributes
+            .iter()
+            .map(|a| (a.name.as_str(), a.target.as_str(), a.member.as_deref()))
+            .collect();
+        assert_eq!(
+            attrs,
+            vec![
+                ("Controller", "class", None),
+                ("Column", "property", Some("name")),
+                ("Inject", "parameter", Some("constructor.users")),
+                ("Get", "method", Some("findOne")),
+                ("HttpCode", "method", Some("findOne")),
+                ("Param", "parameter", Some("findOne.id")),
+            ]
+        );
+        assert_eq!(class.attributes[0].args, vec!["users"]);
+        assert_eq!(class.attributes[1].args, vec!["{ nullable: true }"]);
+        assert_eq!(class.attributes[4].args, vec!["200"]);
+    }
+
+    #[test]
+    fn test_link_resolves_barrels() {
+        let plugin = TypeScriptPlugin::new();
+        let files = [
+            ("src/index.ts", "export * from './users';\nexport * from 'external';"),
+            ("src/users/index.ts", "export * from './model';\nexport const VERSION = 1;"),
+            ("src/users/model.ts", "export interface User {}\nexport default class Repo {}"),
+        ];
+        let mut specs: Vec<FileSpec> = files
+            .iter()
+            .map(|(path, source)| plugin.extract(source, Path::new(path)).unwrap())
+            .collect();
+        plugin.link(&mut specs).unwrap();
+
+        let names: Vec<&str> = specs[0].exports.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["User", "VERSION", "*"]);
+        assert_eq!(specs[0].exports[0].from.as_deref(), Some("./users"));
+        assert_eq!(specs[0].exports[2].from.as_deref(), Some("external"));
+    }
+
+    #[test]
+    fn test_link_finds_types_past_package_reexports() {
+        let plugin = TypeScriptPlugin::new();
+        let files = [
+            (
+                "src/index.ts",
+                "export { Store } from '@acme/store';\nexport { Store } from './store';",
+            ),
+            ("src/store.ts", "export interface Store {}"),
+            (
+                "src/repo.ts",
+                "import { Store } from './index';\nexport class Repo implements Store {}",
+            ),
+        ];
+        let mut specs: Vec<FileSpec> = files
+            .iter()
+            .map(|(path, source)| plugin.extract(source, Path::new(path)).unwrap())
+            .collect();
+        plugin.link(&mut specs).unwrap();
+
+        assert_eq!(specs[1].types[0].implemented_by, vec!["Repo class"]);
+    }
+
+    #[test]
+    fn test_link_resolves_across_packages() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "compilerOptions": { "paths": { "@core/*": ["packages/core/src/*"] } } }"#,
+        )
+        .unwrap();
+
+        let plugin = TypeScriptPlugin::new();
+        let files = [
+            ("packages/core/src/index.ts", "export * from './store';"),
+            ("packages/core/src/store.d.ts", "export interface Store<T> { get(id: string): T; }"),
+            (
+                "apps/web/src/users.ts",
+                "import { Store } from '@core/index';\nexport class UserStore implements Store<User> {}",
+            ),
+        ];
+        let mut specs: Vec<FileSpec> = files
+            .iter()
+            .map(|(path, source)| plugin.extract(source, &root.join(path)).unwrap())
+            .collect();
+        plugin.link(&mut specs).unwrap();
+
+        assert_eq!(specs[1].package, "store");
+        assert_eq!(specs[2].resolved_imports["@core/index"], specs[0].file);
+        assert_eq!(specs[1].types[0].implemented_by, vec!["UserStore class"]);
+    }
 }
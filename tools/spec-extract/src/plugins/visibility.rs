@@ -0,0 +1,247 @@
+use serde::Deserialize;
+
+use crate::spec::{FileSpec, FuncSpec};
+
+/// Which declarations end up in the spec.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Everything the plugins extract
+    #[default]
+    All,
+    /// Only the public API
+    Public,
+}
+
+/// Modifiers that keep a declaration out of the public API.
+const PRIVATE_MODIFIERS: &[&str] =
+    &["private", "protected", "internal", "fileprivate", "defp", "defmacrop"];
+
+/// Recorded visibilities (see `TypeSpec::visibility`) that are part of the public API.
+const PUBLIC_VISIBILITIES: &[&str] = &["pub", "public", "open"];
+
+/// Drop the declarations of `spec` that are not part of its public API.
+///
+/// This goes by what the spec records: the `visibility` of declarations,
+/// `private`-like modifiers in signatures, explicit exports for TypeScript and
+/// JavaScript, and naming conventions for Go (capitalized), Python and Dart (no
+/// leading underscore). Rust, Java, C# and Swift fields need `pub`, `public` or
+/// `open` to stay. Declarations whose visibility the spec does not record are kept.
+pub(super) fn retain_public(spec: &mut FileSpec, language: &str) {
+    let exports = matches!(language, "typescript" | "javascript");
+    let by_name = |name: &str| is_public_name(name, language);
+
+    spec.types.retain(|t| {
+        (!exports || t.export.is_some())
+            && is_public_visibility(&t.visibility)
+            && by_name(t.name.split(' ').next().unwrap_or_default())
+    });
+    for t in &mut spec.types {
+        t.methods.retain(|m| is_public_signature(m, language));
+        t.fields.retain(|f| is_public_field(f, &t.kind, language));
+    }
+    spec.functions.retain(|f| {
+        (!exports || f.export.is_some())
+            && is_public_function(f, language)
+            // `static` functions are local to their C or C++ translation unit
+            && !(matches!(language, "c" | "cpp") && f.signature.starts_with("static "))
+    });
+    spec.methods.retain(|m| is_public_function(m, language));
+    spec.constants.retain(|c| {
+        (!exports || c.export.is_some()) && is_public_visibility(&c.visibility) && by_name(&c.name)
+    });
+    spec.variables.retain(|v| {
+        (!exports || v.export.is_some()) && is_public_visibility(&v.visibility) && by_name(&v.name)
+    });
+    spec.errors.retain(|e| by_name(&e.name));
+}
+
+/// Declarations without a recorded visibility are left to the other checks.
+fn is_public_visibility(visibility: &Option<String>) -> bool {
+    visibility.as_deref().is_none_or(|v| PUBLIC_VISIBILITIES.contains(&v))
+}
+
+fn is_public_function(func: &FuncSpec, language: &str) -> bool {
+    if !is_public_visibility(&func.visibility) {
+        return false;
+    }
+    // Go methods carry their receiver in the signature: `func (s *Store) get()`
+    let signature = match &func.receiver {
+        Some(receiver) if language == "go" => func.signature.replacen(receiver.as_str(), "", 1),
+        _ => func.signature.clone(),
+    };
+    is_public_signature(&signature, language)
+}
+
+fn is_public_signature(signature: &str, language: &str) -> bool {
+    let head = signature.split('(').next().unwrap_or_default();
+    let head = head.split('[').next().unwrap_or_default();
+    let mut words: Vec<&str> = head.split_whitespace().collect();
+    let name = words.pop().unwrap_or_default();
+    let name = name.split('<').next().unwrap_or_default();
+    !words.iter().any(|w| PRIVATE_MODIFIERS.contains(w)) && is_public_name(name, language)
+}
+
+/// Fields render as `name: Type` or as a declaration (`private final Repo repo`).
+fn is_public_field(field: &str, kind: &str, language: &str) -> bool {
+    let words: Vec<&str> = field.split_whitespace().collect();
+    match language {
+        "rust" => return words.first() == Some(&"pub"),
+        // Record components have no modifiers and are public
+        "java" if kind == "record" && !words.iter().any(|w| matches!(*w, "static" | "private")) => {
+            return true
+        }
+        "java" => return words.contains(&"public"),
+        // Positional record parameters and interface members are public
+        "csharp" if matches!(kind, "record" | "interface") => {
+            return !words.iter().any(|w| PRIVATE_MODIFIERS.contains(w))
+        }
+        "csharp" => return words.contains(&"public"),
+        // Protocol requirements share the protocol's access level
+        "swift" if kind == "protocol" => return true,
+        "swift" => return matches!(words.first(), Some(&"public" | &"open")),
+        _ => {}
+    }
+    match field.split_once(':') {
+        Some((name, _)) if !name.trim().contains(' ') => is_public_name(name.trim(), language),
+        _ => is_public_signature(field.split('=').next().unwrap_or_default(), language),
+    }
+}
+
+fn is_public_name(name: &str, language: &str) -> bool {
+    // `Outer.CONSTANT`, `Self::method`
+    let name = name.rsplit(['.', ':']).next().unwrap_or(name);
+    match language {
+        "go" => !name.starts_with(|c: char| c.is_lowercase() || c == '_'),
+        "python" => !name.starts_with('_') || (name.starts_with("__") && name.ends_with("__")),
+        "dart" => !name.starts_with('_'),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::PluginRegistry;
+    use std::path::Path;
+
+    fn public(registry: &mut PluginRegistry, source: &str, path: &str) -> FileSpec {
+        registry.set_visibility(Visibility::Public);
+        registry.extract(source, Path::new(path)).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_retain_public_by_naming_convention() {
+        let mut registry = PluginRegistry::new();
+        let source = r#"package store
+
+type Store struct {
+	Name  string
+	cache map[string]string
+}
+
+type entry struct{}
+
+func New() *Store { return nil }
+func newEntry() entry { return entry{} }
+func (s *Store) Get(key string) string { return "" }
+func (s *Store) evict() {}
+
+const MaxSize = 10
+const minSize = 1
+"#;
+        let spec = public(&mut registry, source, "store.go");
+        let types: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(types, vec!["Store struct"]);
+        assert_eq!(spec.types[0].fields, vec!["Name: string"]);
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(spec.methods.len(), 1);
+        assert_eq!(spec.methods[0].signature, "func (s *Store) Get(key string) string");
+        assert_eq!(spec.constants.len(), 1);
+
+        let source = "class Repo:\n    def __init__(self): pass\n    def _load(self): pass\n\n\
+                      def _helper(): pass\n";
+        let spec = public(&mut registry, source, "repo.py");
+        assert_eq!(spec.types[0].methods, vec!["def __init__(self)"]);
+        assert!(spec.functions.is_empty());
+    }
+
+    #[test]
+    fn test_retain_public_by_modifier_and_export() {
+        let mut registry = PluginRegistry::new();
+        let source = "class Repo {\n  public void save() {}\n  private void load() {}\n}\n";
+        let spec = public(&mut registry, source, "Repo.java");
+        let methods: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(methods, vec!["public void save()"]);
+
+        let source = "export function save(): void {}\nfunction load(): void {}\n\
+                      export const LIMIT = 1;\nconst retries = 3;\n";
+        let spec = public(&mut registry, source, "repo.ts");
+        assert_eq!(spec.functions.len(), 1);
+        assert_eq!(spec.constants[0].name, "LIMIT");
+        assert_eq!(spec.constants.len(), 1);
+    }
+
+    #[test]
+    fn test_retain_public_by_recorded_visibility() {
+        let mut registry = PluginRegistry::new();
+        let source = r#"pub struct A { pub x: i32, y: i32 }
+struct B;
+pub(crate) struct C;
+pub fn f() {}
+fn g() {}
+impl A {
+    pub fn m(&self) {}
+    fn n(&self) {}
+}
+impl Drop for A {
+    fn drop(&mut self) {}
+}
+pub const MAX: i32 = 1;
+const MIN: i32 = 0;
+"#;
+        let spec = public(&mut registry, source, "lib.rs");
+        let types: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(types, vec!["A struct"]);
+        assert_eq!(spec.types[0].fields, vec!["pub x: i32"]);
+        let functions: Vec<&str> = spec.functions.iter().map(|f| f.signature.as_str()).collect();
+        assert_eq!(functions, vec!["fn f()"]);
+        let methods: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(methods, vec!["fn m(&self)", "fn drop(&mut self)"]);
+        let constants: Vec<&str> = spec.constants.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(constants, vec!["MAX"]);
+
+        let source = "public class Repo {\n  public int a;\n  int b;\n  public void save() {}\n\
+                      void load() {}\n}\nclass Helper {}\n";
+        let spec = public(&mut registry, source, "Repo.java");
+        assert_eq!(spec.types.len(), 1);
+        assert_eq!(spec.types[0].fields, vec!["public int a"]);
+        assert_eq!(spec.methods.len(), 1);
+
+        let source = "class Repo\n  def save; end\n  def load; end\n  def self.build; end\n\
+                      private :load\n  private_class_method :build\nend\n";
+        let spec = public(&mut registry, source, "repo.rb");
+        let methods: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(methods, vec!["def save"]);
+
+        let source = "public class Repo {\n  public int A { get; }\n  int b;\n\
+                      public void Save() {}\n  void Load() {}\n  class Entry {}\n}\n\
+                      class Helper {}\n";
+        let spec = public(&mut registry, source, "Repo.cs");
+        let types: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(types, vec!["Repo class"]);
+        assert_eq!(spec.types[0].fields, vec!["public int A { get; }"]);
+        let methods: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(methods, vec!["public void Save()"]);
+
+        let source = "public struct Repo {\n  public var a: Int\n  var b: Int\n\
+                      public func save() {}\n  func load() {}\n}\n\
+                      open class Base {}\nclass Helper {}\n";
+        let spec = public(&mut registry, source, "Repo.swift");
+        let types: Vec<&str> = spec.types.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(types, vec!["Repo struct", "Base class"]);
+        assert_eq!(spec.types[0].fields, vec!["public var a: Int"]);
+        let methods: Vec<&str> = spec.methods.iter().map(|m| m.signature.as_str()).collect();
+        assert_eq!(methods, vec!["public func save()"]);
+    }
+}
@@ -1,13 +1,16 @@
 use anyhow::{Context, Result};
+use serde::Deserialize;
 use std::fs;
 use std::path::Path;
 
 use super::types::{ExtractedSpec, FileSpec, IndexSpec};
 
 /// Output format for spec files.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     #[default]
+    #[serde(alias = "yml")]
     Yaml,
     Json,
 }
@@ -20,6 +23,7 @@ impl OutputFormat {
         }
     }
 
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(s: &str) -> Option<Self> {
         match s.to_lowercase().as_str() {
             "yaml" | "yml" => Some(OutputFormat::Yaml),
@@ -100,7 +104,11 @@ pub fn write_extracted_spec(spec: &ExtractedSpec, path: &Path, format: OutputFor
             .with_context(|| format!("Failed to create directory: {}", parent.display()))?;
     }
 
-    fs::write(path, content)
+    // Written aside and renamed, so readers never see half a spec
+    let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&temp, content)
+        .with_context(|| format!("Failed to write extracted spec to: {}", temp.display()))?;
+    fs::rename(&temp, path)
         .with_context(|| format!("Failed to write extracted spec to: {}", path.display()))?;
 
     Ok(())
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Represents a complete specification for a single source file.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -10,6 +11,12 @@ pub struct FileSpec {
     /// Import statements
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub imports: Vec<String>,
+    /// Import specifiers resolved to files of the extracted tree
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resolved_imports: BTreeMap<String, String>,
+    /// Exported names, including re-exports from other modules
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exports: Vec<ExportSpec>,
     /// Type definitions (structs, interfaces, classes, traits, enums)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub types: Vec<TypeSpec>,
@@ -28,6 +35,9 @@ pub struct FileSpec {
     /// Error definitions
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<ErrorSpec>,
+    /// Declarations only, without implementations (TypeScript `.d.ts`)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub declaration: bool,
 }
 
 /// Represents a type definition (struct, interface, class, trait, enum).
@@ -61,6 +71,31 @@ pub struct TypeSpec {
     /// Enum variants (for enums)
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub variants: Vec<String>,
+    /// Decorators/attributes on the type and its members
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<AttributeSpec>,
+    /// Export status: "named" or "default" (for languages with explicit exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+    /// Visibility where the language does not make declarations public by default:
+    /// the modifier as written (`pub(crate)`, `protected`) or the default (`private`, `package`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+}
+
+/// Represents a decorator or attribute attached to a declaration.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct AttributeSpec {
+    /// Attribute name as written (e.g., "Controller", "Get", "ns.Injectable")
+    pub name: String,
+    /// Arguments as written, with string literals unquoted
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// What the attribute is attached to: class, method, property, field, event, or parameter
+    pub target: String,
+    /// Member name for member attributes ("findOne", or "findOne.id" for parameters)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member: Option<String>,
 }
 
 /// Represents a function or method.
@@ -74,6 +109,12 @@ pub struct FuncSpec {
     /// Receiver type (for methods)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub receiver: Option<String>,
+    /// Declared without a body (C/C++ prototypes)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub declaration: bool,
+    /// File holding the definition of a declaration, when one was found
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defined_in: Option<String>,
     /// Dependencies used by this function
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub uses: Vec<String>,
@@ -83,6 +124,13 @@ pub struct FuncSpec {
     /// Link to related tests
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tests: Option<TestLink>,
+    /// Export status: "named" or "default" (for languages with explicit exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+    /// Visibility where the language does not make declarations public by default:
+    /// the modifier as written (`pub(crate)`, `protected`) or the default (`private`, `package`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
 }
 
 /// Behavioral specification extracted from documentation.
@@ -126,6 +174,13 @@ pub struct ConstSpec {
     /// Documentation comment
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub doc: Option<String>,
+    /// Export status: "named" or "default" (for languages with explicit exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+    /// Visibility where the language does not make declarations public by default:
+    /// the modifier as written (`pub(crate)`, `protected`) or the default (`private`, `package`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
 }
 
 /// Represents a variable definition.
@@ -139,6 +194,26 @@ pub struct VarSpec {
     /// Documentation comment
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub doc: Option<String>,
+    /// Export status: "named" or "default" (for languages with explicit exports)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export: Option<String>,
+    /// Visibility where the language does not make declarations public by default:
+    /// the modifier as written (`pub(crate)`, `protected`) or the default (`private`, `package`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<String>,
+}
+
+/// Represents a name exported from a module.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ExportSpec {
+    /// Exported name ("default" for default exports, "*" for unresolved star re-exports)
+    pub name: String,
+    /// Local or original name, when it differs from the exported name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local: Option<String>,
+    /// Module specifier this name is re-exported from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
 }
 
 /// Represents an error definition.
@@ -166,6 +241,24 @@ pub struct ExtractedSpec {
     pub extracted_at: Option<String>,
     /// All extracted file specifications
     pub files: Vec<FileSpec>,
+    /// Git ref the spec is partial to: only files changed since it are listed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Files deleted since `since`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deleted: Vec<String>,
+    /// Files renamed since `since`; their specs are listed under the new path
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub renamed: Vec<RenameSpec>,
+}
+
+/// A file moved to another path.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct RenameSpec {
+    /// Path before the rename
+    pub from: String,
+    /// Path after the rename
+    pub to: String,
 }
 
 /// Index file containing references to all spec files in a project.
@@ -200,5 +293,5 @@ pub struct IndexStats {
     pub total_types: usize,
     pub total_functions: usize,
     pub total_methods: usize,
-    pub by_language: std::collections::HashMap<String, usize>,
+    pub by_language: BTreeMap<String, usize>,
 }
@@ -0,0 +1,222 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// How much of a file is searched for a generated-code marker.
+const GENERATED_MARKER_WINDOW: usize = 1024;
+
+/// Dependency and cache directories left out by default, ignore files or not.
+/// Build output is left to `.gitignore`: a `build/` directory may well hold source.
+pub const DEFAULT_EXCLUDES: &[&str] = &["node_modules", "vendor", "__pycache__"];
+
+/// Which files of a tree to extract.
+#[derive(Debug, Clone)]
+pub struct WalkOptions {
+    /// Globs a file must match (any of them) to be extracted; empty means all
+    pub include: Vec<String>,
+    /// Globs of files and directories to leave out
+    pub exclude: Vec<String>,
+    /// Directories left out wherever they appear; `DEFAULT_EXCLUDES` unless overridden
+    pub default_excludes: Vec<String>,
+    /// Leave out files marked as generated (`Code generated ... DO NOT EDIT`, `@generated`)
+    pub skip_generated: bool,
+}
+
+impl Default for WalkOptions {
+    fn default() -> Self {
+        Self {
+            include: Vec::new(),
+            exclude: Vec::new(),
+            default_excludes: DEFAULT_EXCLUDES.iter().map(|dir| dir.to_string()).collect(),
+            skip_generated: false,
+        }
+    }
+}
+
+/// Lists the source files of a tree.
+///
+/// `.gitignore`, `.ignore` and git's global and repository excludes are honored
+/// (also outside of a git checkout), and hidden files are skipped. Globs are
+/// matched against paths relative to the root; a glob without a `/` matches
+/// at any depth, like a `.gitignore` line.
+pub struct SourceWalker {
+    root: PathBuf,
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+    skip_generated: bool,
+}
+
+impl SourceWalker {
+    pub fn new(root: &Path, options: &WalkOptions) -> Result<Self> {
+        let include = if options.include.is_empty() {
+            None
+        } else {
+            Some(glob_set(&options.include)?)
+        };
+        let mut exclude = options.exclude.clone();
+        exclude.extend(options.default_excludes.iter().cloned());
+        Ok(Self {
+            root: root.to_path_buf(),
+            include,
+            exclude: glob_set(&exclude)?,
+            skip_generated: options.skip_generated,
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// All files to extract, sorted by path.
+    pub fn files(&self) -> Vec<PathBuf> {
+        let exclude = self.exclude.clone();
+        let root = self.root.clone();
+        let mut files: Vec<PathBuf> = WalkBuilder::new(&self.root)
+            .follow_links(true)
+            .require_git(false)
+            .filter_entry(move |entry| {
+                let relative = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+                relative.as_os_str().is_empty() || !exclude.is_match(relative)
+            })
+            .build()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(|entry| entry.into_path())
+            .filter(|path| self.is_included(path))
+            .collect();
+        files.sort();
+        files
+    }
+
+    /// Whether a file under the root passes the include, exclude and
+    /// generated-code filters. Ignore files are only consulted by `files`.
+    pub fn is_included(&self, path: &Path) -> bool {
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        if self.exclude.is_match(relative)
+            || relative.ancestors().skip(1).any(|dir| self.exclude.is_match(dir))
+        {
+            return false;
+        }
+        if let Some(include) = &self.include {
+            if !include.is_match(relative) {
+                return false;
+            }
+        }
+        !(self.skip_generated && is_generated_file(path))
+    }
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+        let glob = |p: &str| {
+            Glob::new(p).with_context(|| format!("Invalid glob pattern: {}", pattern))
+        };
+        if pattern.contains('/') {
+            builder.add(glob(pattern.trim_start_matches('/'))?);
+        } else {
+            builder.add(glob(pattern)?);
+            builder.add(glob(&format!("**/{}", pattern))?);
+        }
+    }
+    builder.build().context("Failed to build glob set")
+}
+
+fn is_generated_file(path: &Path) -> bool {
+    let mut head = Vec::with_capacity(GENERATED_MARKER_WINDOW);
+    let read = File::open(path)
+        .and_then(|file| file.take(GENERATED_MARKER_WINDOW as u64).read_to_end(&mut head));
+    read.is_ok() && is_generated(&String::from_utf8_lossy(&head))
+}
+
+/// Whether the start of `source` carries a generated-code marker: Go's
+/// `// Code generated ... DO NOT EDIT.` line or an `@generated` tag.
+pub fn is_generated(source: &str) -> bool {
+    let head = match source.char_indices().nth(GENERATED_MARKER_WINDOW) {
+        Some((end, _)) => &source[..end],
+        None => source,
+    };
+    head.lines().any(|line| {
+        (line.contains("Code generated") && line.contains("DO NOT EDIT"))
+            || line.contains("@generated")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn relative(root: &Path, files: Vec<PathBuf>) -> Vec<String> {
+        files
+            .iter()
+            .map(|f| f.strip_prefix(root).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect()
+    }
+
+    #[test]
+    fn test_honors_ignore_files_and_globs() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, ".gitignore", "dist/\n*.log\n");
+        write(root, "build/build.go", "package build\n");
+        write(root, "dist/bundle.js", "");
+        write(root, "app.log", "");
+        write(root, "cmd/main.go", "package main\n");
+        write(root, "cmd/main_test.go", "package main\n");
+        write(root, "vendor/lib/lib.go", "package lib\n");
+        write(root, ".hidden/secret.go", "package hidden\n");
+
+        write(root, "web/node_modules/react/index.js", "");
+
+        // Dependency directories are left out even when not ignored, build output is not
+        let walker = SourceWalker::new(root, &WalkOptions::default()).unwrap();
+        assert_eq!(
+            relative(root, walker.files()),
+            vec!["build/build.go", "cmd/main.go", "cmd/main_test.go"]
+        );
+
+        let options = WalkOptions {
+            include: vec!["*.go".to_string()],
+            exclude: vec!["*_test.go".to_string()],
+            default_excludes: vec!["node_modules".to_string()],
+            skip_generated: false,
+        };
+        let walker = SourceWalker::new(root, &options).unwrap();
+        assert_eq!(
+            relative(root, walker.files()),
+            vec!["build/build.go", "cmd/main.go", "vendor/lib/lib.go"]
+        );
+    }
+
+    #[test]
+    fn test_skips_generated_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(root, "api.pb.go", "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n");
+        write(root, "schema.ts", "/** @generated */\nexport type Id = string;\n");
+        write(root, "users.go", "package users\n");
+
+        let options = WalkOptions { skip_generated: true, ..Default::default() };
+        let walker = SourceWalker::new(root, &options).unwrap();
+        assert_eq!(relative(root, walker.files()), vec!["users.go"]);
+        assert!(!is_generated("// This code is not generated\n"));
+    }
+
+    #[test]
+    fn test_invalid_glob() {
+        let options = WalkOptions { exclude: vec!["src/[".to_string()], ..Default::default() };
+        let err = SourceWalker::new(Path::new("."), &options).err().unwrap();
+        assert_eq!(err.to_string(), "Invalid glob pattern: src/[");
+    }
+}
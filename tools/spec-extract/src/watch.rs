@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::cache::SpecCache;
+use crate::plugins::PluginRegistry;
+use crate::spec::{FileSpec, FuncSpec};
+
+/// The specs of a tree kept in step with its files, for `spec-extract watch`.
+///
+/// Each `update` re-extracts only the files it is told were touched, plus
+/// those that newly appeared, and reports how their specs changed. Specs are
+/// kept as extracted; `specs` runs the cross-file `link` pass on a copy.
+pub struct WatchSession<'a> {
+    registry: &'a PluginRegistry,
+    cache: Option<&'a SpecCache>,
+    files: Vec<PathBuf>,
+    specs: HashMap<PathBuf, FileSpec>,
+}
+
+/// How a file's spec changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaKind {
+    Added,
+    Modified,
+    Deleted,
+}
+
+/// What changed in a file's spec: declarations added, removed or changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDelta {
+    pub file: String,
+    pub kind: DeltaKind,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl<'a> WatchSession<'a> {
+    pub fn new(registry: &'a PluginRegistry, cache: Option<&'a SpecCache>) -> Self {
+        Self { registry, cache, files: Vec::new(), specs: HashMap::new() }
+    }
+
+    /// Bring the specs in line with `files`, the source files now in the tree
+    /// in output order, re-extracting those in `touched` and any new ones.
+    /// Returns the deltas of the files whose spec changed, in output order.
+    pub fn update(&mut self, files: Vec<PathBuf>, touched: &HashSet<PathBuf>) -> Vec<FileDelta> {
+        let stale: Vec<&PathBuf> = files
+            .iter()
+            .filter(|path| touched.contains(*path) || !self.specs.contains_key(*path))
+            .collect();
+        let results: Vec<Result<Option<FileSpec>>> =
+            stale.par_iter().map(|path| self.extract(path)).collect();
+
+        let mut deltas = Vec::new();
+        let mut fresh = HashMap::new();
+        for (path, result) in stale.into_iter().zip(results) {
+            match result {
+                Ok(Some(spec)) => {
+                    fresh.insert(path.clone(), spec);
+                }
+                Ok(None) => {}
+                // A file caught mid-save is extracted again on its next event
+                Err(e) => eprintln!("Error extracting {}: {:#}", path.display(), e),
+            }
+        }
+        let present: HashSet<&PathBuf> = files.iter().collect();
+        for path in &files {
+            if let Some(spec) = fresh.remove(path) {
+                if let Some(delta) = diff_specs(self.specs.get(path), Some(&spec)) {
+                    deltas.push(delta);
+                }
+                self.specs.insert(path.clone(), spec);
+            }
+        }
+        for path in &self.files {
+            if !present.contains(path) {
+                if let Some(spec) = self.specs.remove(path) {
+                    deltas.extend(diff_specs(Some(&spec), None));
+                }
+            }
+        }
+        self.files = files;
+        deltas
+    }
+
+    /// The specs of all files in output order, linked if `link` is set.
+    pub fn specs(&self, link: bool) -> Result<Vec<FileSpec>> {
+        let mut specs: Vec<FileSpec> =
+            self.files.iter().filter_map(|path| self.specs.get(path).cloned()).collect();
+        if link {
+            self.registry.link(&mut specs)?;
+        }
+        Ok(specs)
+    }
+
+    fn extract(&self, path: &PathBuf) -> Result<Option<FileSpec>> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read source file: {}", path.display()))?;
+        match self.cache {
+            Some(cache) => cache.get_or_extract(&source, path, self.registry),
+            None => crate::output::extract_spec(&source, path, self.registry),
+        }
+    }
+}
+
+/// What changed between two specs of a file; `None` when nothing did.
+pub fn diff_specs(old: Option<&FileSpec>, new: Option<&FileSpec>) -> Option<FileDelta> {
+    let (file, kind) = match (old, new) {
+        (None, Some(new)) => (&new.file, DeltaKind::Added),
+        (Some(old), None) => (&old.file, DeltaKind::Deleted),
+        (Some(_), Some(new)) => (&new.file, DeltaKind::Modified),
+        (None, None) => return None,
+    };
+    let before = old.map(declarations).unwrap_or_default();
+    let after = new.map(declarations).unwrap_or_default();
+
+    let mut delta = FileDelta {
+        file: file.clone(),
+        kind,
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (label, value) in &after {
+        match before.get(label) {
+            None => delta.added.push(label.clone()),
+            Some(previous) if previous != value => delta.changed.push(label.clone()),
+            Some(_) => {}
+        }
+    }
+    delta.removed = before.keys().filter(|label| !after.contains_key(*label)).cloned().collect();
+
+    let unchanged = delta.added.is_empty() && delta.removed.is_empty() && delta.changed.is_empty();
+    if kind == DeltaKind::Modified && unchanged && spec_value(old) == spec_value(new) {
+        return None;
+    }
+    Some(delta)
+}
+
+/// `+ api/users.go: +Delete() ~User struct`, `~ ...` or `- ...`.
+impl fmt::Display for FileDelta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self.kind {
+            DeltaKind::Added => '+',
+            DeltaKind::Modified => '~',
+            DeltaKind::Deleted => '-',
+        };
+        write!(f, "{} {}", mark, self.file)?;
+        if self.kind == DeltaKind::Deleted {
+            return Ok(());
+        }
+        let items: Vec<String> = (self.added.iter().map(|label| format!("+{}", label)))
+            .chain(self.removed.iter().map(|label| format!("-{}", label)))
+            .chain(self.changed.iter().map(|label| format!("~{}", label)))
+            .collect();
+        if !items.is_empty() {
+            write!(f, ": {}", items.join(" "))?;
+        }
+        Ok(())
+    }
+}
+
+/// The declarations of a spec by label (`User struct`, `Store.Get()`,
+/// `MaxSize`); overloads share a label.
+fn declarations(spec: &FileSpec) -> BTreeMap<String, Vec<serde_json::Value>> {
+    let mut items: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+    let mut add =
+        |label: String, value: serde_json::Value| items.entry(label).or_default().push(value);
+    for t in &spec.types {
+        add(t.name.clone(), serde_json::to_value(t).unwrap_or_default());
+    }
+    for f in spec.functions.iter().chain(&spec.methods) {
+        add(function_label(f), serde_json::to_value(f).unwrap_or_default());
+    }
+    for c in &spec.constants {
+        add(c.name.clone(), serde_json::to_value(c).unwrap_or_default());
+    }
+    for v in &spec.variables {
+        add(v.name.clone(), serde_json::to_value(v).unwrap_or_default());
+    }
+    for e in &spec.errors {
+        add(e.name.clone(), serde_json::to_value(e).unwrap_or_default());
+    }
+    items
+}
+
+fn spec_value(spec: Option<&FileSpec>) -> serde_json::Value {
+    serde_json::to_value(spec).unwrap_or_default()
+}
+
+/// `Get()`, or `Store.Get()` for a method.
+fn function_label(func: &FuncSpec) -> String {
+    // The name is the word right before the parameter list, generics aside
+    let signature = func.signature.as_str();
+    let head = signature
+        .match_indices('(')
+        .map(|(i, _)| &signature[..i])
+        .find(|head| head.ends_with(|c: char| c.is_alphanumeric() || c == '_' || c == '>'))
+        .unwrap_or(signature);
+    let mut depth = 0;
+    let head: String = head
+        .chars()
+        .filter(|&c| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => return depth == 0,
+            }
+            false
+        })
+        .collect();
+    let name = head.split_whitespace().last().unwrap_or_default().trim_start_matches(['*', '&']);
+
+    // Receivers are a type (`Store`) or, for Go, a parameter (`(s *Store)`)
+    match &func.receiver {
+        Some(receiver) => {
+            let receiver = receiver.trim_matches(['(', ')']);
+            let receiver = receiver.split_whitespace().last().unwrap_or(receiver);
+            format!("{}.{}()", receiver.trim_start_matches(['*', '&']), name)
+        }
+        None => format!("{}()", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn spec(registry: &PluginRegistry, source: &str, path: &str) -> FileSpec {
+        registry.extract(source, Path::new(path)).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_diff_specs() {
+        let registry = PluginRegistry::new();
+        let old = spec(
+            &registry,
+            "package a\n\ntype User struct{}\n\nfunc (u *User) Name() string { return \"\" }\n\
+             func Legacy() {}\nfunc Keep() {}\n",
+            "a.go",
+        );
+        let new = spec(
+            &registry,
+            "package a\n\ntype User struct{ ID int }\n\n\
+             func (u *User) Name() string { return \"\" }\nfunc Delete(id int) {}\n\
+             func Keep() {}\n",
+            "a.go",
+        );
+        let delta = diff_specs(Some(&old), Some(&new)).unwrap();
+        assert_eq!(delta.added, vec!["Delete()"]);
+        assert_eq!(delta.removed, vec!["Legacy()"]);
+        assert_eq!(delta.changed, vec!["User struct"]);
+        assert_eq!(delta.to_string(), "~ a.go: +Delete() -Legacy() ~User struct");
+        assert!(declarations(&old).contains_key("User.Name()"));
+        assert_eq!(diff_specs(Some(&old), Some(&old)), None);
+        assert_eq!(diff_specs(Some(&old), None).unwrap().to_string(), "- a.go");
+
+        let func = FuncSpec {
+            signature: "pub fn get<T: Into<String>>(&self, t: T)".to_string(),
+            receiver: Some("Store".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(function_label(&func), "Store.get()");
+    }
+
+    #[test]
+    fn test_session_updates_touched_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let registry = PluginRegistry::new();
+        let mut session = WatchSession::new(&registry, None);
+        let a = dir.path().join("a.py");
+        let b = dir.path().join("b.py");
+        fs::write(&a, "def load(): pass\n").unwrap();
+        fs::write(&b, "def save(): pass\n").unwrap();
+
+        let deltas = session.update(vec![a.clone(), b.clone()], &HashSet::new());
+        assert_eq!(deltas.len(), 2);
+        assert!(deltas.iter().all(|delta| delta.kind == DeltaKind::Added));
+
+        // Untouched files are not read again
+        fs::write(&a, "def load(): pass\ndef reload(): pass\n").unwrap();
+        fs::write(&b, "def save(): pass\ndef flush(): pass\n").unwrap();
+        let deltas = session.update(vec![a.clone(), b.clone()], &HashSet::from([b.clone()]));
+        assert_eq!(deltas.len(), 1);
+        assert_eq!(deltas[0].added, vec!["flush()"]);
+
+        let deltas = session.update(vec![b.clone()], &HashSet::new());
+        assert_eq!(deltas[0].kind, DeltaKind::Deleted);
+        let specs = session.specs(true).unwrap();
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].functions.len(), 2);
+    }
+}